@@ -0,0 +1,526 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use lru::LruCache;
+
+use crate::{
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::ExportRepository, get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository, health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository, reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+};
+
+const DEFAULT_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone)]
+enum CacheEntry {
+    Found(StoredUrl),
+    Missing { until: Instant },
+}
+
+#[derive(Debug, Clone)]
+pub struct CachingRepository<R> {
+    inner: R,
+    cache: Arc<Mutex<LruCache<String, CacheEntry>>>,
+    negative_ttl: Option<Duration>,
+}
+
+impl<R> CachingRepository<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            cache: Arc::new(Mutex::new(LruCache::new(capacity(DEFAULT_CAPACITY)))),
+            negative_ttl: None,
+        }
+    }
+
+    pub fn with_capacity(mut self, size: usize) -> Self {
+        self.cache = Arc::new(Mutex::new(LruCache::new(capacity(size))));
+        self
+    }
+
+    pub fn with_negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.negative_ttl = Some(negative_ttl);
+        self
+    }
+
+    fn cached(&self, id: &str) -> Option<Result<StoredUrl, AppError>> {
+        let mut cache = self.cache.lock().unwrap();
+        match cache.get(id)? {
+            CacheEntry::Found(record) => Some(Ok(record.clone())),
+            CacheEntry::Missing { until } if Instant::now() < *until => {
+                Some(Err(AppError::NotFound))
+            }
+            CacheEntry::Missing { .. } => {
+                cache.pop(id);
+                None
+            }
+        }
+    }
+
+    fn put(&self, id: &str, entry: CacheEntry) {
+        self.cache.lock().unwrap().put(id.to_owned(), entry);
+    }
+
+    fn invalidate(&self, id: &str) {
+        self.cache.lock().unwrap().pop(id);
+    }
+}
+
+fn capacity(capacity: usize) -> NonZeroUsize {
+    NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN)
+}
+
+#[async_trait]
+impl<R> CreateShortUrlRepository for CachingRepository<R>
+where
+    R: CreateShortUrlRepository + Send + Sync,
+{
+    async fn save(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        let key = id.to_string();
+        self.inner
+            .save(
+                full_url,
+                id,
+                created_at,
+                expires_at,
+                password_hash,
+                metadata,
+            )
+            .await?;
+        self.invalidate(&key);
+
+        Ok(())
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        let key = id.to_string();
+        let saved = self
+            .inner
+            .save_if_absent(
+                full_url,
+                id,
+                created_at,
+                expires_at,
+                password_hash,
+                metadata,
+            )
+            .await?;
+        if saved {
+            self.invalidate(&key);
+        }
+
+        Ok(saved)
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        self.inner.exists(id).await
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        self.inner.find_by_url(url).await
+    }
+}
+
+#[async_trait]
+impl<R> DeleteShortUrlRepository for CachingRepository<R>
+where
+    R: DeleteShortUrlRepository + Send + Sync,
+{
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        let result = self.inner.delete(id).await;
+        self.invalidate(id);
+
+        result
+    }
+}
+
+#[async_trait]
+impl<R> PurgeExpiredRepository for CachingRepository<R>
+where
+    R: PurgeExpiredRepository + Send + Sync,
+{
+    // The purged ids are not reported back, so drop the whole cache rather
+    // than keep serving links that no longer exist.
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let purged = self.inner.purge_expired(now).await?;
+        if purged > 0 {
+            self.cache.lock().unwrap().clear();
+        }
+
+        Ok(purged)
+    }
+}
+
+#[async_trait]
+impl<R> AccessLogRepository for CachingRepository<R>
+where
+    R: AccessLogRepository + Send + Sync,
+{
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        self.inner.record(id, at, user_agent, referer).await
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        self.inner.recent(id, limit).await
+    }
+}
+
+#[async_trait]
+impl<R> RecordClickRepository for CachingRepository<R>
+where
+    R: RecordClickRepository + Send + Sync,
+{
+    // Resolving a link counts a click, so dropping the entry here would
+    // empty the cache for every hot link. Keep the cached count current.
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        let result = self.inner.increment(id).await;
+        let mut cache = self.cache.lock().unwrap();
+        match (&result, cache.peek_mut(id)) {
+            (Ok(clicks), Some(CacheEntry::Found(record))) => record.clicks = *clicks,
+            (Err(_), Some(_)) => {
+                cache.pop(id);
+            }
+            _ => {}
+        }
+
+        result
+    }
+}
+
+#[async_trait]
+impl<R> SetEnabledRepository for CachingRepository<R>
+where
+    R: SetEnabledRepository + Send + Sync,
+{
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        let result = self.inner.set_enabled(id, enabled).await;
+        self.invalidate(id);
+
+        result
+    }
+}
+
+#[async_trait]
+impl<R> UpdateShortUrlRepository for CachingRepository<R>
+where
+    R: UpdateShortUrlRepository + Send + Sync,
+{
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        let result = self.inner.update(id, full_url).await;
+        self.invalidate(id);
+
+        result
+    }
+}
+
+#[async_trait]
+impl<R> PatchShortUrlRepository for CachingRepository<R>
+where
+    R: PatchShortUrlRepository + Send + Sync,
+{
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        let result = self.inner.patch(id, patch).await;
+        self.invalidate(id);
+
+        result
+    }
+}
+
+impl<R> ExportRepository for CachingRepository<R>
+where
+    R: ExportRepository,
+{
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        self.inner.export()
+    }
+}
+
+impl<R> GetFullUrlRepository for CachingRepository<R>
+where
+    R: GetFullUrlRepository + Sync,
+{
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        if let Some(result) = self.cached(id) {
+            return result;
+        }
+
+        match self.inner.get_record(id).await {
+            Ok(record) => {
+                self.put(id, CacheEntry::Found(record.clone()));
+                Ok(record)
+            }
+            Err(AppError::NotFound) => {
+                if let Some(negative_ttl) = self.negative_ttl {
+                    let until = Instant::now() + negative_ttl;
+                    self.put(id, CacheEntry::Missing { until });
+                }
+                Err(AppError::NotFound)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<R> GetStatsRepository for CachingRepository<R>
+where
+    R: GetStatsRepository + Sync,
+{
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        self.inner.clicks(id).await
+    }
+}
+
+impl<R> HealthCheckRepository for CachingRepository<R>
+where
+    R: HealthCheckRepository + Sync,
+{
+    async fn ping(&self) -> Result<(), AppError> {
+        self.inner.ping().await
+    }
+}
+
+impl<R> ListUrlsRepository for CachingRepository<R>
+where
+    R: ListUrlsRepository + Sync,
+{
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        self.inner.list(offset, limit).await
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        self.inner.count().await
+    }
+}
+
+impl<R> ReverseLookupRepository for CachingRepository<R>
+where
+    R: ReverseLookupRepository + Sync,
+{
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        self.inner.ids_for_url(url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository, app::query::get_full_url::GetFullUrlQuery,
+    };
+
+    use super::*;
+
+    struct CountingRepository {
+        calls: Arc<AtomicU32>,
+        url: Option<String>,
+    }
+
+    impl CountingRepository {
+        fn new(url: Option<&str>) -> Self {
+            Self {
+                calls: Arc::new(AtomicU32::new(0)),
+                url: url.map(str::to_owned),
+            }
+        }
+    }
+
+    impl GetFullUrlRepository for CountingRepository {
+        async fn get_record(&self, _id: &str) -> Result<StoredUrl, AppError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.url
+                .clone()
+                .map(StoredUrl::new)
+                .ok_or(AppError::NotFound)
+        }
+    }
+
+    #[tokio::test]
+    async fn second_get_is_served_from_cache() {
+        // Given
+        let sut = CachingRepository::new(CountingRepository::new(Some("https://www.google.com/")));
+
+        // When
+        let first = sut.get("123").await;
+        let second = sut.get("123").await;
+
+        // Then
+        assert_eq!(first, Ok("https://www.google.com/".to_owned()));
+        assert_eq!(second, first);
+        assert_eq!(sut.inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used() {
+        // Given
+        let sut = CachingRepository::new(CountingRepository::new(Some("https://www.google.com/")))
+            .with_capacity(1);
+
+        // When
+        sut.get("123").await.unwrap();
+        sut.get("456").await.unwrap();
+        sut.get("123").await.unwrap();
+
+        // Then
+        assert_eq!(sut.inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn not_found_is_not_cached_by_default() {
+        // Given
+        let sut = CachingRepository::new(CountingRepository::new(None));
+
+        // When
+        sut.get("123").await.unwrap_err();
+        let result = sut.get("123").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+        assert_eq!(sut.inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn not_found_is_negatively_cached_until_ttl() {
+        // Given
+        let sut = CachingRepository::new(CountingRepository::new(None))
+            .with_negative_ttl(Duration::from_millis(20));
+
+        // When
+        sut.get("123").await.unwrap_err();
+        let cached = sut.get("123").await;
+        let calls_within_ttl = sut.inner.calls.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        sut.get("123").await.unwrap_err();
+
+        // Then
+        assert_eq!(cached, Err(AppError::NotFound));
+        assert_eq!(calls_within_ttl, 1);
+        assert_eq!(sut.inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn writes_refresh_cached_entries() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let sut = CachingRepository::new(InMemoryRepository::new(store.clone()))
+            .with_negative_ttl(Duration::from_secs(60));
+        sut.get("123").await.unwrap_err();
+
+        // When
+        sut.save(
+            ValidUrl::parse("https://www.google.com/").unwrap(),
+            ShortId::parse("123").unwrap(),
+            Utc::now(),
+            None,
+            None,
+            LinkMetadata::default(),
+        )
+        .await
+        .unwrap();
+        let after_save = sut.get("123").await;
+        sut.update("123", "https://www.rust-lang.org/".to_owned())
+            .await
+            .unwrap();
+        let after_update = sut.get("123").await;
+        sut.delete("123").await.unwrap();
+        let after_delete = sut.get("123").await;
+
+        // Then
+        assert_eq!(after_save, Ok("https://www.google.com/".to_owned()));
+        assert_eq!(after_update, Ok("https://www.rust-lang.org/".to_owned()));
+        assert_eq!(after_delete, Err(AppError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn resolves_are_served_from_cache() {
+        // Given
+        let repository = CountingRepository::new(Some("https://www.google.com/"));
+        let calls = repository.calls.clone();
+        let sut = GetFullUrlQuery::new(CachingRepository::new(repository));
+
+        // When
+        let first = sut.execute("123").await;
+        let second = sut.execute("123").await;
+
+        // Then
+        assert_eq!(first, Ok("https://www.google.com/".to_owned()));
+        assert_eq!(second, first);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn cached_records_follow_clicks_and_state_changes() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let sut = CachingRepository::new(InMemoryRepository::new(store));
+        sut.get_record("123").await.unwrap();
+
+        // When
+        sut.increment("123").await.unwrap();
+        let clicked = sut.get_record("123").await.unwrap();
+        sut.set_enabled("123", false).await.unwrap();
+        let disabled = sut.get_record("123").await.unwrap();
+        sut.patch(
+            "123",
+            UrlPatch {
+                full_url: Some("https://www.rust-lang.org/".to_owned()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let patched = sut.get_record("123").await.unwrap();
+
+        // Then
+        assert_eq!(clicked.clicks, 1);
+        assert!(disabled.disabled);
+        assert_eq!(patched.full_url, "https://www.rust-lang.org/");
+    }
+}
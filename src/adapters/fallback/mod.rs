@@ -0,0 +1,201 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+
+use crate::{
+    app::{
+        command::record_access::{AccessEntry, AccessLogRepository},
+        domain::StoredUrl,
+        query::{
+            export::ExportRepository, get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository, health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository, reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+};
+
+/// Resolves ids from `primary` and only asks `secondary` when `primary`
+/// reports [`AppError::NotFound`]; any other error is returned as is.
+/// Everything besides resolution is served by `primary` alone, so layers
+/// such as cache → local → upstream nest as
+/// `FallbackRepository<Cache, FallbackRepository<Local, Upstream>>`.
+#[derive(Clone)]
+pub struct FallbackRepository<A, B> {
+    primary: A,
+    secondary: B,
+}
+
+impl<A, B> FallbackRepository<A, B> {
+    pub fn new(primary: A, secondary: B) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+impl<A, B> GetFullUrlRepository for FallbackRepository<A, B>
+where
+    A: GetFullUrlRepository,
+    B: GetFullUrlRepository,
+{
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        match self.primary.get_record(id).await {
+            Err(AppError::NotFound) => self.secondary.get_record(id).await,
+            result => result,
+        }
+    }
+}
+
+#[async_trait]
+impl<A, B> AccessLogRepository for FallbackRepository<A, B>
+where
+    A: AccessLogRepository + Send + Sync,
+    B: Send + Sync,
+{
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        self.primary.record(id, at, user_agent, referer).await
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        self.primary.recent(id, limit).await
+    }
+}
+
+impl<A, B> ExportRepository for FallbackRepository<A, B>
+where
+    A: ExportRepository,
+{
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        self.primary.export()
+    }
+}
+
+impl<A, B> GetStatsRepository for FallbackRepository<A, B>
+where
+    A: GetStatsRepository + Sync,
+    B: Sync,
+{
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        self.primary.clicks(id).await
+    }
+}
+
+impl<A, B> HealthCheckRepository for FallbackRepository<A, B>
+where
+    A: HealthCheckRepository + Sync,
+    B: Sync,
+{
+    async fn ping(&self) -> Result<(), AppError> {
+        self.primary.ping().await
+    }
+}
+
+impl<A, B> ListUrlsRepository for FallbackRepository<A, B>
+where
+    A: ListUrlsRepository + Sync,
+    B: Sync,
+{
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        self.primary.list(offset, limit).await
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        self.primary.count().await
+    }
+}
+
+impl<A, B> ReverseLookupRepository for FallbackRepository<A, B>
+where
+    A: ReverseLookupRepository + Sync,
+    B: Sync,
+{
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        self.primary.ids_for_url(url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct FakeRepository {
+        result: fn() -> Result<StoredUrl, AppError>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl FakeRepository {
+        fn new(result: fn() -> Result<StoredUrl, AppError>) -> Self {
+            Self {
+                result,
+                calls: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+    }
+
+    impl GetFullUrlRepository for FakeRepository {
+        async fn get_record(&self, _id: &str) -> Result<StoredUrl, AppError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            (self.result)()
+        }
+    }
+
+    #[tokio::test]
+    async fn primary_hit_is_returned() {
+        // Given
+        let secondary = FakeRepository::new(|| Ok(StoredUrl::new("https://secondary.example/")));
+        let sut = FallbackRepository::new(
+            FakeRepository::new(|| Ok(StoredUrl::new("https://primary.example/"))),
+            secondary.clone(),
+        );
+
+        // When
+        let result = sut.get("123").await;
+
+        // Then
+        assert_eq!(result, Ok("https://primary.example/".to_owned()));
+        assert_eq!(secondary.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn primary_not_found_falls_through() {
+        // Given
+        let sut = FallbackRepository::new(
+            FakeRepository::new(|| Err(AppError::NotFound)),
+            FakeRepository::new(|| Ok(StoredUrl::new("https://secondary.example/"))),
+        );
+
+        // When
+        let result = sut.get("123").await;
+
+        // Then
+        assert_eq!(result, Ok("https://secondary.example/".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn primary_transient_error_does_not_fall_through() {
+        // Given
+        let secondary = FakeRepository::new(|| Ok(StoredUrl::new("https://secondary.example/")));
+        let sut = FallbackRepository::new(
+            FakeRepository::new(|| Err(AppError::Transient("timeout".to_owned()))),
+            secondary.clone(),
+        );
+
+        // When
+        let result = sut.get("123").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::Transient("timeout".to_owned())));
+        assert_eq!(secondary.calls.load(Ordering::SeqCst), 0);
+    }
+}
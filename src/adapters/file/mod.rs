@@ -0,0 +1,518 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::{DashMap, mapref::entry::Entry};
+use futures::{Stream, StreamExt, future, stream};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::{
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository, MAX_ACCESS_LOG_LENGTH},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::ExportRepository, get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository, health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository, reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileEntry {
+    full_url: String,
+    #[serde(default)]
+    clicks: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    accesses: Vec<AccessEntry>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    disabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl FileEntry {
+    fn new(
+        full_url: ValidUrl,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Self {
+        Self {
+            full_url: full_url.into_string(),
+            clicks: 0,
+            created_at: Some(created_at),
+            expires_at,
+            accesses: Vec::new(),
+            disabled: false,
+            password_hash,
+            title: metadata.title,
+            description: metadata.description,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileRepository {
+    path: Arc<PathBuf>,
+    entries: Arc<DashMap<String, FileEntry>>,
+    flush_lock: Arc<Mutex<()>>,
+}
+
+impl FileRepository {
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = load(&path);
+
+        Self {
+            path: Arc::new(path),
+            entries: Arc::new(entries),
+            flush_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    async fn flush(&self) -> Result<(), AppError> {
+        let _guard = self.flush_lock.lock().await;
+        let snapshot: BTreeMap<String, FileEntry> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        let json = serde_json::to_vec_pretty(&snapshot)
+            .map_err(|e| AppError::RepositoryError(e.to_string()))?;
+
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, json)
+            .await
+            .map_err(to_app_error)?;
+        tokio::fs::rename(&tmp_path, self.path.as_ref())
+            .await
+            .map_err(to_app_error)
+    }
+}
+
+fn load(path: &Path) -> DashMap<String, FileEntry> {
+    let contents = match std::fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return DashMap::new(),
+        Err(e) => {
+            tracing::warn!("failed to read {}: {}, starting empty", path.display(), e);
+            return DashMap::new();
+        }
+    };
+
+    match serde_json::from_slice::<BTreeMap<String, FileEntry>>(&contents) {
+        Ok(entries) => entries.into_iter().collect(),
+        Err(e) => {
+            tracing::warn!("failed to parse {}: {}, starting empty", path.display(), e);
+            DashMap::new()
+        }
+    }
+}
+
+/// A write the disk may yet accept, one that was interrupted, timed out or
+/// hit a busy file, is worth retrying; anything else is final.
+fn to_app_error(e: std::io::Error) -> AppError {
+    match e.kind() {
+        std::io::ErrorKind::Interrupted
+        | std::io::ErrorKind::WouldBlock
+        | std::io::ErrorKind::TimedOut
+        | std::io::ErrorKind::ResourceBusy => AppError::Transient(e.to_string()),
+        _ => AppError::RepositoryError(e.to_string()),
+    }
+}
+
+#[async_trait]
+impl CreateShortUrlRepository for FileRepository {
+    async fn save(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        self.entries.insert(
+            id.into_string(),
+            FileEntry::new(full_url, created_at, expires_at, password_hash, metadata),
+        );
+
+        self.flush().await
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        match self.entries.entry(id.into_string()) {
+            Entry::Occupied(_) => return Ok(false),
+            Entry::Vacant(entry) => {
+                entry.insert(FileEntry::new(
+                    full_url,
+                    created_at,
+                    expires_at,
+                    password_hash,
+                    metadata,
+                ));
+            }
+        }
+
+        self.flush().await.map(|()| true)
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        Ok(self.entries.contains_key(id))
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        Ok(self
+            .entries
+            .iter()
+            .find(|entry| {
+                entry.full_url == url && entry.expires_at.is_none() && entry.password_hash.is_none()
+            })
+            .map(|entry| entry.key().clone()))
+    }
+}
+
+#[async_trait]
+impl DeleteShortUrlRepository for FileRepository {
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        self.entries.remove(id).ok_or(AppError::NotFound)?;
+
+        self.flush().await
+    }
+}
+
+#[async_trait]
+impl PurgeExpiredRepository for FileRepository {
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let before = self.entries.len();
+        self.entries
+            .retain(|_, entry| entry.expires_at.is_none_or(|expires_at| now < expires_at));
+        let purged = before - self.entries.len();
+        if purged > 0 {
+            self.flush().await?;
+        }
+
+        Ok(purged)
+    }
+}
+
+#[async_trait]
+impl AccessLogRepository for FileRepository {
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        let mut entry = self.entries.get_mut(id).ok_or(AppError::NotFound)?;
+        entry.accesses.push(AccessEntry {
+            at,
+            user_agent,
+            referer,
+        });
+        let excess = entry.accesses.len().saturating_sub(MAX_ACCESS_LOG_LENGTH);
+        entry.accesses.drain(..excess);
+        drop(entry);
+
+        self.flush().await
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        self.entries
+            .get(id)
+            .map(|entry| entry.accesses.iter().rev().take(limit).cloned().collect())
+            .ok_or(AppError::NotFound)
+    }
+}
+
+#[async_trait]
+impl RecordClickRepository for FileRepository {
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        let clicks = {
+            let mut entry = self.entries.get_mut(id).ok_or(AppError::NotFound)?;
+            entry.clicks += 1;
+            entry.clicks
+        };
+        self.flush().await?;
+
+        Ok(clicks)
+    }
+}
+
+#[async_trait]
+impl SetEnabledRepository for FileRepository {
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        self.entries.get_mut(id).ok_or(AppError::NotFound)?.disabled = !enabled;
+
+        self.flush().await
+    }
+}
+
+#[async_trait]
+impl UpdateShortUrlRepository for FileRepository {
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        self.entries.get_mut(id).ok_or(AppError::NotFound)?.full_url = full_url;
+
+        self.flush().await
+    }
+}
+
+#[async_trait]
+impl PatchShortUrlRepository for FileRepository {
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        let mut entry = self.entries.get_mut(id).ok_or(AppError::NotFound)?;
+        if let Some(full_url) = patch.full_url {
+            entry.full_url = full_url;
+        }
+        if let Some(disabled) = patch.disabled {
+            entry.disabled = disabled;
+        }
+        if let Some(expires_at) = patch.expires_at {
+            entry.expires_at = expires_at;
+        }
+
+        drop(entry);
+        self.flush().await
+    }
+}
+
+impl ExportRepository for FileRepository {
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        let entries = self.entries.clone();
+        let ids: Vec<String> = entries.iter().map(|entry| entry.key().clone()).collect();
+
+        stream::iter(ids).filter_map(move |id| {
+            let url = entries.get(&id).map(|entry| entry.full_url.clone());
+            future::ready(url.map(|url| Ok((id, url))))
+        })
+    }
+}
+
+impl GetFullUrlRepository for FileRepository {
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        self.entries
+            .get(id)
+            .map(|entry| StoredUrl {
+                full_url: entry.full_url.clone(),
+                clicks: entry.clicks,
+                created_at: entry.created_at,
+                expires_at: entry.expires_at,
+                disabled: entry.disabled,
+                password_hash: entry.password_hash.clone(),
+                metadata: LinkMetadata {
+                    title: entry.title.clone(),
+                    description: entry.description.clone(),
+                },
+            })
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl GetStatsRepository for FileRepository {
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        self.entries
+            .get(id)
+            .map(|entry| entry.clicks)
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl HealthCheckRepository for FileRepository {
+    async fn ping(&self) -> Result<(), AppError> {
+        Ok(())
+    }
+}
+
+impl ListUrlsRepository for FileRepository {
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        let mut items: Vec<(String, String)> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.full_url.clone()))
+            .collect();
+        items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(items.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        Ok(self.entries.len())
+    }
+}
+
+impl ReverseLookupRepository for FileRepository {
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .entries
+            .iter()
+            .filter(|entry| entry.full_url == url)
+            .map(|entry| entry.key().clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("url_shortener_{}.json", nanoid::nanoid!()))
+    }
+
+    #[tokio::test]
+    async fn data_survives_reload() {
+        // Given
+        let path = temp_path();
+        let expires_at = Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let repository = FileRepository::open(&path);
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        repository
+            .save(
+                ValidUrl::parse("https://www.rust-lang.org/").unwrap(),
+                ShortId::parse("456").unwrap(),
+                Utc::now(),
+                Some(expires_at),
+                None,
+                LinkMetadata {
+                    title: Some("Rust".to_owned()),
+                    description: Some("A language".to_owned()),
+                },
+            )
+            .await
+            .unwrap();
+        repository.increment("123").await.unwrap();
+        drop(repository);
+
+        // When
+        let reloaded = FileRepository::open(&path);
+
+        // Then
+        assert_eq!(
+            reloaded.get("123").await,
+            Ok("https://www.google.com/".to_owned())
+        );
+        assert_eq!(reloaded.clicks("123").await, Ok(1));
+        assert_eq!(
+            reloaded
+                .get_record("456")
+                .await
+                .map(|record| record.expires_at),
+            Ok(Some(expires_at))
+        );
+        assert_eq!(
+            reloaded.get_record("456").await.unwrap().metadata,
+            LinkMetadata {
+                title: Some("Rust".to_owned()),
+                description: Some("A language".to_owned()),
+            }
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn delete_survives_reload() {
+        // Given
+        let path = temp_path();
+        let repository = FileRepository::open(&path);
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        repository.delete("123").await.unwrap();
+        drop(repository);
+
+        // When
+        let reloaded = FileRepository::open(&path);
+
+        // Then
+        assert_eq!(reloaded.get("123").await, Err(AppError::NotFound));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn missing_file_starts_empty() {
+        // When
+        let repository = FileRepository::open(temp_path());
+
+        // Then
+        assert_eq!(repository.count().await, Ok(0));
+    }
+
+    #[tokio::test]
+    async fn corrupt_file_starts_empty() {
+        // Given
+        let path = temp_path();
+        std::fs::write(&path, "{ not json").unwrap();
+
+        // When
+        let repository = FileRepository::open(&path);
+
+        // Then
+        assert_eq!(repository.count().await, Ok(0));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn classifies_retryable_io_errors_as_transient() {
+        // When
+        let interrupted = to_app_error(std::io::ErrorKind::Interrupted.into());
+        let denied = to_app_error(std::io::ErrorKind::PermissionDenied.into());
+
+        // Then
+        assert!(matches!(interrupted, AppError::Transient(_)));
+        assert!(matches!(denied, AppError::RepositoryError(_)));
+    }
+}
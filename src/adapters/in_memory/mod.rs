@@ -1,41 +1,636 @@
-use std::sync::Arc;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
 
 use async_trait::async_trait;
-use dashmap::DashMap;
+use chrono::{DateTime, Utc};
+use dashmap::{DashMap, mapref::entry::Entry};
+use futures::{Stream, StreamExt, future, stream};
 
 use crate::{
     app::{
-        command::create_short_url::CreateShortUrlRepository,
-        query::get_full_url::GetFullUrlRepository,
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository, MAX_ACCESS_LOG_LENGTH},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::ExportRepository, get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository, health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository, reverse_lookup::ReverseLookupRepository,
+        },
     },
     error::AppError,
 };
 
 #[derive(Debug, Clone)]
 pub struct InMemoryRepository {
-    store: Arc<DashMap<String, String>>,
+    store: Arc<DashMap<String, StoredUrl>>,
+    accesses: Arc<DashMap<String, VecDeque<AccessEntry>>>,
+    max_entries: Option<usize>,
+    insertion_order: Arc<Mutex<VecDeque<String>>>,
 }
 
 impl InMemoryRepository {
-    pub fn new(store: Arc<DashMap<String, String>>) -> Self {
-        Self { store }
+    pub fn new(store: Arc<DashMap<String, StoredUrl>>) -> Self {
+        Self {
+            store,
+            accesses: Arc::new(DashMap::new()),
+            max_entries: None,
+            insertion_order: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Pre-sizes the maps and sets their shard count, which must be a power of two greater than one.
+    /// More shards reduce lock contention when many requests hit the store concurrently; a capacity
+    /// close to the expected number of urls avoids rehashing while the store grows.
+    pub fn with_capacity(capacity: usize, shards: usize) -> Self {
+        assert!(
+            shards > 1 && shards.is_power_of_two(),
+            "shard count must be a power of two greater than one"
+        );
+
+        Self {
+            store: Arc::new(DashMap::with_capacity_and_shard_amount(capacity, shards)),
+            accesses: Arc::new(DashMap::with_shard_amount(shards)),
+            max_entries: None,
+            insertion_order: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Caps the number of stored urls. Once the cap is exceeded, the oldest inserted entries are
+    /// evicted first (FIFO); urls already in the store are queued for eviction in arbitrary order.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        let existing = self.store.iter().map(|entry| entry.key().clone()).collect();
+        self.max_entries = Some(max_entries.max(1));
+        self.insertion_order = Arc::new(Mutex::new(existing));
+        self
+    }
+
+    fn insert(&self, id: String, record: StoredUrl) {
+        let Some(max_entries) = self.max_entries else {
+            self.store.insert(id, record);
+            return;
+        };
+
+        let mut insertion_order = self.insertion_order.lock().unwrap();
+        if self.store.insert(id.clone(), record).is_none() {
+            insertion_order.push_back(id);
+        }
+        self.evict(&mut insertion_order, max_entries);
+    }
+
+    fn insert_if_absent(&self, id: String, record: StoredUrl) -> bool {
+        let mut insertion_order = self
+            .max_entries
+            .map(|max_entries| (self.insertion_order.lock().unwrap(), max_entries));
+        match self.store.entry(id.clone()) {
+            Entry::Occupied(_) => return false,
+            Entry::Vacant(entry) => {
+                entry.insert(record);
+            }
+        }
+        if let Some((insertion_order, max_entries)) = insertion_order.as_mut() {
+            insertion_order.push_back(id);
+            self.evict(insertion_order, *max_entries);
+        }
+
+        true
+    }
+
+    fn evict(&self, insertion_order: &mut VecDeque<String>, max_entries: usize) {
+        while self.store.len() > max_entries
+            && let Some(oldest) = insertion_order.pop_front()
+        {
+            self.store.remove(&oldest);
+            self.accesses.remove(&oldest);
+        }
     }
 }
 
 #[async_trait]
 impl CreateShortUrlRepository for InMemoryRepository {
-    async fn save(&self, full_url: String, id: String) -> Result<(), AppError> {
-        self.store.insert(id, full_url);
+    async fn save(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        self.insert(
+            id.into_string(),
+            StoredUrl {
+                created_at: Some(created_at),
+                expires_at,
+                password_hash,
+                metadata,
+                ..StoredUrl::new(full_url.into_string())
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        Ok(self.insert_if_absent(
+            id.into_string(),
+            StoredUrl {
+                created_at: Some(created_at),
+                expires_at,
+                password_hash,
+                metadata,
+                ..StoredUrl::new(full_url.into_string())
+            },
+        ))
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        Ok(self.store.contains_key(id))
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        Ok(self
+            .store
+            .iter()
+            .find(|entry| {
+                entry.full_url == url && entry.expires_at.is_none() && entry.password_hash.is_none()
+            })
+            .map(|entry| entry.key().clone()))
+    }
+}
+
+#[async_trait]
+impl DeleteShortUrlRepository for InMemoryRepository {
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        self.accesses.remove(id);
+        if self.max_entries.is_some() {
+            self.insertion_order
+                .lock()
+                .unwrap()
+                .retain(|queued| queued != id);
+        }
+        self.store.remove(id).map(|_| ()).ok_or(AppError::NotFound)
+    }
+}
+
+#[async_trait]
+impl PurgeExpiredRepository for InMemoryRepository {
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let is_expired = |record: &StoredUrl| {
+            record
+                .expires_at
+                .is_some_and(|expires_at| now >= expires_at)
+        };
+        let expired: Vec<String> = self
+            .store
+            .iter()
+            .filter(|entry| is_expired(entry.value()))
+            .map(|entry| entry.key().clone())
+            .collect();
+        let purged: Vec<String> = expired
+            .into_iter()
+            .filter(|id| {
+                self.store
+                    .remove_if(id, |_, record| is_expired(record))
+                    .is_some()
+            })
+            .collect();
+        for id in &purged {
+            self.accesses.remove(id);
+        }
+        if self.max_entries.is_some() && !purged.is_empty() {
+            self.insertion_order
+                .lock()
+                .unwrap()
+                .retain(|queued| !purged.contains(queued));
+        }
+
+        Ok(purged.len())
+    }
+}
+
+#[async_trait]
+impl AccessLogRepository for InMemoryRepository {
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        if !self.store.contains_key(id) {
+            return Err(AppError::NotFound);
+        }
+
+        let mut accesses = self.accesses.entry(id.to_owned()).or_default();
+        accesses.push_back(AccessEntry {
+            at,
+            user_agent,
+            referer,
+        });
+        if accesses.len() > MAX_ACCESS_LOG_LENGTH {
+            accesses.pop_front();
+        }
+
+        Ok(())
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        if !self.store.contains_key(id) {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(self
+            .accesses
+            .get(id)
+            .map(|accesses| accesses.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl RecordClickRepository for InMemoryRepository {
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        let mut record = self.store.get_mut(id).ok_or(AppError::NotFound)?;
+        record.clicks += 1;
+
+        Ok(record.clicks)
+    }
+}
+
+#[async_trait]
+impl SetEnabledRepository for InMemoryRepository {
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        self.store.get_mut(id).ok_or(AppError::NotFound)?.disabled = !enabled;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UpdateShortUrlRepository for InMemoryRepository {
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        self.store.get_mut(id).ok_or(AppError::NotFound)?.full_url = full_url;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PatchShortUrlRepository for InMemoryRepository {
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        let mut record = self.store.get_mut(id).ok_or(AppError::NotFound)?;
+        if let Some(full_url) = patch.full_url {
+            record.full_url = full_url;
+        }
+        if let Some(disabled) = patch.disabled {
+            record.disabled = disabled;
+        }
+        if let Some(expires_at) = patch.expires_at {
+            record.expires_at = expires_at;
+        }
 
         Ok(())
     }
 }
 
+impl ExportRepository for InMemoryRepository {
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        let store = self.store.clone();
+        let ids: Vec<String> = store.iter().map(|entry| entry.key().clone()).collect();
+
+        stream::iter(ids).filter_map(move |id| {
+            let url = store.get(&id).map(|record| record.full_url.clone());
+            future::ready(url.map(|url| Ok((id, url))))
+        })
+    }
+}
+
 impl GetFullUrlRepository for InMemoryRepository {
-    async fn get(&self, id: &str) -> Result<String, AppError> {
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        self.store
+            .get(id)
+            .map(|record| record.clone())
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl GetStatsRepository for InMemoryRepository {
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
         self.store
             .get(id)
-            .map(|url| url.clone())
+            .map(|record| record.clicks)
             .ok_or(AppError::NotFound)
     }
 }
+
+impl HealthCheckRepository for InMemoryRepository {
+    async fn ping(&self) -> Result<(), AppError> {
+        Ok(())
+    }
+}
+
+impl ListUrlsRepository for InMemoryRepository {
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        let mut items: Vec<(String, String)> = self
+            .store
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.full_url.clone()))
+            .collect();
+        items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(items.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        Ok(self.store.len())
+    }
+}
+
+impl ReverseLookupRepository for InMemoryRepository {
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .store
+            .iter()
+            .filter(|entry| entry.full_url == url)
+            .map(|entry| entry.key().clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn behaves_the_same_regardless_of_shards() {
+        for shards in [2, 4, 64] {
+            // Given
+            let repository = InMemoryRepository::with_capacity(16, shards);
+
+            // When
+            repository
+                .save(
+                    ValidUrl::parse("https://www.google.com/").unwrap(),
+                    ShortId::parse("123").unwrap(),
+                    Utc::now(),
+                    None,
+                    None,
+                    LinkMetadata::default(),
+                )
+                .await
+                .unwrap();
+
+            // Then
+            assert_eq!(
+                repository.get("123").await,
+                Ok("https://www.google.com/".to_owned())
+            );
+            assert_eq!(repository.get("456").await, Err(AppError::NotFound));
+        }
+    }
+
+    #[tokio::test]
+    async fn get_record_returns_the_stored_fields() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let created_at = Utc::now();
+        let expires_at = created_at + chrono::Duration::hours(1);
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                created_at,
+                Some(expires_at),
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        repository
+            .save(
+                ValidUrl::parse("https://www.rust-lang.org/").unwrap(),
+                ShortId::parse("456").unwrap(),
+                created_at,
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+
+        // When
+        repository.increment("123").await.unwrap();
+        repository.increment("123").await.unwrap();
+        repository.set_enabled("123", false).await.unwrap();
+
+        // Then
+        assert_eq!(
+            repository.get_record("123").await,
+            Ok(StoredUrl {
+                full_url: "https://www.google.com/".to_owned(),
+                clicks: 2,
+                created_at: Some(created_at),
+                expires_at: Some(expires_at),
+                disabled: true,
+                password_hash: None,
+                metadata: LinkMetadata::default(),
+            })
+        );
+        assert_eq!(
+            repository.get_record("456").await,
+            Ok(StoredUrl {
+                created_at: Some(created_at),
+                ..StoredUrl::new("https://www.rust-lang.org/")
+            })
+        );
+        assert_eq!(repository.get_record("789").await, Err(AppError::NotFound));
+    }
+
+    async fn save(repository: &InMemoryRepository, id: &str) {
+        repository
+            .save(
+                ValidUrl::parse(&format!("https://{id}.example/")).unwrap(),
+                ShortId::parse(id).unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn evicts_oldest_entries_beyond_max_entries() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new())).with_max_entries(3);
+
+        // When
+        for id in ["a", "b", "c", "d", "e"] {
+            save(&repository, id).await;
+        }
+
+        // Then
+        assert_eq!(repository.count().await, Ok(3));
+        assert_eq!(repository.get("a").await, Err(AppError::NotFound));
+        assert_eq!(repository.get("b").await, Err(AppError::NotFound));
+        for id in ["c", "d", "e"] {
+            assert_eq!(
+                repository.get(id).await,
+                Ok(format!("https://{id}.example/"))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn deleted_entries_do_not_count_towards_max_entries() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new())).with_max_entries(2);
+        save(&repository, "a").await;
+        save(&repository, "b").await;
+        repository.delete("a").await.unwrap();
+
+        // When
+        save(&repository, "a").await;
+        save(&repository, "c").await;
+
+        // Then
+        assert_eq!(repository.get("b").await, Err(AppError::NotFound));
+        assert!(repository.get("a").await.is_ok());
+        assert!(repository.get("c").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn evicts_preexisting_entries_too() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        store.insert("old".to_owned(), StoredUrl::new("https://old.example/"));
+        let repository = InMemoryRepository::new(store).with_max_entries(1);
+
+        // When
+        save(&repository, "new").await;
+
+        // Then
+        assert_eq!(repository.get("old").await, Err(AppError::NotFound));
+        assert!(repository.get("new").await.is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "shard count must be a power of two")]
+    fn rejects_invalid_shard_count() {
+        // When
+        InMemoryRepository::with_capacity(16, 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_inserts_are_all_stored() {
+        // Given
+        let repository = InMemoryRepository::with_capacity(4096, 32);
+
+        // When
+        let tasks: Vec<_> = (0..8)
+            .map(|task| {
+                let repository = repository.clone();
+                tokio::spawn(async move {
+                    for i in 0..500 {
+                        repository
+                            .save(
+                                ValidUrl::parse(&format!("https://example.com/{task}/{i}"))
+                                    .unwrap(),
+                                ShortId::parse(&format!("id-{task}-{i}")).unwrap(),
+                                Utc::now(),
+                                None,
+                                None,
+                                LinkMetadata::default(),
+                            )
+                            .await
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // Then
+        assert_eq!(repository.count().await, Ok(4000));
+        assert_eq!(
+            repository.get("id-7-499").await,
+            Ok("https://example.com/7/499".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn save_if_absent_keeps_existing_entry() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new())).with_max_entries(10);
+        let save = |url: &str| {
+            repository.save_if_absent(
+                ValidUrl::parse(url).unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+        };
+
+        // When
+        let first = save("https://first.example/").await;
+        let second = save("https://second.example/").await;
+
+        // Then
+        assert_eq!(first, Ok(true));
+        assert_eq!(second, Ok(false));
+        assert_eq!(
+            repository.get("123").await,
+            Ok("https://first.example/".to_owned())
+        );
+        assert_eq!(repository.insertion_order.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn access_log_keeps_only_the_latest_entries() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://example.com/"));
+        let repository = InMemoryRepository::new(store);
+        let start = Utc::now();
+
+        // When
+        for i in 0..MAX_ACCESS_LOG_LENGTH + 5 {
+            let at = start + chrono::Duration::seconds(i as i64);
+            repository.record("123", at, None, None).await.unwrap();
+        }
+        let missing = repository.record("missing", start, None, None).await;
+
+        // Then
+        let accesses = repository.recent("123", usize::MAX).await.unwrap();
+        assert_eq!(accesses.len(), MAX_ACCESS_LOG_LENGTH);
+        assert_eq!(
+            accesses.last().map(|entry| entry.at),
+            Some(start + chrono::Duration::seconds(5))
+        );
+        assert_eq!(missing, Err(AppError::NotFound));
+        assert!(!repository.accesses.contains_key("missing"));
+    }
+}
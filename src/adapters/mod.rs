@@ -1 +1,18 @@
+pub mod cache;
+pub mod fallback;
+pub mod file;
 pub mod in_memory;
+#[cfg(feature = "mysql")]
+pub mod mysql;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "redis")]
+pub mod redis;
+pub mod replicating;
+pub mod retry;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+mod sqlx_error;
+pub mod upstream;
+pub mod webhook;
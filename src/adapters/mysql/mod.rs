@@ -0,0 +1,597 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{Stream, TryStreamExt, stream};
+use sqlx::MySqlPool;
+
+use crate::{
+    adapters::sqlx_error,
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::{EXPORT_BATCH_SIZE, ExportRepository},
+            get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository,
+            health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository,
+            reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+};
+
+#[derive(Debug, Clone)]
+pub struct MySqlRepository {
+    pool: MySqlPool,
+}
+
+/// Columns added to `urls` after its first release. `CREATE TABLE IF NOT
+/// EXISTS` leaves older tables without them, so `migrate` adds them.
+const ADDED_URL_COLUMNS: [(&str, &str); 2] = [("title", "TEXT"), ("description", "TEXT")];
+
+fn to_app_error(e: sqlx::Error) -> AppError {
+    // SQLSTATE of a deadlock (ER_LOCK_DEADLOCK).
+    sqlx_error::to_app_error(e, |code| code == "40001")
+}
+
+impl MySqlRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), AppError> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS urls (
+                id VARCHAR(64) PRIMARY KEY,
+                full_url TEXT NOT NULL,
+                clicks BIGINT NOT NULL DEFAULT 0,
+                created_at DATETIME(6),
+                expires_at DATETIME(6),
+                disabled BOOLEAN NOT NULL DEFAULT FALSE,
+                password_hash TEXT,
+                title TEXT,
+                description TEXT
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+        let columns: Vec<String> = sqlx::query_scalar(
+            "SELECT COLUMN_NAME FROM information_schema.COLUMNS
+             WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = 'urls'",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+        for (column, column_type) in ADDED_URL_COLUMNS {
+            if !columns.iter().any(|existing| existing == column) {
+                sqlx::query(&format!(
+                    "ALTER TABLE urls ADD COLUMN {column} {column_type}"
+                ))
+                .execute(&self.pool)
+                .await
+                .map_err(to_app_error)?;
+            }
+        }
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS accesses (
+                id VARCHAR(64) NOT NULL,
+                at DATETIME(6) NOT NULL,
+                user_agent TEXT,
+                referer TEXT,
+                INDEX (id)
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(())
+    }
+
+    async fn insert(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO urls (id, full_url, created_at, expires_at, password_hash, title, description) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id.into_string())
+        .bind(full_url.into_string())
+        .bind(created_at)
+        .bind(expires_at)
+        .bind(password_hash)
+        .bind(metadata.title)
+        .bind(metadata.description)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn is_duplicate_key(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .is_some_and(|e| e.is_unique_violation())
+}
+
+#[async_trait]
+impl CreateShortUrlRepository for MySqlRepository {
+    async fn save(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        self.insert(
+            full_url,
+            id,
+            created_at,
+            expires_at,
+            password_hash,
+            metadata,
+        )
+        .await
+        .map_err(|e| {
+            if is_duplicate_key(&e) {
+                AppError::AliasTaken
+            } else {
+                AppError::RepositoryError(e.to_string())
+            }
+        })
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        match self
+            .insert(
+                full_url,
+                id,
+                created_at,
+                expires_at,
+                password_hash,
+                metadata,
+            )
+            .await
+        {
+            Ok(()) => Ok(true),
+            Err(e) if is_duplicate_key(&e) => Ok(false),
+            Err(e) => Err(AppError::RepositoryError(e.to_string())),
+        }
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        sqlx::query("SELECT 1 FROM urls WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(|row| row.is_some())
+            .map_err(to_app_error)
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT id FROM urls WHERE full_url = ? AND expires_at IS NULL AND password_hash IS NULL LIMIT 1",
+        )
+        .bind(url)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(to_app_error)
+    }
+}
+
+#[async_trait]
+impl DeleteShortUrlRepository for MySqlRepository {
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM accesses WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+        let result = sqlx::query("DELETE FROM urls WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PurgeExpiredRepository for MySqlRepository {
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        sqlx::query("DELETE FROM accesses WHERE id IN (SELECT id FROM urls WHERE expires_at <= ?)")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+        let result = sqlx::query("DELETE FROM urls WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(result.rows_affected() as usize)
+    }
+}
+
+#[async_trait]
+impl AccessLogRepository for MySqlRepository {
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        sqlx::query("INSERT INTO accesses (id, at, user_agent, referer) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind(at)
+            .bind(user_agent)
+            .bind(referer)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(())
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        if !self.exists(id).await? {
+            return Err(AppError::NotFound);
+        }
+
+        let rows = sqlx::query_as::<_, (DateTime<Utc>, Option<String>, Option<String>)>(
+            "SELECT at, user_agent, referer FROM accesses WHERE id = ? ORDER BY at DESC LIMIT ?",
+        )
+        .bind(id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(at, user_agent, referer)| AccessEntry {
+                at,
+                user_agent,
+                referer,
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl RecordClickRepository for MySqlRepository {
+    // MySQL has no `UPDATE ... RETURNING`, so the increment and the read
+    // share a transaction to report the count this click produced.
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        let mut tx = self.pool.begin().await.map_err(to_app_error)?;
+        let result = sqlx::query("UPDATE urls SET clicks = clicks + 1 WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        let clicks = sqlx::query_scalar::<_, i64>("SELECT clicks FROM urls WHERE id = ?")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(to_app_error)?;
+        tx.commit().await.map_err(to_app_error)?;
+
+        Ok(clicks as u64)
+    }
+}
+
+#[async_trait]
+impl SetEnabledRepository for MySqlRepository {
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        let result = sqlx::query("UPDATE urls SET disabled = ? WHERE id = ?")
+            .bind(!enabled)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        // MySQL reports changed rows, not matched ones, so a no-op update
+        // reads as zero.
+        if result.rows_affected() == 0 && !self.exists(id).await? {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UpdateShortUrlRepository for MySqlRepository {
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        let result = sqlx::query("UPDATE urls SET full_url = ? WHERE id = ?")
+            .bind(full_url)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 && !self.exists(id).await? {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PatchShortUrlRepository for MySqlRepository {
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "UPDATE urls SET full_url = COALESCE(?, full_url), disabled = COALESCE(?, disabled), expires_at = CASE WHEN ? THEN ? ELSE expires_at END WHERE id = ?",
+        )
+        .bind(patch.full_url)
+        .bind(patch.disabled)
+        .bind(patch.expires_at.is_some())
+        .bind(patch.expires_at.flatten())
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 && !self.exists(id).await? {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+impl ExportRepository for MySqlRepository {
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        let pool = self.pool.clone();
+
+        stream::try_unfold(Some(String::new()), move |after| {
+            let pool = pool.clone();
+            async move {
+                let Some(after) = after else {
+                    return Ok(None);
+                };
+                let batch = sqlx::query_as::<_, (String, String)>(
+                    "SELECT id, full_url FROM urls WHERE id > ? ORDER BY id LIMIT ?",
+                )
+                .bind(after)
+                .bind(EXPORT_BATCH_SIZE as i64)
+                .fetch_all(&pool)
+                .await
+                .map_err(to_app_error)?;
+                let next = match batch.last() {
+                    Some((id, _)) if batch.len() == EXPORT_BATCH_SIZE => Some(id.clone()),
+                    _ => None,
+                };
+
+                Ok::<_, AppError>(Some((batch, next)))
+            }
+        })
+        .map_ok(|batch| stream::iter(batch.into_iter().map(Ok)))
+        .try_flatten()
+    }
+}
+
+impl GetFullUrlRepository for MySqlRepository {
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        sqlx::query_as::<
+            _,
+            (
+                String,
+                i64,
+                Option<DateTime<Utc>>,
+                Option<DateTime<Utc>>,
+                bool,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+            ),
+        >(
+            "SELECT full_url, clicks, created_at, expires_at, disabled, password_hash, title, description FROM urls WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(to_app_error)?
+        .map(|row| {
+            let (
+                full_url,
+                clicks,
+                created_at,
+                expires_at,
+                disabled,
+                password_hash,
+                title,
+                description,
+            ) = row;
+
+            StoredUrl {
+                full_url,
+                clicks: clicks as u64,
+                created_at,
+                expires_at,
+                disabled,
+                password_hash,
+                metadata: LinkMetadata { title, description },
+            }
+        })
+        .ok_or(AppError::NotFound)
+    }
+
+    async fn get(&self, id: &str) -> Result<String, AppError> {
+        sqlx::query_scalar::<_, String>("SELECT full_url FROM urls WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(to_app_error)?
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl GetStatsRepository for MySqlRepository {
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        sqlx::query_scalar::<_, i64>("SELECT clicks FROM urls WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(to_app_error)?
+            .map(|clicks| clicks as u64)
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl HealthCheckRepository for MySqlRepository {
+    async fn ping(&self) -> Result<(), AppError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(())
+    }
+}
+
+impl ListUrlsRepository for MySqlRepository {
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        sqlx::query_as::<_, (String, String)>(
+            "SELECT id, full_url FROM urls ORDER BY id LIMIT ? OFFSET ?",
+        )
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(to_app_error)
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM urls")
+            .fetch_one(&self.pool)
+            .await
+            .map(|count| count as usize)
+            .map_err(to_app_error)
+    }
+}
+
+impl ReverseLookupRepository for MySqlRepository {
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        sqlx::query_scalar::<_, String>("SELECT id FROM urls WHERE full_url = ? ORDER BY id")
+            .bind(url)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(to_app_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get_repository() -> MySqlRepository {
+        let database_url = std::env::var("MYSQL_URL").expect("MYSQL_URL must be set");
+        let pool = MySqlPool::connect(&database_url).await.unwrap();
+        let repository = MySqlRepository::new(pool);
+        repository.migrate().await.unwrap();
+
+        repository
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a MySQL server at MYSQL_URL"]
+    async fn save_and_get() {
+        // Given
+        let repository = get_repository().await;
+        let id = nanoid::nanoid!(12);
+
+        // When
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse(&id).unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        let result = repository.get(&id).await;
+
+        // Then
+        assert_eq!(result, Ok("https://www.google.com/".to_owned()));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a MySQL server at MYSQL_URL"]
+    async fn save_duplicate_id_is_alias_taken() {
+        // Given
+        let repository = get_repository().await;
+        let id = nanoid::nanoid!(12);
+        let save = || {
+            repository.save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse(&id).unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+        };
+        save().await.unwrap();
+
+        // When
+        let result = save().await;
+
+        // Then
+        assert_eq!(result, Err(AppError::AliasTaken));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a MySQL server at MYSQL_URL"]
+    async fn get_missing_id() {
+        // Given
+        let repository = get_repository().await;
+
+        // When
+        let result = repository.get("missing-id").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+    }
+}
@@ -0,0 +1,539 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{Stream, TryStreamExt, stream};
+use sqlx::PgPool;
+
+use crate::{
+    adapters::sqlx_error,
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::{EXPORT_BATCH_SIZE, ExportRepository},
+            get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository,
+            health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository,
+            reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+};
+
+#[derive(Debug, Clone)]
+pub struct PostgresRepository {
+    pool: PgPool,
+}
+
+fn to_app_error(e: sqlx::Error) -> AppError {
+    // serialization_failure and deadlock_detected.
+    sqlx_error::to_app_error(e, |code| matches!(code, "40001" | "40P01"))
+}
+
+impl PostgresRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), AppError> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS urls (
+                id TEXT PRIMARY KEY,
+                full_url TEXT NOT NULL,
+                clicks BIGINT NOT NULL DEFAULT 0,
+                created_at TIMESTAMPTZ,
+                expires_at TIMESTAMPTZ,
+                disabled BOOLEAN NOT NULL DEFAULT FALSE,
+                password_hash TEXT,
+                title TEXT,
+                description TEXT
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+        // Tables created before link metadata existed lack these columns.
+        sqlx::query(
+            "ALTER TABLE urls
+                ADD COLUMN IF NOT EXISTS title TEXT,
+                ADD COLUMN IF NOT EXISTS description TEXT",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS accesses (
+                id TEXT NOT NULL,
+                at TIMESTAMPTZ NOT NULL,
+                user_agent TEXT,
+                referer TEXT
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CreateShortUrlRepository for PostgresRepository {
+    async fn save(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO urls (id, full_url, created_at, expires_at, password_hash, title, description) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(id.into_string())
+        .bind(full_url.into_string())
+        .bind(created_at)
+        .bind(expires_at)
+        .bind(password_hash)
+        .bind(metadata.title)
+        .bind(metadata.description)
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(())
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO urls (id, full_url, created_at, expires_at, password_hash, title, description) VALUES ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(id.into_string())
+        .bind(full_url.into_string())
+        .bind(created_at)
+        .bind(expires_at)
+        .bind(password_hash)
+        .bind(metadata.title)
+        .bind(metadata.description)
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM urls WHERE id = $1)")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(to_app_error)
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT id FROM urls WHERE full_url = $1 AND expires_at IS NULL AND password_hash IS NULL LIMIT 1",
+        )
+        .bind(url)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(to_app_error)
+    }
+}
+
+#[async_trait]
+impl DeleteShortUrlRepository for PostgresRepository {
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM accesses WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+        let result = sqlx::query("DELETE FROM urls WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PurgeExpiredRepository for PostgresRepository {
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        sqlx::query(
+            "DELETE FROM accesses WHERE id IN (SELECT id FROM urls WHERE expires_at <= $1)",
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+        let result = sqlx::query("DELETE FROM urls WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(result.rows_affected() as usize)
+    }
+}
+
+#[async_trait]
+impl AccessLogRepository for PostgresRepository {
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        sqlx::query("INSERT INTO accesses (id, at, user_agent, referer) VALUES ($1, $2, $3, $4)")
+            .bind(id)
+            .bind(at)
+            .bind(user_agent)
+            .bind(referer)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(())
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        if !self.exists(id).await? {
+            return Err(AppError::NotFound);
+        }
+
+        let rows = sqlx::query_as::<_, (DateTime<Utc>, Option<String>, Option<String>)>(
+            "SELECT at, user_agent, referer FROM accesses WHERE id = $1 ORDER BY at DESC LIMIT $2",
+        )
+        .bind(id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(at, user_agent, referer)| AccessEntry {
+                at,
+                user_agent,
+                referer,
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl RecordClickRepository for PostgresRepository {
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        sqlx::query_scalar::<_, i64>(
+            "UPDATE urls SET clicks = clicks + 1 WHERE id = $1 RETURNING clicks",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(to_app_error)?
+        .map(|clicks| clicks as u64)
+        .ok_or(AppError::NotFound)
+    }
+}
+
+#[async_trait]
+impl SetEnabledRepository for PostgresRepository {
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        let result = sqlx::query("UPDATE urls SET disabled = $1 WHERE id = $2")
+            .bind(!enabled)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UpdateShortUrlRepository for PostgresRepository {
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        let result = sqlx::query("UPDATE urls SET full_url = $1 WHERE id = $2")
+            .bind(full_url)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PatchShortUrlRepository for PostgresRepository {
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "UPDATE urls SET full_url = COALESCE($1, full_url), disabled = COALESCE($2, disabled), expires_at = CASE WHEN $3 THEN $4 ELSE expires_at END WHERE id = $5",
+        )
+        .bind(patch.full_url)
+        .bind(patch.disabled)
+        .bind(patch.expires_at.is_some())
+        .bind(patch.expires_at.flatten())
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+impl ExportRepository for PostgresRepository {
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        let pool = self.pool.clone();
+
+        stream::try_unfold(Some(String::new()), move |after| {
+            let pool = pool.clone();
+            async move {
+                let Some(after) = after else {
+                    return Ok(None);
+                };
+                let batch = sqlx::query_as::<_, (String, String)>(
+                    "SELECT id, full_url FROM urls WHERE id > $1 ORDER BY id LIMIT $2",
+                )
+                .bind(after)
+                .bind(EXPORT_BATCH_SIZE as i64)
+                .fetch_all(&pool)
+                .await
+                .map_err(to_app_error)?;
+                let next = match batch.last() {
+                    Some((id, _)) if batch.len() == EXPORT_BATCH_SIZE => Some(id.clone()),
+                    _ => None,
+                };
+
+                Ok::<_, AppError>(Some((batch, next)))
+            }
+        })
+        .map_ok(|batch| stream::iter(batch.into_iter().map(Ok)))
+        .try_flatten()
+    }
+}
+
+impl GetFullUrlRepository for PostgresRepository {
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        sqlx::query_as::<
+            _,
+            (
+                String,
+                i64,
+                Option<DateTime<Utc>>,
+                Option<DateTime<Utc>>,
+                bool,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+            ),
+        >(
+            "SELECT full_url, clicks, created_at, expires_at, disabled, password_hash, title, description FROM urls WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(to_app_error)?
+        .map(|row| {
+            let (
+                full_url,
+                clicks,
+                created_at,
+                expires_at,
+                disabled,
+                password_hash,
+                title,
+                description,
+            ) = row;
+
+            StoredUrl {
+                full_url,
+                clicks: clicks as u64,
+                created_at,
+                expires_at,
+                disabled,
+                password_hash,
+                metadata: LinkMetadata { title, description },
+            }
+        })
+        .ok_or(AppError::NotFound)
+    }
+
+    async fn get(&self, id: &str) -> Result<String, AppError> {
+        sqlx::query_scalar::<_, String>("SELECT full_url FROM urls WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(to_app_error)?
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl GetStatsRepository for PostgresRepository {
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        sqlx::query_scalar::<_, i64>("SELECT clicks FROM urls WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(to_app_error)?
+            .map(|clicks| clicks as u64)
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl HealthCheckRepository for PostgresRepository {
+    async fn ping(&self) -> Result<(), AppError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "postgres-integration"))]
+mod tests {
+    use super::*;
+
+    async fn get_repository() -> PostgresRepository {
+        let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        let pool = PgPool::connect(&database_url).await.unwrap();
+        let repository = PostgresRepository::new(pool);
+        repository.migrate().await.unwrap();
+
+        repository
+    }
+
+    #[tokio::test]
+    async fn save_and_get() {
+        // Given
+        let repository = get_repository().await;
+        let id = nanoid::nanoid!(12);
+
+        // When
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse(&id).unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        let result = repository.get(&id).await;
+
+        // Then
+        assert_eq!(result, Ok("https://www.google.com/".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn get_missing_id() {
+        // Given
+        let repository = get_repository().await;
+
+        // When
+        let result = repository.get("missing-id").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn increment_and_get_clicks() {
+        // Given
+        let repository = get_repository().await;
+        let id = nanoid::nanoid!(12);
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse(&id).unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+
+        // When
+        repository.increment(&id).await.unwrap();
+        repository.increment(&id).await.unwrap();
+        let result = repository.clicks(&id).await;
+
+        // Then
+        assert_eq!(result, Ok(2));
+    }
+}
+
+impl ListUrlsRepository for PostgresRepository {
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        sqlx::query_as::<_, (String, String)>(
+            "SELECT id, full_url FROM urls ORDER BY id LIMIT $1 OFFSET $2",
+        )
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(to_app_error)
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM urls")
+            .fetch_one(&self.pool)
+            .await
+            .map(|count| count as usize)
+            .map_err(to_app_error)
+    }
+}
+
+impl ReverseLookupRepository for PostgresRepository {
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        sqlx::query_scalar::<_, String>("SELECT id FROM urls WHERE full_url = $1 ORDER BY id")
+            .bind(url)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(to_app_error)
+    }
+}
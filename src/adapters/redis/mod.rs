@@ -0,0 +1,795 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use futures::{Stream, StreamExt, TryStreamExt, stream};
+use redis::{AsyncCommands, aio::ConnectionManager};
+
+use crate::{
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository, MAX_ACCESS_LOG_LENGTH},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::{EXPORT_BATCH_SIZE, ExportRepository},
+            get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository,
+            health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository,
+            reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+};
+
+fn url_key(id: &str) -> String {
+    format!("url:{}", id)
+}
+
+fn clicks_key(id: &str) -> String {
+    format!("clicks:{}", id)
+}
+
+fn accesses_key(id: &str) -> String {
+    format!("accesses:{}", id)
+}
+
+fn disabled_key(id: &str) -> String {
+    format!("disabled:{}", id)
+}
+
+fn password_key(id: &str) -> String {
+    format!("password:{}", id)
+}
+
+fn title_key(id: &str) -> String {
+    format!("title:{}", id)
+}
+
+fn description_key(id: &str) -> String {
+    format!("description:{}", id)
+}
+
+fn created_key(id: &str) -> String {
+    format!("created:{}", id)
+}
+
+fn id_by_url_key(url: &str) -> String {
+    format!("id_by_url:{}", url)
+}
+
+fn ttl_seconds(expires_at: DateTime<Utc>) -> u64 {
+    (expires_at - Utc::now()).num_seconds().max(1) as u64
+}
+
+/// Connection failures, timeouts and a server that is still loading or
+/// failing over are worth retrying; anything else is final.
+fn to_app_error(e: redis::RedisError) -> AppError {
+    let transient = e.is_io_error()
+        || e.is_connection_dropped()
+        || e.is_connection_refusal()
+        || e.is_timeout()
+        || matches!(
+            e.kind(),
+            redis::ErrorKind::BusyLoadingError
+                | redis::ErrorKind::TryAgain
+                | redis::ErrorKind::ClusterDown
+                | redis::ErrorKind::MasterDown
+        );
+
+    match transient {
+        true => AppError::Transient(e.to_string()),
+        false => AppError::RepositoryError(e.to_string()),
+    }
+}
+
+/// Remaining lifetime of `url:{id}` in milliseconds, or `None` for a link
+/// that never expires. Keys written after `save` copy it, so they go away
+/// together with the link instead of outliving it.
+async fn remaining_ttl(
+    connection: &mut ConnectionManager,
+    id: &str,
+) -> Result<Option<i64>, AppError> {
+    let ttl: i64 = connection.pttl(url_key(id)).await.map_err(to_app_error)?;
+    match ttl {
+        -2 => Err(AppError::NotFound),
+        -1 => Ok(None),
+        ttl => Ok(Some(ttl)),
+    }
+}
+
+#[derive(Clone)]
+pub struct RedisRepository {
+    connection: ConnectionManager,
+}
+
+impl RedisRepository {
+    pub fn new(connection: ConnectionManager) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl CreateShortUrlRepository for RedisRepository {
+    async fn save(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        let full_url = full_url.into_string();
+        let created_at = created_at.to_rfc3339();
+        let mut connection = self.connection.clone();
+        let mut pipe = redis::pipe();
+        match expires_at {
+            Some(expires_at) => {
+                let ttl = ttl_seconds(expires_at);
+                pipe.set_ex(url_key(&id), &full_url, ttl);
+                pipe.set_ex(created_key(&id), &created_at, ttl);
+                if let Some(password_hash) = &password_hash {
+                    pipe.set_ex(password_key(&id), password_hash, ttl);
+                }
+                if let Some(title) = &metadata.title {
+                    pipe.set_ex(title_key(&id), title, ttl);
+                }
+                if let Some(description) = &metadata.description {
+                    pipe.set_ex(description_key(&id), description, ttl);
+                }
+            }
+            None => {
+                pipe.set(url_key(&id), &full_url);
+                pipe.set(created_key(&id), &created_at);
+                match &password_hash {
+                    Some(password_hash) => pipe.set(password_key(&id), password_hash),
+                    None => pipe.set(id_by_url_key(&full_url), id.as_str()),
+                };
+                if let Some(title) = &metadata.title {
+                    pipe.set(title_key(&id), title);
+                }
+                if let Some(description) = &metadata.description {
+                    pipe.set(description_key(&id), description);
+                }
+            }
+        }
+
+        pipe.query_async::<()>(&mut connection)
+            .await
+            .map_err(to_app_error)
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        let mut connection = self.connection.clone();
+        let mut claim = redis::cmd("SET");
+        claim.arg(url_key(&id)).arg(full_url.as_str()).arg("NX");
+        if let Some(expires_at) = expires_at {
+            claim.arg("EX").arg(ttl_seconds(expires_at));
+        }
+        let claimed: Option<String> = claim
+            .query_async(&mut connection)
+            .await
+            .map_err(to_app_error)?;
+        if claimed.is_none() {
+            return Ok(false);
+        }
+
+        // A link that expired on its own leaves its counters behind; the new
+        // owner of the id must not inherit them.
+        connection
+            .del::<_, ()>(&[
+                clicks_key(&id),
+                accesses_key(&id),
+                disabled_key(&id),
+                password_key(&id),
+                created_key(&id),
+                title_key(&id),
+                description_key(&id),
+            ])
+            .await
+            .map_err(to_app_error)?;
+
+        self.save(
+            full_url,
+            id,
+            created_at,
+            expires_at,
+            password_hash,
+            metadata,
+        )
+        .await
+        .map(|()| true)
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        let mut connection = self.connection.clone();
+        connection.exists(url_key(id)).await.map_err(to_app_error)
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        let mut connection = self.connection.clone();
+        let Some(id) = connection
+            .get::<_, Option<String>>(id_by_url_key(url))
+            .await
+            .map_err(to_app_error)?
+        else {
+            return Ok(None);
+        };
+
+        let stored_url: Option<String> =
+            connection.get(url_key(&id)).await.map_err(to_app_error)?;
+        let ttl: i64 = connection.pttl(url_key(&id)).await.map_err(to_app_error)?;
+
+        Ok((stored_url.as_deref() == Some(url) && ttl == -1).then_some(id))
+    }
+}
+
+#[async_trait]
+impl DeleteShortUrlRepository for RedisRepository {
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        let mut connection = self.connection.clone();
+        let deleted: u64 = connection
+            .del(&[
+                url_key(id),
+                clicks_key(id),
+                accesses_key(id),
+                disabled_key(id),
+                password_key(id),
+                created_key(id),
+                title_key(id),
+                description_key(id),
+            ])
+            .await
+            .map_err(to_app_error)?;
+
+        if deleted == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PurgeExpiredRepository for RedisRepository {
+    // Keys of expiring links are written with a TTL, so Redis drops them
+    // itself and there is never anything left to purge.
+    async fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AppError> {
+        Ok(0)
+    }
+}
+
+#[async_trait]
+impl AccessLogRepository for RedisRepository {
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        let entry = serde_json::to_string(&AccessEntry {
+            at,
+            user_agent,
+            referer,
+        })
+        .map_err(|e| AppError::RepositoryError(e.to_string()))?;
+        let mut connection = self.connection.clone();
+        let ttl = remaining_ttl(&mut connection, id).await?;
+
+        let mut pipe = redis::pipe();
+        pipe.lpush(accesses_key(id), entry).ignore();
+        pipe.ltrim(accesses_key(id), 0, MAX_ACCESS_LOG_LENGTH as isize - 1)
+            .ignore();
+        if let Some(ttl) = ttl {
+            pipe.pexpire(accesses_key(id), ttl).ignore();
+        }
+        pipe.query_async::<()>(&mut connection)
+            .await
+            .map_err(to_app_error)
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut connection = self.connection.clone();
+        let (exists, entries): (bool, Vec<String>) = redis::pipe()
+            .exists(url_key(id))
+            .lrange(accesses_key(id), 0, limit as isize - 1)
+            .query_async(&mut connection)
+            .await
+            .map_err(to_app_error)?;
+
+        if !exists {
+            return Err(AppError::NotFound);
+        }
+
+        entries
+            .iter()
+            .map(|entry| {
+                serde_json::from_str(entry).map_err(|e| AppError::RepositoryError(e.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl RecordClickRepository for RedisRepository {
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        let mut connection = self.connection.clone();
+        let ttl = remaining_ttl(&mut connection, id).await?;
+
+        let mut pipe = redis::pipe();
+        pipe.incr(clicks_key(id), 1);
+        if let Some(ttl) = ttl {
+            pipe.pexpire(clicks_key(id), ttl).ignore();
+        }
+        let (clicks,): (u64,) = pipe
+            .query_async(&mut connection)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(clicks)
+    }
+}
+
+#[async_trait]
+impl SetEnabledRepository for RedisRepository {
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        let mut connection = self.connection.clone();
+        let ttl = remaining_ttl(&mut connection, id).await?;
+
+        match (enabled, ttl) {
+            (true, _) => connection.del::<_, ()>(disabled_key(id)).await,
+            (false, Some(ttl)) => {
+                connection
+                    .pset_ex::<_, _, ()>(disabled_key(id), true, ttl as u64)
+                    .await
+            }
+            (false, None) => connection.set::<_, _, ()>(disabled_key(id), true).await,
+        }
+        .map_err(to_app_error)
+    }
+}
+
+#[async_trait]
+impl UpdateShortUrlRepository for RedisRepository {
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        let mut connection = self.connection.clone();
+        let updated: Option<String> = redis::cmd("SET")
+            .arg(url_key(id))
+            .arg(full_url)
+            .arg("XX")
+            .arg("KEEPTTL")
+            .query_async(&mut connection)
+            .await
+            .map_err(to_app_error)?;
+
+        updated.map(|_| ()).ok_or(AppError::NotFound)
+    }
+}
+
+#[async_trait]
+impl PatchShortUrlRepository for RedisRepository {
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        let mut connection = self.connection.clone();
+        let ttl = remaining_ttl(&mut connection, id).await?;
+
+        let mut pipe = redis::pipe();
+        if let Some(full_url) = &patch.full_url {
+            pipe.cmd("SET")
+                .arg(url_key(id))
+                .arg(full_url)
+                .arg("XX")
+                .arg("KEEPTTL")
+                .ignore();
+        }
+        match patch.disabled {
+            Some(true) => {
+                pipe.set(disabled_key(id), true).ignore();
+                if let Some(ttl) = ttl {
+                    pipe.pexpire(disabled_key(id), ttl).ignore();
+                }
+            }
+            Some(false) => {
+                pipe.del(disabled_key(id)).ignore();
+            }
+            None => {}
+        }
+        let expiring = [
+            url_key(id),
+            clicks_key(id),
+            accesses_key(id),
+            created_key(id),
+            password_key(id),
+            disabled_key(id),
+            title_key(id),
+            description_key(id),
+        ];
+        match patch.expires_at {
+            Some(Some(expires_at)) => {
+                let ttl = ttl_seconds(expires_at) as i64;
+                for key in expiring {
+                    pipe.expire(key, ttl).ignore();
+                }
+            }
+            Some(None) => {
+                for key in expiring {
+                    pipe.persist(key).ignore();
+                }
+            }
+            None => {}
+        }
+
+        pipe.query_async::<()>(&mut connection)
+            .await
+            .map_err(to_app_error)
+    }
+}
+
+impl ExportRepository for RedisRepository {
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        let repository = self.clone();
+
+        stream::once(async move {
+            let ids = repository.ids().await?;
+            let batches: Vec<Vec<String>> = ids
+                .chunks(EXPORT_BATCH_SIZE)
+                .map(<[String]>::to_vec)
+                .collect();
+
+            Ok::<_, AppError>(stream::iter(batches).then(move |batch| {
+                let mut connection = repository.connection.clone();
+                async move {
+                    let urls: Vec<Option<String>> = connection
+                        .mget(batch.iter().map(|id| url_key(id)).collect::<Vec<_>>())
+                        .await
+                        .map_err(to_app_error)?;
+
+                    Ok::<_, AppError>(stream::iter(
+                        batch
+                            .into_iter()
+                            .zip(urls)
+                            .filter_map(|(id, url)| url.map(|url| Ok((id, url)))),
+                    ))
+                }
+            }))
+        })
+        .try_flatten()
+        .try_flatten()
+    }
+}
+
+/// Values read back by `get_record`, in pipeline order.
+type RecordFields = (
+    Option<String>,
+    Option<u64>,
+    i64,
+    bool,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+impl GetFullUrlRepository for RedisRepository {
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        let mut connection = self.connection.clone();
+        let fields: RecordFields = redis::pipe()
+            .get(url_key(id))
+            .get(clicks_key(id))
+            .pttl(url_key(id))
+            .exists(disabled_key(id))
+            .get(password_key(id))
+            .get(created_key(id))
+            .get(title_key(id))
+            .get(description_key(id))
+            .query_async(&mut connection)
+            .await
+            .map_err(to_app_error)?;
+        let (full_url, clicks, ttl, disabled, password_hash, created_at, title, description) =
+            fields;
+
+        Ok(StoredUrl {
+            full_url: full_url.ok_or(AppError::NotFound)?,
+            clicks: clicks.unwrap_or(0),
+            created_at: created_at
+                .and_then(|created_at| DateTime::parse_from_rfc3339(&created_at).ok())
+                .map(|created_at| created_at.with_timezone(&Utc)),
+            expires_at: (ttl >= 0).then(|| Utc::now() + Duration::milliseconds(ttl)),
+            disabled,
+            password_hash,
+            metadata: LinkMetadata { title, description },
+        })
+    }
+
+    async fn get(&self, id: &str) -> Result<String, AppError> {
+        let mut connection = self.connection.clone();
+        connection
+            .get::<_, Option<String>>(url_key(id))
+            .await
+            .map_err(to_app_error)?
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl GetStatsRepository for RedisRepository {
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        let mut connection = self.connection.clone();
+        let (exists, clicks): (bool, Option<u64>) = redis::pipe()
+            .exists(url_key(id))
+            .get(clicks_key(id))
+            .query_async(&mut connection)
+            .await
+            .map_err(to_app_error)?;
+
+        if !exists {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(clicks.unwrap_or(0))
+    }
+}
+
+impl HealthCheckRepository for RedisRepository {
+    async fn ping(&self) -> Result<(), AppError> {
+        let mut connection = self.connection.clone();
+        redis::cmd("PING")
+            .query_async::<()>(&mut connection)
+            .await
+            .map_err(to_app_error)
+    }
+}
+
+impl RedisRepository {
+    async fn ids(&self) -> Result<Vec<String>, AppError> {
+        let mut connection = self.connection.clone();
+        let mut keys: Vec<String> = Vec::new();
+        let mut iter = connection
+            .scan_match::<_, String>(url_key("*"))
+            .await
+            .map_err(to_app_error)?;
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        keys.sort();
+
+        Ok(keys
+            .into_iter()
+            .filter_map(|key| key.strip_prefix("url:").map(str::to_owned))
+            .collect())
+    }
+}
+
+impl ListUrlsRepository for RedisRepository {
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        let ids: Vec<String> = self
+            .ids()
+            .await?
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .collect();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut connection = self.connection.clone();
+        let urls: Vec<Option<String>> = connection
+            .mget(ids.iter().map(|id| url_key(id)).collect::<Vec<_>>())
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(ids
+            .into_iter()
+            .zip(urls)
+            .filter_map(|(id, url)| url.map(|url| (id, url)))
+            .collect())
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        Ok(self.ids().await?.len())
+    }
+}
+
+impl ReverseLookupRepository for RedisRepository {
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        self.export()
+            .try_filter_map(|(id, full_url)| async move { Ok((full_url == url).then_some(id)) })
+            .try_collect()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get_repository() -> RedisRepository {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_owned());
+        let client = redis::Client::open(redis_url).unwrap();
+        let connection = ConnectionManager::new(client).await.unwrap();
+
+        RedisRepository::new(connection)
+    }
+
+    #[test]
+    fn classifies_connection_errors_as_transient() {
+        // Given
+        let dropped =
+            redis::RedisError::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        let loading = redis::RedisError::from((redis::ErrorKind::BusyLoadingError, "loading"));
+        let wrong_type = redis::RedisError::from((redis::ErrorKind::TypeError, "wrong type"));
+
+        // When
+        let dropped = to_app_error(dropped);
+        let loading = to_app_error(loading);
+        let wrong_type = to_app_error(wrong_type);
+
+        // Then
+        assert!(matches!(dropped, AppError::Transient(_)));
+        assert!(matches!(loading, AppError::Transient(_)));
+        assert!(matches!(wrong_type, AppError::RepositoryError(_)));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn save_and_get() {
+        // Given
+        let repository = get_repository().await;
+        let id = nanoid::nanoid!(12);
+
+        // When
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse(&id).unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        let result = repository.get(&id).await;
+
+        // Then
+        assert_eq!(result, Ok("https://www.google.com/".to_owned()));
+        assert_eq!(
+            repository
+                .get_record(&id)
+                .await
+                .map(|record| record.expires_at),
+            Ok(None)
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn save_with_ttl() {
+        // Given
+        let repository = get_repository().await;
+        let id = nanoid::nanoid!(12);
+
+        // When
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse(&id).unwrap(),
+                Utc::now(),
+                Some(Utc::now() + Duration::seconds(60)),
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        let result = repository
+            .get_record(&id)
+            .await
+            .map(|record| record.expires_at)
+            .unwrap();
+
+        // Then
+        assert!(result.is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn side_keys_expire_with_the_link() {
+        // Given
+        let repository = get_repository().await;
+        let id = nanoid::nanoid!(12);
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse(&id).unwrap(),
+                Utc::now(),
+                Some(Utc::now() + Duration::seconds(60)),
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+
+        // When
+        repository.increment(&id).await.unwrap();
+        repository.set_enabled(&id, false).await.unwrap();
+        repository
+            .record(&id, Utc::now(), None, None)
+            .await
+            .unwrap();
+
+        // Then
+        let mut connection = repository.connection.clone();
+        for key in [clicks_key(&id), disabled_key(&id), accesses_key(&id)] {
+            let ttl: i64 = connection.pttl(&key).await.unwrap();
+            assert!(ttl > 0, "{key} has no TTL");
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn claiming_an_expired_id_starts_fresh() {
+        // Given
+        let repository = get_repository().await;
+        let id = nanoid::nanoid!(12);
+        let mut connection = repository.connection.clone();
+        connection
+            .set::<_, _, ()>(clicks_key(&id), 5)
+            .await
+            .unwrap();
+        connection
+            .set::<_, _, ()>(disabled_key(&id), true)
+            .await
+            .unwrap();
+
+        // When
+        let claimed = repository
+            .save_if_absent(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse(&id).unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await;
+        let record = repository.get_record(&id).await.unwrap();
+
+        // Then
+        assert_eq!(claimed, Ok(true));
+        assert_eq!(record.clicks, 0);
+        assert!(!record.disabled);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn get_missing_id() {
+        // Given
+        let repository = get_repository().await;
+
+        // When
+        let result = repository.get("missing-id").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+    }
+}
@@ -0,0 +1,496 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+
+use crate::{
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::ExportRepository, get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository, health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository, reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+};
+
+/// Writes every change to both `primary` and `secondary`, e.g. while moving
+/// from one backend to another. `primary` is authoritative: its errors fail
+/// the request and all reads are served from it. `secondary` is best-effort;
+/// its errors are only logged.
+#[derive(Clone)]
+pub struct ReplicatingRepository<A, B> {
+    primary: A,
+    secondary: B,
+}
+
+impl<A, B> ReplicatingRepository<A, B> {
+    pub fn new(primary: A, secondary: B) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+fn log_replication(id: &str, result: Result<bool, AppError>) {
+    match result {
+        Ok(true) => {}
+        Ok(false) => tracing::warn!("replica already holds {}, not overwritten", id),
+        Err(e) => tracing::warn!("replica write for {} failed: {}", id, e),
+    }
+}
+
+#[async_trait]
+impl<A, B> CreateShortUrlRepository for ReplicatingRepository<A, B>
+where
+    A: CreateShortUrlRepository + Send + Sync,
+    B: CreateShortUrlRepository + Send + Sync,
+{
+    async fn save(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        self.primary
+            .save(
+                full_url.clone(),
+                id.clone(),
+                created_at,
+                expires_at,
+                password_hash.clone(),
+                metadata.clone(),
+            )
+            .await?;
+        let replicated = self
+            .secondary
+            .save(
+                full_url,
+                id.clone(),
+                created_at,
+                expires_at,
+                password_hash,
+                metadata,
+            )
+            .await;
+        log_replication(id.as_str(), replicated.map(|()| true));
+
+        Ok(())
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        let saved = self
+            .primary
+            .save_if_absent(
+                full_url.clone(),
+                id.clone(),
+                created_at,
+                expires_at,
+                password_hash.clone(),
+                metadata.clone(),
+            )
+            .await?;
+        if saved {
+            let replicated = self
+                .secondary
+                .save_if_absent(
+                    full_url,
+                    id.clone(),
+                    created_at,
+                    expires_at,
+                    password_hash,
+                    metadata,
+                )
+                .await;
+            log_replication(id.as_str(), replicated);
+        }
+
+        Ok(saved)
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        self.primary.exists(id).await
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        self.primary.find_by_url(url).await
+    }
+}
+
+#[async_trait]
+impl<A, B> DeleteShortUrlRepository for ReplicatingRepository<A, B>
+where
+    A: DeleteShortUrlRepository + Send + Sync,
+    B: DeleteShortUrlRepository + Send + Sync,
+{
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        self.primary.delete(id).await?;
+        log_replication(id, self.secondary.delete(id).await.map(|()| true));
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<A, B> PurgeExpiredRepository for ReplicatingRepository<A, B>
+where
+    A: PurgeExpiredRepository + Send + Sync,
+    B: PurgeExpiredRepository + Send + Sync,
+{
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let purged = self.primary.purge_expired(now).await?;
+        let replicated = self.secondary.purge_expired(now).await;
+        log_replication("expired links", replicated.map(|_| true));
+
+        Ok(purged)
+    }
+}
+
+#[async_trait]
+impl<A, B> AccessLogRepository for ReplicatingRepository<A, B>
+where
+    A: AccessLogRepository + Send + Sync,
+    B: AccessLogRepository + Send + Sync,
+{
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        self.primary
+            .record(id, at, user_agent.clone(), referer.clone())
+            .await?;
+        let replicated = self.secondary.record(id, at, user_agent, referer).await;
+        log_replication(id, replicated.map(|()| true));
+
+        Ok(())
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        self.primary.recent(id, limit).await
+    }
+}
+
+#[async_trait]
+impl<A, B> RecordClickRepository for ReplicatingRepository<A, B>
+where
+    A: RecordClickRepository + Send + Sync,
+    B: RecordClickRepository + Send + Sync,
+{
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        let clicks = self.primary.increment(id).await?;
+        log_replication(id, self.secondary.increment(id).await.map(|_| true));
+
+        Ok(clicks)
+    }
+}
+
+#[async_trait]
+impl<A, B> SetEnabledRepository for ReplicatingRepository<A, B>
+where
+    A: SetEnabledRepository + Send + Sync,
+    B: SetEnabledRepository + Send + Sync,
+{
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        self.primary.set_enabled(id, enabled).await?;
+        let replicated = self.secondary.set_enabled(id, enabled).await;
+        log_replication(id, replicated.map(|()| true));
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<A, B> UpdateShortUrlRepository for ReplicatingRepository<A, B>
+where
+    A: UpdateShortUrlRepository + Send + Sync,
+    B: UpdateShortUrlRepository + Send + Sync,
+{
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        self.primary.update(id, full_url.clone()).await?;
+        let replicated = self.secondary.update(id, full_url).await;
+        log_replication(id, replicated.map(|()| true));
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<A, B> PatchShortUrlRepository for ReplicatingRepository<A, B>
+where
+    A: PatchShortUrlRepository + Send + Sync,
+    B: PatchShortUrlRepository + Send + Sync,
+{
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        self.primary.patch(id, patch.clone()).await?;
+        let replicated = self.secondary.patch(id, patch).await;
+        log_replication(id, replicated.map(|()| true));
+
+        Ok(())
+    }
+}
+
+impl<A, B> ExportRepository for ReplicatingRepository<A, B>
+where
+    A: ExportRepository,
+{
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        self.primary.export()
+    }
+}
+
+impl<A, B> GetFullUrlRepository for ReplicatingRepository<A, B>
+where
+    A: GetFullUrlRepository + Sync,
+    B: Sync,
+{
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        self.primary.get_record(id).await
+    }
+}
+
+impl<A, B> GetStatsRepository for ReplicatingRepository<A, B>
+where
+    A: GetStatsRepository + Sync,
+    B: Sync,
+{
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        self.primary.clicks(id).await
+    }
+}
+
+impl<A, B> HealthCheckRepository for ReplicatingRepository<A, B>
+where
+    A: HealthCheckRepository + Sync,
+    B: Sync,
+{
+    async fn ping(&self) -> Result<(), AppError> {
+        self.primary.ping().await
+    }
+}
+
+impl<A, B> ListUrlsRepository for ReplicatingRepository<A, B>
+where
+    A: ListUrlsRepository + Sync,
+    B: Sync,
+{
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        self.primary.list(offset, limit).await
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        self.primary.count().await
+    }
+}
+
+impl<A, B> ReverseLookupRepository for ReplicatingRepository<A, B>
+where
+    A: ReverseLookupRepository + Sync,
+    B: Sync,
+{
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        self.primary.ids_for_url(url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::command::create_short_url::MockCreateShortUrlRepository, di::ContainerBuilder,
+        id_provider::FakeIdProvider,
+    };
+
+    use super::*;
+
+    async fn save<A, B>(sut: &ReplicatingRepository<A, B>) -> Result<(), AppError>
+    where
+        A: CreateShortUrlRepository + Send + Sync,
+        B: CreateShortUrlRepository + Send + Sync,
+    {
+        sut.save(
+            ValidUrl::parse("https://www.google.com/").unwrap(),
+            ShortId::parse("123").unwrap(),
+            Utc::now(),
+            None,
+            None,
+            LinkMetadata::default(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn save_writes_to_both() {
+        // Given
+        let primary = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let secondary = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ReplicatingRepository::new(primary.clone(), secondary.clone());
+
+        // When
+        let result = save(&sut).await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            primary.get("123").await,
+            Ok("https://www.google.com/".to_owned())
+        );
+        assert_eq!(
+            secondary.get("123").await,
+            Ok("https://www.google.com/".to_owned())
+        );
+        assert_eq!(
+            sut.get("123").await,
+            Ok("https://www.google.com/".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn secondary_failure_does_not_fail_the_save() {
+        // Given
+        let primary = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let mut secondary = MockCreateShortUrlRepository::new();
+        secondary
+            .expect_save()
+            .times(1)
+            .returning(|_, _, _, _, _, _| Err(AppError::RepositoryError("down".to_owned())));
+        let sut = ReplicatingRepository::new(primary.clone(), secondary);
+
+        // When
+        let result = save(&sut).await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+        assert_eq!(primary.exists("123").await, Ok(true));
+    }
+
+    #[tokio::test]
+    async fn primary_failure_fails_the_save() {
+        // Given
+        let mut primary = MockCreateShortUrlRepository::new();
+        primary
+            .expect_save()
+            .times(1)
+            .returning(|_, _, _, _, _, _| Err(AppError::RepositoryError("down".to_owned())));
+        let secondary = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ReplicatingRepository::new(primary, secondary.clone());
+
+        // When
+        let result = save(&sut).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::RepositoryError("down".to_owned())));
+        assert_eq!(secondary.exists("123").await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn save_if_absent_replicates_only_new_ids() {
+        // Given
+        let primary = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let mut secondary = MockCreateShortUrlRepository::new();
+        secondary
+            .expect_save_if_absent()
+            .times(1)
+            .returning(|_, _, _, _, _, _| Ok(true));
+        let sut = ReplicatingRepository::new(primary, secondary);
+        let save_if_absent = || {
+            sut.save_if_absent(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+        };
+
+        // When
+        let first = save_if_absent().await;
+        let second = save_if_absent().await;
+
+        // Then
+        assert_eq!(first, Ok(true));
+        assert_eq!(second, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn replicates_every_write_behind_a_container() {
+        // Given
+        let primary = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let secondary = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let repository = ReplicatingRepository::new(primary.clone(), secondary.clone());
+        let container = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("123".to_owned()))
+            .with_command_repository(repository.clone())
+            .with_query_repository(repository)
+            .build();
+        let id = container
+            .short_url_command
+            .execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+
+        // When
+        container
+            .update_short_url_command
+            .execute(&id, "https://www.rust-lang.org")
+            .await
+            .unwrap();
+        container.record_click_command.execute(&id).await.unwrap();
+        container
+            .set_enabled_command
+            .execute(&id, false)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(
+            secondary.get(&id).await,
+            Ok("https://www.rust-lang.org/".to_owned())
+        );
+        assert_eq!(secondary.clicks(&id).await, Ok(1));
+        assert_eq!(
+            secondary
+                .get_record(&id)
+                .await
+                .map(|record| record.disabled),
+            Ok(true)
+        );
+
+        // When
+        container
+            .delete_short_url_command
+            .execute(&id)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(primary.exists(&id).await, Ok(false));
+        assert_eq!(secondary.exists(&id).await, Ok(false));
+    }
+}
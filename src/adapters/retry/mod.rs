@@ -0,0 +1,418 @@
+use std::{future::Future, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+
+use crate::{
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::ExportRepository, get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository, health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository, reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone)]
+pub struct RetryingRepository<R> {
+    inner: R,
+    max_attempts: u32,
+    base_delay: Duration,
+}
+
+impl<R> RetryingRepository<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    async fn retry<T, F, Fut>(&self, mut operation: F) -> Result<T, AppError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation().await {
+                Err(AppError::Transient(e)) if attempt + 1 < self.max_attempts => {
+                    let delay = self.base_delay.saturating_mul(2u32.saturating_pow(attempt));
+                    tracing::warn!("transient repository error: {}, retrying in {:?}", e, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<R> CreateShortUrlRepository for RetryingRepository<R>
+where
+    R: CreateShortUrlRepository + Send + Sync,
+{
+    async fn save(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        self.retry(|| {
+            self.inner.save(
+                full_url.clone(),
+                id.clone(),
+                created_at,
+                expires_at,
+                password_hash.clone(),
+                metadata.clone(),
+            )
+        })
+        .await
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        // Not retried: a success lost to a transient error would read back as a conflict.
+        self.inner
+            .save_if_absent(
+                full_url,
+                id,
+                created_at,
+                expires_at,
+                password_hash,
+                metadata,
+            )
+            .await
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        self.inner.exists(id).await
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        self.inner.find_by_url(url).await
+    }
+}
+
+#[async_trait]
+impl<R> DeleteShortUrlRepository for RetryingRepository<R>
+where
+    R: DeleteShortUrlRepository + Send + Sync,
+{
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        self.inner.delete(id).await
+    }
+}
+
+#[async_trait]
+impl<R> PurgeExpiredRepository for RetryingRepository<R>
+where
+    R: PurgeExpiredRepository + Send + Sync,
+{
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        self.inner.purge_expired(now).await
+    }
+}
+
+#[async_trait]
+impl<R> AccessLogRepository for RetryingRepository<R>
+where
+    R: AccessLogRepository + Send + Sync,
+{
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        self.inner.record(id, at, user_agent, referer).await
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        self.inner.recent(id, limit).await
+    }
+}
+
+#[async_trait]
+impl<R> RecordClickRepository for RetryingRepository<R>
+where
+    R: RecordClickRepository + Send + Sync,
+{
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        self.inner.increment(id).await
+    }
+}
+
+#[async_trait]
+impl<R> SetEnabledRepository for RetryingRepository<R>
+where
+    R: SetEnabledRepository + Send + Sync,
+{
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        self.inner.set_enabled(id, enabled).await
+    }
+}
+
+#[async_trait]
+impl<R> UpdateShortUrlRepository for RetryingRepository<R>
+where
+    R: UpdateShortUrlRepository + Send + Sync,
+{
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        self.inner.update(id, full_url).await
+    }
+}
+
+#[async_trait]
+impl<R> PatchShortUrlRepository for RetryingRepository<R>
+where
+    R: PatchShortUrlRepository + Send + Sync,
+{
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        self.inner.patch(id, patch).await
+    }
+}
+
+impl<R> ExportRepository for RetryingRepository<R>
+where
+    R: ExportRepository,
+{
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        self.inner.export()
+    }
+}
+
+impl<R> GetFullUrlRepository for RetryingRepository<R>
+where
+    R: GetFullUrlRepository + Sync,
+{
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        self.retry(|| self.inner.get_record(id)).await
+    }
+
+    async fn get(&self, id: &str) -> Result<String, AppError> {
+        self.retry(|| self.inner.get(id)).await
+    }
+}
+
+impl<R> GetStatsRepository for RetryingRepository<R>
+where
+    R: GetStatsRepository + Sync,
+{
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        self.inner.clicks(id).await
+    }
+}
+
+impl<R> HealthCheckRepository for RetryingRepository<R>
+where
+    R: HealthCheckRepository + Sync,
+{
+    async fn ping(&self) -> Result<(), AppError> {
+        self.inner.ping().await
+    }
+}
+
+impl<R> ListUrlsRepository for RetryingRepository<R>
+where
+    R: ListUrlsRepository + Sync,
+{
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        self.inner.list(offset, limit).await
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        self.inner.count().await
+    }
+}
+
+impl<R> ReverseLookupRepository for RetryingRepository<R>
+where
+    R: ReverseLookupRepository + Sync,
+{
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        self.inner.ids_for_url(url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    };
+
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::command::create_short_url::MockCreateShortUrlRepository, di::ContainerBuilder,
+        id_provider::FakeIdProvider,
+    };
+
+    use super::*;
+
+    fn fail_times(
+        failures: u32,
+        error: fn() -> AppError,
+    ) -> (MockCreateShortUrlRepository, Arc<AtomicU32>) {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save().returning(move |_, _, _, _, _, _| {
+            if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                Err(error())
+            } else {
+                Ok(())
+            }
+        });
+
+        (mock_repo, calls)
+    }
+
+    async fn save(sut: &RetryingRepository<MockCreateShortUrlRepository>) -> Result<(), AppError> {
+        sut.save(
+            ValidUrl::parse("https://www.google.com/").unwrap(),
+            ShortId::parse("123").unwrap(),
+            Utc::now(),
+            None,
+            None,
+            LinkMetadata::default(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn save_retries_transient_errors() {
+        // Given
+        let (mock_repo, calls) = fail_times(2, || AppError::Transient("deadlock".to_owned()));
+        let sut = RetryingRepository::new(mock_repo).with_base_delay(Duration::from_millis(1));
+
+        // When
+        let result = save(&sut).await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn save_gives_up_after_max_attempts() {
+        // Given
+        let (mock_repo, calls) = fail_times(u32::MAX, || AppError::Transient("down".to_owned()));
+        let sut = RetryingRepository::new(mock_repo)
+            .with_max_attempts(4)
+            .with_base_delay(Duration::from_millis(1));
+
+        // When
+        let result = save(&sut).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::Transient("down".to_owned())));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn save_propagates_other_errors_immediately() {
+        // Given
+        let (mock_repo, calls) = fail_times(1, || AppError::RepositoryError("boom".to_owned()));
+        let sut = RetryingRepository::new(mock_repo).with_base_delay(Duration::from_millis(1));
+
+        // When
+        let result = save(&sut).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::RepositoryError("boom".to_owned())));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn get_retries_transient_errors() {
+        // Given
+        struct FakeRepository {
+            calls: AtomicU32,
+        }
+
+        impl GetFullUrlRepository for FakeRepository {
+            async fn get_record(&self, _id: &str) -> Result<StoredUrl, AppError> {
+                match self.calls.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(AppError::Transient("connection reset".to_owned())),
+                    _ => Ok(StoredUrl::new("https://www.google.com/")),
+                }
+            }
+        }
+        let sut = RetryingRepository::new(FakeRepository {
+            calls: AtomicU32::new(0),
+        })
+        .with_base_delay(Duration::from_millis(1));
+
+        // When
+        let result = sut.get("123").await;
+
+        // Then
+        assert_eq!(result, Ok("https://www.google.com/".to_owned()));
+        assert_eq!(sut.inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn wraps_a_full_repository() {
+        // Given
+        let repository = RetryingRepository::new(InMemoryRepository::new(Arc::new(DashMap::new())));
+        let container = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("123".to_owned()))
+            .with_command_repository(repository.clone())
+            .with_query_repository(repository)
+            .build();
+
+        // When
+        let id = container
+            .short_url_command
+            .execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+        let result = container.get_full_url_query.execute(&id).await;
+
+        // Then
+        assert_eq!(result, Ok("https://www.google.com/".to_owned()));
+    }
+}
@@ -0,0 +1,925 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{Stream, TryStreamExt, stream};
+use sqlx::SqlitePool;
+
+use crate::{
+    adapters::sqlx_error,
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::{EXPORT_BATCH_SIZE, ExportRepository},
+            get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository,
+            health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository,
+            reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+};
+
+#[derive(Debug, Clone)]
+pub struct SqliteRepository {
+    pool: SqlitePool,
+}
+
+/// Columns added to `urls` after its first release. `CREATE TABLE IF NOT
+/// EXISTS` leaves older tables without them, so `migrate` adds them.
+const ADDED_URL_COLUMNS: [(&str, &str); 2] = [("title", "TEXT"), ("description", "TEXT")];
+
+/// Primary result codes of a database another connection holds locked.
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+
+fn to_app_error(e: sqlx::Error) -> AppError {
+    // Extended result codes keep SQLITE_BUSY and SQLITE_LOCKED in the low byte.
+    sqlx_error::to_app_error(e, |code| {
+        code.parse::<i32>()
+            .is_ok_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED))
+    })
+}
+
+impl SqliteRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), AppError> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS urls (
+                id TEXT PRIMARY KEY,
+                full_url TEXT NOT NULL,
+                clicks INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT,
+                expires_at TEXT,
+                disabled BOOLEAN NOT NULL DEFAULT FALSE,
+                password_hash TEXT,
+                title TEXT,
+                description TEXT
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info('urls')")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+        for (column, column_type) in ADDED_URL_COLUMNS {
+            if !columns.iter().any(|existing| existing == column) {
+                sqlx::query(&format!(
+                    "ALTER TABLE urls ADD COLUMN {column} {column_type}"
+                ))
+                .execute(&self.pool)
+                .await
+                .map_err(to_app_error)?;
+            }
+        }
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS accesses (
+                id TEXT NOT NULL,
+                at TEXT NOT NULL,
+                user_agent TEXT,
+                referer TEXT
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CreateShortUrlRepository for SqliteRepository {
+    async fn save(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO urls (id, full_url, created_at, expires_at, password_hash, title, description) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id.into_string())
+        .bind(full_url.into_string())
+        .bind(created_at)
+        .bind(expires_at)
+        .bind(password_hash)
+        .bind(metadata.title)
+        .bind(metadata.description)
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(())
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO urls (id, full_url, created_at, expires_at, password_hash, title, description) VALUES (?, ?, ?, ?, ?, ?, ?) ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(id.into_string())
+        .bind(full_url.into_string())
+        .bind(created_at)
+        .bind(expires_at)
+        .bind(password_hash)
+        .bind(metadata.title)
+        .bind(metadata.description)
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM urls WHERE id = ?)")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(to_app_error)
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT id FROM urls WHERE full_url = ? AND expires_at IS NULL AND password_hash IS NULL LIMIT 1",
+        )
+        .bind(url)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(to_app_error)
+    }
+}
+
+#[async_trait]
+impl DeleteShortUrlRepository for SqliteRepository {
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM accesses WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+        let result = sqlx::query("DELETE FROM urls WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PurgeExpiredRepository for SqliteRepository {
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        sqlx::query("DELETE FROM accesses WHERE id IN (SELECT id FROM urls WHERE expires_at <= ?)")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+        let result = sqlx::query("DELETE FROM urls WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(result.rows_affected() as usize)
+    }
+}
+
+#[async_trait]
+impl AccessLogRepository for SqliteRepository {
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        sqlx::query("INSERT INTO accesses (id, at, user_agent, referer) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind(at)
+            .bind(user_agent)
+            .bind(referer)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(())
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        if !self.exists(id).await? {
+            return Err(AppError::NotFound);
+        }
+
+        let rows = sqlx::query_as::<_, (DateTime<Utc>, Option<String>, Option<String>)>(
+            "SELECT at, user_agent, referer FROM accesses WHERE id = ? ORDER BY at DESC LIMIT ?",
+        )
+        .bind(id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(at, user_agent, referer)| AccessEntry {
+                at,
+                user_agent,
+                referer,
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl RecordClickRepository for SqliteRepository {
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        sqlx::query_scalar::<_, i64>(
+            "UPDATE urls SET clicks = clicks + 1 WHERE id = ? RETURNING clicks",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(to_app_error)?
+        .map(|clicks| clicks as u64)
+        .ok_or(AppError::NotFound)
+    }
+}
+
+#[async_trait]
+impl SetEnabledRepository for SqliteRepository {
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        let result = sqlx::query("UPDATE urls SET disabled = ? WHERE id = ?")
+            .bind(!enabled)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UpdateShortUrlRepository for SqliteRepository {
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        let result = sqlx::query("UPDATE urls SET full_url = ? WHERE id = ?")
+            .bind(full_url)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PatchShortUrlRepository for SqliteRepository {
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "UPDATE urls SET full_url = COALESCE(?, full_url), disabled = COALESCE(?, disabled), expires_at = CASE WHEN ? THEN ? ELSE expires_at END WHERE id = ?",
+        )
+        .bind(patch.full_url)
+        .bind(patch.disabled)
+        .bind(patch.expires_at.is_some())
+        .bind(patch.expires_at.flatten())
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(to_app_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+impl ExportRepository for SqliteRepository {
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        let pool = self.pool.clone();
+
+        stream::try_unfold(Some(String::new()), move |after| {
+            let pool = pool.clone();
+            async move {
+                let Some(after) = after else {
+                    return Ok(None);
+                };
+                let batch = sqlx::query_as::<_, (String, String)>(
+                    "SELECT id, full_url FROM urls WHERE id > ? ORDER BY id LIMIT ?",
+                )
+                .bind(after)
+                .bind(EXPORT_BATCH_SIZE as i64)
+                .fetch_all(&pool)
+                .await
+                .map_err(to_app_error)?;
+                let next = match batch.last() {
+                    Some((id, _)) if batch.len() == EXPORT_BATCH_SIZE => Some(id.clone()),
+                    _ => None,
+                };
+
+                Ok::<_, AppError>(Some((batch, next)))
+            }
+        })
+        .map_ok(|batch| stream::iter(batch.into_iter().map(Ok)))
+        .try_flatten()
+    }
+}
+
+impl GetFullUrlRepository for SqliteRepository {
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        sqlx::query_as::<
+            _,
+            (
+                String,
+                i64,
+                Option<DateTime<Utc>>,
+                Option<DateTime<Utc>>,
+                bool,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+            ),
+        >(
+            "SELECT full_url, clicks, created_at, expires_at, disabled, password_hash, title, description FROM urls WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(to_app_error)?
+        .map(|row| {
+            let (
+                full_url,
+                clicks,
+                created_at,
+                expires_at,
+                disabled,
+                password_hash,
+                title,
+                description,
+            ) = row;
+
+            StoredUrl {
+                full_url,
+                clicks: clicks as u64,
+                created_at,
+                expires_at,
+                disabled,
+                password_hash,
+                metadata: LinkMetadata { title, description },
+            }
+        })
+        .ok_or(AppError::NotFound)
+    }
+
+    async fn get(&self, id: &str) -> Result<String, AppError> {
+        sqlx::query_scalar::<_, String>("SELECT full_url FROM urls WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(to_app_error)?
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl GetStatsRepository for SqliteRepository {
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        sqlx::query_scalar::<_, i64>("SELECT clicks FROM urls WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(to_app_error)?
+            .map(|clicks| clicks as u64)
+            .ok_or(AppError::NotFound)
+    }
+}
+
+impl HealthCheckRepository for SqliteRepository {
+    async fn ping(&self) -> Result<(), AppError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(to_app_error)?;
+
+        Ok(())
+    }
+}
+
+impl ListUrlsRepository for SqliteRepository {
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        sqlx::query_as::<_, (String, String)>(
+            "SELECT id, full_url FROM urls ORDER BY id LIMIT ? OFFSET ?",
+        )
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(to_app_error)
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM urls")
+            .fetch_one(&self.pool)
+            .await
+            .map(|count| count as usize)
+            .map_err(to_app_error)
+    }
+}
+
+impl ReverseLookupRepository for SqliteRepository {
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        sqlx::query_scalar::<_, String>("SELECT id FROM urls WHERE full_url = ? ORDER BY id")
+            .bind(url)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(to_app_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    async fn get_repository() -> SqliteRepository {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let repository = SqliteRepository::new(pool);
+        repository.migrate().await.unwrap();
+
+        repository
+    }
+
+    #[tokio::test]
+    async fn save_and_get() {
+        // Given
+        let repository = get_repository().await;
+
+        // When
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        let result = repository.get("123").await;
+
+        // Then
+        assert_eq!(result, Ok("https://www.google.com/".to_owned()));
+        assert_eq!(repository.exists("123").await, Ok(true));
+        assert_eq!(
+            repository
+                .get_record("123")
+                .await
+                .map(|record| record.expires_at),
+            Ok(None)
+        );
+    }
+
+    #[tokio::test]
+    async fn save_with_metadata() {
+        // Given
+        let repository = get_repository().await;
+        let metadata = LinkMetadata {
+            title: Some("Google".to_owned()),
+            description: Some("Search engine".to_owned()),
+        };
+
+        // When
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                metadata.clone(),
+            )
+            .await
+            .unwrap();
+        let result = repository.get_record("123").await.unwrap();
+
+        // Then
+        assert_eq!(result.metadata, metadata);
+    }
+
+    #[tokio::test]
+    async fn migrate_adds_metadata_columns_to_existing_tables() {
+        // Given
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE urls (
+                id TEXT PRIMARY KEY,
+                full_url TEXT NOT NULL,
+                clicks INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT,
+                expires_at TEXT,
+                disabled BOOLEAN NOT NULL DEFAULT FALSE,
+                password_hash TEXT
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO urls (id, full_url) VALUES ('old', 'https://example.com/')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let repository = SqliteRepository::new(pool);
+
+        // When
+        repository.migrate().await.unwrap();
+        repository.migrate().await.unwrap();
+
+        // Then
+        let old = repository.get_record("old").await.unwrap();
+        assert_eq!(old.full_url, "https://example.com/");
+        assert_eq!(old.metadata, LinkMetadata::default());
+    }
+
+    #[tokio::test]
+    async fn save_if_absent_does_not_overwrite() {
+        // Given
+        let repository = get_repository().await;
+        let save = |url: &str| {
+            repository.save_if_absent(
+                ValidUrl::parse(url).unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+        };
+
+        // When
+        let first = save("https://first.example/").await;
+        let second = save("https://second.example/").await;
+
+        // Then
+        assert_eq!(first, Ok(true));
+        assert_eq!(second, Ok(false));
+        assert_eq!(
+            repository.get("123").await,
+            Ok("https://first.example/".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn purge_expired_removes_only_past_entries() {
+        // Given
+        let repository = get_repository().await;
+        let now = Utc::now();
+        for (id, expires_at) in [
+            ("past", Some(now - chrono::Duration::seconds(1))),
+            ("future", Some(now + chrono::Duration::hours(1))),
+            ("forever", None),
+        ] {
+            repository
+                .save(
+                    ValidUrl::parse("https://www.google.com/").unwrap(),
+                    ShortId::parse(id).unwrap(),
+                    now,
+                    expires_at,
+                    None,
+                    LinkMetadata::default(),
+                )
+                .await
+                .unwrap();
+        }
+        repository.record("past", now, None, None).await.unwrap();
+
+        // When
+        let purged = repository.purge_expired(now).await;
+
+        // Then
+        assert_eq!(purged, Ok(1));
+        assert_eq!(repository.exists("past").await, Ok(false));
+        assert_eq!(repository.exists("future").await, Ok(true));
+        assert_eq!(repository.exists("forever").await, Ok(true));
+    }
+
+    #[tokio::test]
+    async fn get_missing_id() {
+        // Given
+        let repository = get_repository().await;
+
+        // When
+        let result = repository.get("missing-id").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn save_with_expiry() {
+        // Given
+        let repository = get_repository().await;
+        let expires_at = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+
+        // When
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                Some(expires_at),
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        let result = repository
+            .get_record("123")
+            .await
+            .map(|record| record.expires_at);
+
+        // Then
+        assert_eq!(result, Ok(Some(expires_at)));
+    }
+
+    #[tokio::test]
+    async fn increment_delete_and_ping() {
+        // Given
+        let repository = get_repository().await;
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+
+        // When
+        repository.increment("123").await.unwrap();
+        repository.increment("123").await.unwrap();
+        let clicks = repository.clicks("123").await;
+        let deleted = repository.delete("123").await;
+        let deleted_again = repository.delete("123").await;
+
+        // Then
+        assert_eq!(clicks, Ok(2));
+        assert_eq!(deleted, Ok(()));
+        assert_eq!(deleted_again, Err(AppError::NotFound));
+        assert_eq!(repository.ping().await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn find_by_url_ignores_expiring_links() {
+        // Given
+        let repository = get_repository().await;
+        let expires_at = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("expiring").unwrap(),
+                Utc::now(),
+                Some(expires_at),
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("permanent").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+
+        // When
+        let found = repository.find_by_url("https://www.google.com/").await;
+        let missing = repository.find_by_url("https://www.example.com/").await;
+
+        // Then
+        assert_eq!(found, Ok(Some("permanent".to_owned())));
+        assert_eq!(missing, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn list_in_id_order() {
+        // Given
+        let repository = get_repository().await;
+        for id in ["c", "a", "b"] {
+            repository
+                .save(
+                    ValidUrl::parse(&format!("https://{id}.example/")).unwrap(),
+                    ShortId::parse(id).unwrap(),
+                    Utc::now(),
+                    None,
+                    None,
+                    LinkMetadata::default(),
+                )
+                .await
+                .unwrap();
+        }
+
+        // When
+        let page = repository.list(1, 5).await;
+        let total = repository.count().await;
+
+        // Then
+        assert_eq!(
+            page,
+            Ok(vec![
+                ("b".to_owned(), "https://b.example/".to_owned()),
+                ("c".to_owned(), "https://c.example/".to_owned()),
+            ])
+        );
+        assert_eq!(total, Ok(3));
+    }
+
+    #[tokio::test]
+    async fn export_spans_multiple_batches() {
+        // Given
+        let repository = get_repository().await;
+        let count = EXPORT_BATCH_SIZE + 1;
+        for i in 0..count {
+            repository
+                .save(
+                    ValidUrl::parse(&format!("https://example.com/{i}")).unwrap(),
+                    ShortId::parse(&format!("id-{i:04}")).unwrap(),
+                    Utc::now(),
+                    None,
+                    None,
+                    LinkMetadata::default(),
+                )
+                .await
+                .unwrap();
+        }
+
+        // When
+        let result: Vec<(String, String)> = repository.export().try_collect().await.unwrap();
+
+        // Then
+        assert_eq!(result.len(), count);
+        assert_eq!(
+            result.last(),
+            Some(&(
+                format!("id-{:04}", count - 1),
+                format!("https://example.com/{}", count - 1)
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn record_and_read_recent_accesses() {
+        // Given
+        let repository = get_repository().await;
+        let at = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+
+        // When
+        repository
+            .record("123", at, Some("curl/8.0".to_owned()), None)
+            .await
+            .unwrap();
+        repository
+            .record(
+                "123",
+                at + chrono::Duration::seconds(1),
+                Some("Mozilla/5.0".to_owned()),
+                Some("https://news.example/".to_owned()),
+            )
+            .await
+            .unwrap();
+        let result = repository.recent("123", 1).await;
+
+        // Then
+        assert_eq!(
+            result,
+            Ok(vec![AccessEntry {
+                at: at + chrono::Duration::seconds(1),
+                user_agent: Some("Mozilla/5.0".to_owned()),
+                referer: Some("https://news.example/".to_owned()),
+            }])
+        );
+        assert_eq!(
+            repository.recent("missing", 1).await,
+            Err(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn disable_and_enable() {
+        // Given
+        let repository = get_repository().await;
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+
+        // When
+        repository.set_enabled("123", false).await.unwrap();
+        let disabled = repository
+            .get_record("123")
+            .await
+            .map(|record| record.disabled);
+        repository.set_enabled("123", true).await.unwrap();
+        let enabled = repository
+            .get_record("123")
+            .await
+            .map(|record| record.disabled);
+
+        // Then
+        assert_eq!(disabled, Ok(true));
+        assert_eq!(enabled, Ok(false));
+        assert_eq!(
+            repository.set_enabled("missing", false).await,
+            Err(AppError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn closed_pool_is_transient() {
+        // Given
+        let repository = get_repository().await;
+        repository.pool.close().await;
+
+        // When
+        let result = repository.get("123").await;
+
+        // Then
+        assert!(matches!(result, Err(AppError::Transient(_))));
+    }
+}
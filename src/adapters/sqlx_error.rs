@@ -0,0 +1,53 @@
+use crate::error::AppError;
+
+/// Maps a sqlx error for the SQL adapters. A dropped connection, an
+/// exhausted or closed pool, and any database error whose code
+/// `is_transient` accepts (deadlocks, serialization failures, busy
+/// locks) become [`AppError::Transient`] so they can be retried; the
+/// rest are final.
+pub fn to_app_error(e: sqlx::Error, is_transient: fn(&str) -> bool) -> AppError {
+    let transient = match &e {
+        sqlx::Error::Io(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::WorkerCrashed => true,
+        sqlx::Error::Database(error) => error.code().is_some_and(|code| is_transient(&code)),
+        _ => false,
+    };
+
+    match transient {
+        true => AppError::Transient(e.to_string()),
+        false => AppError::RepositoryError(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[test]
+    fn classifies_connection_errors_as_transient() {
+        // Given
+        let cases = [
+            (sqlx::Error::PoolTimedOut, true),
+            (sqlx::Error::PoolClosed, true),
+            (sqlx::Error::Io(io::ErrorKind::ConnectionReset.into()), true),
+            (sqlx::Error::RowNotFound, false),
+            (sqlx::Error::Protocol("bad packet".to_owned()), false),
+        ];
+
+        for (error, transient) in cases {
+            // When
+            let result = to_app_error(error, |_| false);
+
+            // Then
+            assert_eq!(
+                matches!(result, AppError::Transient(_)),
+                transient,
+                "{result}"
+            );
+        }
+    }
+}
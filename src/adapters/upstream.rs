@@ -0,0 +1,308 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    app::{domain::StoredUrl, query::get_full_url::GetFullUrlRepository, url_policy::UrlPolicy},
+    error::AppError,
+};
+
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(2);
+const DEFAULT_CAPACITY: NonZeroUsize = NonZeroUsize::new(1024).unwrap();
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+struct ResolveResponse {
+    url: String,
+}
+
+/// Resolves ids through another instance's `GET /api/resolve/{id}`.
+/// Targets are held to the local [`UrlPolicy`], so an upstream cannot
+/// redirect to anything this instance would refuse to store. Successful
+/// lookups are cached for a while, so links disabled, expired or changed
+/// upstream stop resolving here soon after; misses and errors are not
+/// cached.
+#[derive(Clone)]
+pub struct UpstreamRepository {
+    client: reqwest::Client,
+    base_url: String,
+    url_policy: UrlPolicy,
+    cache_ttl: Duration,
+    cache: Arc<Mutex<LruCache<String, (String, Instant)>>>,
+}
+
+impl UpstreamRepository {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(UPSTREAM_TIMEOUT)
+            .build()
+            .expect("failed to build upstream client");
+
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            url_policy: UrlPolicy::default(),
+            cache_ttl: DEFAULT_CACHE_TTL,
+            cache: Arc::new(Mutex::new(LruCache::new(DEFAULT_CAPACITY))),
+        }
+    }
+
+    pub fn with_url_policy(mut self, url_policy: UrlPolicy) -> Self {
+        self.url_policy = url_policy;
+        self
+    }
+
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    fn cached(&self, id: &str) -> Option<String> {
+        let mut cache = self.cache.lock().unwrap();
+        match cache.get(id) {
+            Some((url, cached_at)) if cached_at.elapsed() < self.cache_ttl => Some(url.clone()),
+            Some(_) => {
+                cache.pop(id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Rejects a target this instance would not accept from its own users.
+    /// It is reported as missing rather than as a bad request, since the
+    /// caller asked for a valid id.
+    fn check_policy(&self, id: &str, url: &str) -> Result<(), AppError> {
+        let checked = self
+            .url_policy
+            .parse_verbatim(url)
+            .and_then(|url| self.url_policy.check_domain(&url));
+        if let Err(e) = checked {
+            tracing::warn!("rejected upstream target for {}: {}", id, e);
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn fetch(&self, id: &str) -> Result<String, AppError> {
+        let response = self
+            .client
+            .get(format!("{}/api/resolve/{}", self.base_url, id))
+            .send()
+            .await
+            .map_err(|e| AppError::Transient(e.to_string()))?;
+
+        match response.status() {
+            status if status.is_success() => response
+                .json::<ResolveResponse>()
+                .await
+                .map(|body| body.url)
+                .map_err(|e| AppError::RepositoryError(e.to_string())),
+            StatusCode::NOT_FOUND => Err(AppError::NotFound),
+            StatusCode::GONE => Err(AppError::Gone),
+            StatusCode::FORBIDDEN => Err(AppError::Disabled),
+            StatusCode::UNAUTHORIZED => Err(AppError::Unauthorized),
+            status if status.is_server_error() => {
+                Err(AppError::Transient(format!("upstream returned {status}")))
+            }
+            status => Err(AppError::RepositoryError(format!(
+                "upstream returned {status}"
+            ))),
+        }
+    }
+}
+
+impl GetFullUrlRepository for UpstreamRepository {
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        let url = match self.cached(id) {
+            Some(url) => url,
+            None => {
+                let url = self.fetch(id).await?;
+                self.check_policy(id, &url)?;
+                self.cache
+                    .lock()
+                    .unwrap()
+                    .put(id.to_owned(), (url.clone(), Instant::now()));
+                url
+            }
+        };
+
+        Ok(StoredUrl::new(url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{
+        Json, Router,
+        extract::{Path, State},
+        http::StatusCode as AxumStatusCode,
+        response::IntoResponse,
+        routing::get,
+    };
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::{fallback::FallbackRepository, in_memory::InMemoryRepository},
+        app::query::get_full_url::GetFullUrlQuery,
+    };
+
+    use super::*;
+
+    async fn spawn_upstream() -> (String, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let router = Router::new()
+            .route(
+                "/api/resolve/{id}",
+                get(
+                    |State(hits): State<Arc<AtomicUsize>>, Path(id): Path<String>| async move {
+                        hits.fetch_add(1, Ordering::SeqCst);
+                        match id.as_str() {
+                            "remote" => Json(serde_json::json!({
+                                "id": "remote",
+                                "url": "https://remote.example/",
+                            }))
+                            .into_response(),
+                            "script" => Json(serde_json::json!({
+                                "id": "script",
+                                "url": "javascript:alert(1)",
+                            }))
+                            .into_response(),
+                            "blocked" => Json(serde_json::json!({
+                                "id": "blocked",
+                                "url": "https://evil.example/",
+                            }))
+                            .into_response(),
+                            _ => AxumStatusCode::NOT_FOUND.into_response(),
+                        }
+                    },
+                ),
+            )
+            .with_state(hits.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        (format!("http://{addr}"), hits)
+    }
+
+    async fn get_sut() -> (
+        GetFullUrlQuery<FallbackRepository<InMemoryRepository, UpstreamRepository>>,
+        Arc<AtomicUsize>,
+    ) {
+        let (upstream_url, hits) = spawn_upstream().await;
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("local".to_owned(), StoredUrl::new("https://local.example/"));
+        let repository = FallbackRepository::new(
+            InMemoryRepository::new(store),
+            UpstreamRepository::new(upstream_url),
+        );
+
+        (GetFullUrlQuery::new(repository), hits)
+    }
+
+    #[tokio::test]
+    async fn resolves_local_miss_from_upstream() {
+        // Given
+        let (sut, hits) = get_sut().await;
+
+        // When
+        let local = sut.execute("local").await;
+        let remote = sut.execute("remote").await;
+
+        // Then
+        assert_eq!(local, Ok("https://local.example/".to_owned()));
+        assert_eq!(remote, Ok("https://remote.example/".to_owned()));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn caches_upstream_hits() {
+        // Given
+        let (sut, hits) = get_sut().await;
+
+        // When
+        sut.execute("remote").await.unwrap();
+        let again = sut.execute("remote").await;
+
+        // Then
+        assert_eq!(again, Ok("https://remote.example/".to_owned()));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn refetches_expired_cache_entries() {
+        // Given
+        let (upstream_url, hits) = spawn_upstream().await;
+        let sut = UpstreamRepository::new(upstream_url).with_cache_ttl(Duration::ZERO);
+
+        // When
+        sut.get_record("remote").await.unwrap();
+        let again = sut.get_record("remote").await.map(|record| record.full_url);
+
+        // Then
+        assert_eq!(again, Ok("https://remote.example/".to_owned()));
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_targets_outside_the_url_policy() {
+        // Given
+        let (upstream_url, hits) = spawn_upstream().await;
+        let sut = UpstreamRepository::new(upstream_url).with_url_policy(
+            UrlPolicy::default().with_domain_blocklist(std::collections::HashSet::from([
+                "evil.example".to_owned(),
+            ])),
+        );
+
+        // When
+        let script = sut.get_record("script").await;
+        let blocked = sut.get_record("blocked").await;
+        let blocked_again = sut.get_record("blocked").await;
+
+        // Then
+        assert_eq!(script, Err(AppError::NotFound));
+        assert_eq!(blocked, Err(AppError::NotFound));
+        assert_eq!(blocked_again, Err(AppError::NotFound));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn missing_everywhere_is_not_found() {
+        // Given
+        let (sut, hits) = get_sut().await;
+
+        // When
+        let first = sut.execute("missing").await;
+        let second = sut.execute("missing").await;
+
+        // Then
+        assert_eq!(first, Err(AppError::NotFound));
+        assert_eq!(second, Err(AppError::NotFound));
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn unreachable_upstream_is_transient() {
+        // Given
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let sut = UpstreamRepository::new(upstream_url);
+
+        // When
+        let result = sut.get_record("remote").await;
+
+        // Then
+        assert!(matches!(result, Err(AppError::Transient(_))));
+    }
+}
@@ -0,0 +1,162 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    app::events::EventSink,
+    clock::{Clock, SystemClock},
+};
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    event: &'static str,
+    id: &'a str,
+    url: &'a str,
+    at: DateTime<Utc>,
+}
+
+/// Posts creation events to `url`. Delivery runs on the task spawned by
+/// [`crate::app::events::emit_created`], so a slow endpoint never holds up
+/// the request, and failures are only logged.
+pub struct WebhookEventSink {
+    client: reqwest::Client,
+    url: String,
+    clock: Arc<dyn Clock>,
+}
+
+impl WebhookEventSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .expect("failed to build webhook client");
+
+        Self {
+            client,
+            url: url.into(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+#[async_trait]
+impl EventSink for WebhookEventSink {
+    async fn on_created(&self, id: &str, url: &str) {
+        let payload = WebhookPayload {
+            event: "created",
+            id,
+            url,
+            at: self.clock.now(),
+        };
+        let result = self
+            .client
+            .post(&self.url)
+            .json(&payload)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+
+        if let Err(e) = result {
+            tracing::warn!("webhook delivery for {} failed: {}", id, e);
+        }
+    }
+
+    async fn on_resolved(&self, _id: &str) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Json, Router, extract::State, routing::post};
+    use chrono::TimeZone;
+    use dashmap::DashMap;
+    use tokio::sync::mpsc;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::command::create_short_url::CreateShortUrlCommand, clock::FakeClock,
+        id_provider::FakeIdProvider,
+    };
+
+    use super::*;
+
+    async fn spawn_receiver() -> (String, mpsc::UnboundedReceiver<serde_json::Value>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let router = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(sender): State<mpsc::UnboundedSender<serde_json::Value>>,
+                     Json(payload): Json<serde_json::Value>| async move {
+                        let _ = sender.send(payload);
+                    },
+                ),
+            )
+            .with_state(sender);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        (format!("http://{addr}/hook"), receiver)
+    }
+
+    #[tokio::test]
+    async fn create_posts_webhook() {
+        // Given
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let (hook_url, mut payloads) = spawn_receiver().await;
+        let sink = WebhookEventSink::new(hook_url).with_clock(Arc::new(FakeClock::new(now)));
+        let sut = CreateShortUrlCommand::new(
+            FakeIdProvider::new("123".to_owned()),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+        )
+        .with_events(Arc::new(sink));
+
+        // When
+        sut.execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+        let payload = tokio::time::timeout(Duration::from_secs(5), payloads.recv()).await;
+
+        // Then
+        assert_eq!(
+            payload.unwrap(),
+            Some(serde_json::json!({
+                "event": "created",
+                "id": "123",
+                "url": "https://www.google.com/",
+                "at": "2025-01-01T12:00:00Z",
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn unreachable_webhook_does_not_fail_create() {
+        // Given
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let hook_url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+        let sink = WebhookEventSink::new(hook_url);
+
+        // When
+        sink.on_created("123", "https://www.google.com/").await;
+        let result = CreateShortUrlCommand::new(
+            FakeIdProvider::new("123".to_owned()),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+        )
+        .with_events(Arc::new(sink))
+        .execute("https://www.google.com", None, None)
+        .await;
+
+        // Then
+        assert_eq!(result.unwrap(), "123");
+    }
+}
@@ -1,12 +1,58 @@
+use std::{collections::HashSet, sync::Arc};
+
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
 use url::Url;
 
-use crate::{error::AppError, id_provider::IdProvider};
+use crate::{
+    app::{
+        domain::{LinkMetadata, ShortId, ValidUrl},
+        events::{self, EventSink, NoopEventSink},
+        url_policy::UrlPolicy,
+    },
+    clock::{Clock, SystemClock},
+    error::AppError,
+    id_provider::{Checksum, IdProvider},
+    password,
+};
 
 #[mockall::automock]
 #[async_trait]
 pub trait CreateShortUrlRepository {
-    async fn save<'a>(&'a self, full_url: String, id: String) -> Result<(), AppError>;
+    async fn save<'a>(
+        &'a self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError>;
+    /// Atomically saves the url unless `id` is already taken; returns whether it was saved.
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError>;
+    async fn exists(&self, id: &str) -> Result<bool, AppError>;
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError>;
+}
+
+const MAX_ALIAS_LENGTH: usize = 32;
+const DEFAULT_MAX_ATTEMPTS: usize = 5;
+const DEFAULT_RESERVED_IDS: [&str; 6] =
+    ["admin", "available", "batch", "docs", "health", "metrics"];
+
+pub(crate) fn validate_alias(alias: &str) -> Result<ShortId, AppError> {
+    if alias.len() > MAX_ALIAS_LENGTH {
+        return Err(AppError::InvalidAlias);
+    }
+
+    ShortId::parse(alias).map_err(|_| AppError::InvalidAlias)
 }
 
 pub struct CreateShortUrlCommand<I, R>
@@ -16,6 +62,16 @@ where
 {
     id_provider: I,
     repository: R,
+    max_attempts: usize,
+    url_policy: UrlPolicy,
+    reserved_ids: Vec<String>,
+    dedup: bool,
+    assume_https: bool,
+    preserve_input: bool,
+    case_insensitive: bool,
+    checksum: Option<Checksum>,
+    clock: Arc<dyn Clock>,
+    events: Arc<dyn EventSink>,
 }
 
 impl<I, R> CreateShortUrlCommand<I, R>
@@ -27,29 +83,305 @@ where
         Self {
             id_provider,
             repository,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            url_policy: UrlPolicy::default(),
+            reserved_ids: DEFAULT_RESERVED_IDS.map(String::from).to_vec(),
+            dedup: false,
+            assume_https: false,
+            preserve_input: false,
+            case_insensitive: false,
+            checksum: None,
+            clock: Arc::new(SystemClock),
+            events: Arc::new(NoopEventSink),
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn with_url_policy(mut self, url_policy: UrlPolicy) -> Self {
+        self.url_policy = url_policy;
+        self
+    }
+
+    pub fn with_max_url_length(mut self, max_url_length: usize) -> Self {
+        self.url_policy = self.url_policy.with_max_url_length(max_url_length);
+        self
+    }
+
+    pub fn with_allowed_schemes(mut self, allowed_schemes: Vec<String>) -> Self {
+        self.url_policy = self.url_policy.with_allowed_schemes(allowed_schemes);
+        self
+    }
+
+    pub fn with_reserved_ids(mut self, reserved_ids: Vec<String>) -> Self {
+        self.reserved_ids = reserved_ids;
+        self
+    }
+
+    pub fn with_domain_blocklist(mut self, domain_blocklist: HashSet<String>) -> Self {
+        self.url_policy = self.url_policy.with_domain_blocklist(domain_blocklist);
+        self
+    }
+
+    pub fn with_domain_allowlist(mut self, domain_allowlist: Option<HashSet<String>>) -> Self {
+        self.url_policy = self.url_policy.with_domain_allowlist(domain_allowlist);
+        self
+    }
+
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    pub fn with_assume_https(mut self, assume_https: bool) -> Self {
+        self.assume_https = assume_https;
+        self
+    }
+
+    pub fn with_preserve_input(mut self, preserve_input: bool) -> Self {
+        self.preserve_input = preserve_input;
+        self
+    }
+
+    pub fn with_case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Appends the check character to aliases so they resolve like
+    /// generated ids. Generated ids get theirs from the id provider.
+    pub fn with_checksum(mut self, checksum: Option<Checksum>) -> Self {
+        self.checksum = checksum;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_events(mut self, events: Arc<dyn EventSink>) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub async fn execute(
+        &self,
+        full_url: &str,
+        alias: Option<&str>,
+        ttl: Option<Duration>,
+    ) -> Result<ShortId, AppError> {
+        self.execute_with_password(full_url, alias, ttl, None).await
+    }
+
+    pub async fn execute_with_password(
+        &self,
+        full_url: &str,
+        alias: Option<&str>,
+        ttl: Option<Duration>,
+        password: Option<&str>,
+    ) -> Result<ShortId, AppError> {
+        self.execute_with_metadata(full_url, alias, ttl, password, LinkMetadata::default())
+            .await
+    }
+
+    pub async fn execute_with_metadata(
+        &self,
+        full_url: &str,
+        alias: Option<&str>,
+        ttl: Option<Duration>,
+        password: Option<&str>,
+        metadata: LinkMetadata,
+    ) -> Result<ShortId, AppError> {
+        let full_url = self.parse_url(full_url)?;
+
+        self.create(full_url, alias, ttl, password, metadata).await
+    }
+
+    pub async fn dry_run(
+        &self,
+        full_url: &str,
+        alias: Option<&str>,
+    ) -> Result<(ValidUrl, ShortId), AppError> {
+        let full_url = self.parse_url(full_url)?;
+        self.check_domain(&full_url)?;
+        let id = match alias {
+            Some(alias) => self.check_alias(alias).await?,
+            None => self.normalize(self.id_provider.provide(Some(full_url.as_str()))),
+        };
+
+        Ok((full_url, id))
+    }
+
+    pub async fn is_available(&self, alias: &str) -> Result<bool, AppError> {
+        let alias = self.normalize(validate_alias(alias)?);
+        if self.is_reserved(&alias) {
+            return Ok(false);
         }
+        let alias = self.append_checksum(alias)?;
+
+        Ok(!self.repository.exists(&alias).await?)
+    }
+
+    pub async fn execute_url(
+        &self,
+        url: Url,
+        alias: Option<&str>,
+        ttl: Option<Duration>,
+    ) -> Result<ShortId, AppError> {
+        let full_url = self.url_policy.from_url(url)?;
+
+        self.create(full_url, alias, ttl, None, LinkMetadata::default())
+            .await
+    }
+
+    pub async fn execute_valid(
+        &self,
+        full_url: ValidUrl,
+        alias: Option<&str>,
+        ttl: Option<Duration>,
+    ) -> Result<ShortId, AppError> {
+        self.create(full_url, alias, ttl, None, LinkMetadata::default())
+            .await
     }
 
-    pub async fn execute(&self, full_url: &str) -> Result<String, AppError> {
-        let parsed_url = Url::parse(full_url).map_err(|_| AppError::UrlParseError)?;
-        let id = self.id_provider.provide();
-        self.repository
-            .save(parsed_url.to_string(), id.clone())
+    async fn create(
+        &self,
+        full_url: ValidUrl,
+        alias: Option<&str>,
+        ttl: Option<Duration>,
+        password: Option<&str>,
+        metadata: LinkMetadata,
+    ) -> Result<ShortId, AppError> {
+        self.check_domain(&full_url)?;
+        if self.dedup
+            && alias.is_none()
+            && ttl.is_none()
+            && password.is_none()
+            && metadata == LinkMetadata::default()
+            && let Some(id) = self.repository.find_by_url(full_url.as_str()).await?
+        {
+            return ShortId::parse(&id);
+        }
+        let id = match alias {
+            Some(alias) => self.check_alias(alias).await?,
+            None => self.generate_free_id(full_url.as_str()).await?,
+        };
+        let created_at = self.clock.now();
+        let expires_at = ttl.map(|ttl| created_at + ttl);
+        let password_hash = password.map(password::hash).transpose()?;
+        let url = full_url.as_str().to_owned();
+        let saved = self
+            .repository
+            .save_if_absent(
+                full_url,
+                id.clone(),
+                created_at,
+                expires_at,
+                password_hash,
+                metadata,
+            )
             .await?;
 
-        Ok(id)
+        match (saved, alias) {
+            (true, _) => {
+                events::emit_created(&self.events, id.as_str(), &url);
+                Ok(id)
+            }
+            (false, Some(_)) => Err(AppError::AliasTaken),
+            (false, None) => Err(AppError::IdGenerationFailed),
+        }
+    }
+
+    /// Parses and normalizes `full_url` exactly as creation would store it.
+    pub fn parse_url(&self, full_url: &str) -> Result<ValidUrl, AppError> {
+        let parse = |full_url: &str| match self.preserve_input {
+            true => self.url_policy.parse_verbatim(full_url),
+            false => self.url_policy.parse(full_url),
+        };
+
+        match parse(full_url) {
+            Err(AppError::UrlParseError(_))
+                if self.assume_https
+                    && Url::parse(full_url) == Err(url::ParseError::RelativeUrlWithoutBase) =>
+            {
+                parse(&format!("https://{full_url}"))
+            }
+            result => result,
+        }
+    }
+
+    pub fn url_policy(&self) -> &UrlPolicy {
+        &self.url_policy
+    }
+
+    fn check_domain(&self, full_url: &ValidUrl) -> Result<(), AppError> {
+        self.url_policy.check_domain(full_url)
+    }
+
+    async fn check_alias(&self, alias: &str) -> Result<ShortId, AppError> {
+        let alias = self.normalize(validate_alias(alias)?);
+        if self.is_reserved(&alias) {
+            return Err(AppError::ReservedId);
+        }
+        let alias = self.append_checksum(alias)?;
+        if self.repository.exists(&alias).await? {
+            return Err(AppError::AliasTaken);
+        }
+
+        Ok(alias)
+    }
+
+    fn normalize(&self, id: ShortId) -> ShortId {
+        match self.case_insensitive {
+            true => id.to_ascii_lowercase(),
+            false => id,
+        }
+    }
+
+    fn append_checksum(&self, alias: ShortId) -> Result<ShortId, AppError> {
+        match &self.checksum {
+            Some(checksum) => checksum.append(&alias).ok_or(AppError::InvalidAlias),
+            None => Ok(alias),
+        }
+    }
+
+    fn is_reserved(&self, id: &str) -> bool {
+        self.reserved_ids.iter().any(|reserved| reserved == id)
+    }
+
+    async fn generate_free_id(&self, full_url: &str) -> Result<ShortId, AppError> {
+        for attempt in 0..self.max_attempts {
+            let seed = match attempt {
+                0 => full_url.to_owned(),
+                _ => format!("{full_url}#{attempt}"),
+            };
+            let id = self.normalize(self.id_provider.provide(Some(&seed)));
+            if !self.is_reserved(&id) && !self.repository.exists(&id).await? {
+                return Ok(id);
+            }
+        }
+
+        Err(AppError::IdGenerationFailed)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
-
+    use chrono::TimeZone;
     use dashmap::DashMap;
 
     use crate::{
         adapters::in_memory::InMemoryRepository,
-        id_provider::{FakeIdProvider, MockIdProvider, NanoIdProvider},
+        app::{
+            domain::{DEFAULT_MAX_URL_LENGTH, StoredUrl},
+            events::testing::{Event, RecordingSink},
+        },
+        clock::FakeClock,
+        id_provider::{FakeIdProvider, HashIdProvider, MockIdProvider, NanoIdProvider},
     };
 
     use super::*;
@@ -63,28 +395,30 @@ mod tests {
         let create_short_url = CreateShortUrlCommand::new(id_provider, repository);
 
         // When
-        let result = create_short_url.execute("https://www.google.com").await;
+        let result = create_short_url
+            .execute("https://www.google.com", None, None)
+            .await;
 
         // Then
-        assert_ne!(result, Ok("".to_owned()));
+        assert_eq!(result.unwrap(), "123");
     }
 
     #[tokio::test]
     async fn get_two_different_short_urls() {
         // Given
-        let id_provider = NanoIdProvider;
+        let id_provider = NanoIdProvider::default();
         let store = Arc::new(DashMap::new());
         let repository = InMemoryRepository::new(store);
         let create_short_url = CreateShortUrlCommand::new(id_provider, repository);
 
         // When
         let result1 = create_short_url
-            .execute("https://www.google.com")
+            .execute("https://www.google.com", None, None)
             .await
             .unwrap();
 
         let result2 = create_short_url
-            .execute("https://www.example.com")
+            .execute("https://www.example.com", None, None)
             .await
             .unwrap();
 
@@ -95,21 +429,21 @@ mod tests {
     #[tokio::test]
     async fn after_save_store_should_have_one_item() {
         // Given
-        let id_provider = NanoIdProvider;
-        let store: Arc<DashMap<String, String>> = Arc::new(DashMap::new());
+        let id_provider = NanoIdProvider::default();
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
         let repository = InMemoryRepository::new(store.clone());
         let create_short_url = CreateShortUrlCommand::new(id_provider, repository);
 
         // When
         let id = create_short_url
-            .execute("https://www.google.com")
+            .execute("https://www.google.com", None, None)
             .await
             .unwrap();
 
         // Then
         assert_eq!(store.len(), 1);
-        let full_url = store.get(&id).unwrap();
-        assert_eq!(full_url.value(), "https://www.google.com/");
+        let record = store.get(id.as_str()).unwrap();
+        assert_eq!(record.full_url, "https://www.google.com/");
     }
 
     #[tokio::test]
@@ -118,17 +452,1063 @@ mod tests {
         let mut stub_id_provider = MockIdProvider::new();
         stub_id_provider
             .expect_provide()
-            .returning(|| "123".to_owned())
+            .returning(|_| ShortId::parse("123").unwrap())
+            .times(1);
+
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_exists().returning(|_| Ok(false)).times(1);
+        mock_repo
+            .expect_save_if_absent()
+            .returning(|_, _, _, _, _, _| Ok(true))
+            .times(1);
+        let sut = CreateShortUrlCommand::new(stub_id_provider, mock_repo);
+
+        // When
+        let result = sut.execute("https://www.google.com", None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "123");
+    }
+
+    #[tokio::test]
+    async fn create_with_alias() {
+        // Given
+        let mut stub_id_provider = MockIdProvider::new();
+        stub_id_provider.expect_provide().never();
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(stub_id_provider, repository);
+
+        // When
+        let result = sut
+            .execute("https://www.google.com", Some("my-brand"), None)
+            .await;
+
+        // Then
+        assert_eq!(result.unwrap(), "my-brand");
+        assert_eq!(
+            store.get("my-brand").unwrap().full_url,
+            "https://www.google.com/"
+        );
+    }
+
+    #[tokio::test]
+    async fn checksum_is_appended_to_aliases() {
+        // Given
+        let mut stub_id_provider = MockIdProvider::new();
+        stub_id_provider.expect_provide().never();
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let checksum = Checksum::new(nanoid::alphabet::SAFE.to_vec()).unwrap();
+        let sut = CreateShortUrlCommand::new(stub_id_provider, InMemoryRepository::new(store))
+            .with_checksum(Some(checksum.clone()));
+
+        // When
+        let id = sut
+            .execute("https://www.google.com", Some("my-brand"), None)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(id.len(), "my-brand".len() + 1);
+        assert!(id.starts_with("my-brand"));
+        assert!(checksum.verify(&id));
+    }
+
+    #[tokio::test]
+    async fn create_with_taken_alias() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert(
+            "my-brand".to_owned(),
+            StoredUrl::new("https://www.example.com/"),
+        );
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), repository);
+
+        // When
+        let result = sut
+            .execute("https://www.google.com", Some("my-brand"), None)
+            .await;
+
+        // Then
+        assert_eq!(result, Err(AppError::AliasTaken));
+        assert_eq!(
+            store.get("my-brand").unwrap().full_url,
+            "https://www.example.com/"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn concurrent_creates_of_same_alias_have_one_winner() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let sut = Arc::new(CreateShortUrlCommand::new(
+            NanoIdProvider::default(),
+            InMemoryRepository::new(store.clone()),
+        ));
+
+        // When
+        let tasks: Vec<_> = (0..64)
+            .map(|i| {
+                let sut = sut.clone();
+                tokio::spawn(async move {
+                    sut.execute(&format!("https://example.com/{i}"), Some("race"), None)
+                        .await
+                })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for task in tasks {
+            results.push(task.await.unwrap());
+        }
+
+        // Then
+        let winners = results.iter().filter(|result| result.is_ok()).count();
+        assert_eq!(winners, 1);
+        assert!(
+            results
+                .iter()
+                .filter_map(|result| result.as_ref().err())
+                .all(|e| *e == AppError::AliasTaken)
+        );
+        assert_eq!(store.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn lost_alias_race_is_reported_as_taken() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_exists().returning(|_| Ok(false));
+        mock_repo
+            .expect_save_if_absent()
+            .returning(|_, _, _, _, _, _| Ok(false))
             .times(1);
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo);
+
+        // When
+        let result = sut
+            .execute("https://www.google.com", Some("race"), None)
+            .await;
+
+        // Then
+        assert_eq!(result, Err(AppError::AliasTaken));
+    }
+
+    #[tokio::test]
+    async fn create_with_invalid_alias() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_exists().never();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo);
+
+        // When
+        let empty = sut.execute("https://www.google.com", Some(""), None).await;
+        let bad_chars = sut
+            .execute("https://www.google.com", Some("my brand!"), None)
+            .await;
+        let too_long = sut
+            .execute("https://www.google.com", Some(&"a".repeat(33)), None)
+            .await;
+
+        // Then
+        assert_eq!(empty, Err(AppError::InvalidAlias));
+        assert_eq!(bad_chars, Err(AppError::InvalidAlias));
+        assert_eq!(too_long, Err(AppError::InvalidAlias));
+    }
+
+    #[tokio::test]
+    async fn reject_reserved_alias() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_exists().never();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo);
+
+        // When
+        let health = sut
+            .execute("https://www.google.com", Some("health"), None)
+            .await;
+        let admin = sut
+            .execute("https://www.google.com", Some("admin"), None)
+            .await;
+
+        // Then
+        assert_eq!(health, Err(AppError::ReservedId));
+        assert_eq!(admin, Err(AppError::ReservedId));
+    }
+
+    #[tokio::test]
+    async fn reject_configured_reserved_alias() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo)
+            .with_reserved_ids(vec!["login".to_owned()]);
+
+        // When
+        let result = sut
+            .execute("https://www.google.com", Some("login"), None)
+            .await;
+
+        // Then
+        assert_eq!(result, Err(AppError::ReservedId));
+    }
+
+    #[tokio::test]
+    async fn reject_blocked_domains_and_subdomains() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo)
+            .with_domain_blocklist(HashSet::from(["www.Evil.com".to_owned()]));
+
+        // When
+        let domain = sut.execute("https://evil.com/login", None, None).await;
+        let www = sut.execute("https://WWW.EVIL.COM", None, None).await;
+        let subdomain = sut.execute("https://sub.evil.com", None, None).await;
+        let dry_run = sut.dry_run("http://a.b.evil.com.", None).await;
+
+        // Then
+        assert_eq!(domain, Err(AppError::BlockedDomain));
+        assert_eq!(www, Err(AppError::BlockedDomain));
+        assert_eq!(subdomain, Err(AppError::BlockedDomain));
+        assert_eq!(dry_run, Err(AppError::BlockedDomain));
+    }
+
+    #[tokio::test]
+    async fn allow_domains_outside_blocklist() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_domain_blocklist(HashSet::from(["evil.com".to_owned()]));
+
+        // When
+        let result = sut.execute("https://notevil.com", None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "123");
+        assert_eq!(store.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn allowlist_accepts_listed_domains_and_subdomains() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), repository)
+            .with_domain_allowlist(Some(HashSet::from(["example.com".to_owned()])));
+
+        // When
+        let domain = sut.execute("https://example.com", None, None).await;
+        let subdomain = sut.execute("https://a.example.com/path", None, None).await;
+        let www = sut.execute("https://www.EXAMPLE.com", None, None).await;
+
+        // Then
+        assert!(domain.is_ok());
+        assert!(subdomain.is_ok());
+        assert!(www.is_ok());
+        assert_eq!(store.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn allowlist_rejects_other_domains() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo)
+            .with_domain_allowlist(Some(HashSet::from(["example.com".to_owned()])));
+
+        // When
+        let other = sut.execute("https://other.com", None, None).await;
+        let lookalike = sut.execute("https://notexample.com", None, None).await;
+
+        // Then
+        assert_eq!(other, Err(AppError::BlockedDomain));
+        assert_eq!(lookalike, Err(AppError::BlockedDomain));
+    }
+
+    #[tokio::test]
+    async fn idn_hosts_are_stored_as_punycode() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository);
+
+        // When
+        let id = sut
+            .execute("http://München.de/straße?q=ü", None, None)
+            .await
+            .unwrap();
+
+        // Then
+        let record = store.get(id.as_str()).unwrap();
+        assert_eq!(
+            record.full_url,
+            "http://xn--mnchen-3ya.de/stra%C3%9Fe?q=%C3%BC"
+        );
+    }
+
+    #[tokio::test]
+    async fn unicode_blocklist_entries_match_punycode_hosts() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo)
+            .with_domain_blocklist(HashSet::from(["münchen.de".to_owned()]));
+
+        // When
+        let unicode = sut.execute("http://www.münchen.de", None, None).await;
+        let punycode = sut
+            .execute("http://sub.xn--mnchen-3ya.de", None, None)
+            .await;
+
+        // Then
+        assert_eq!(unicode, Err(AppError::BlockedDomain));
+        assert_eq!(punycode, Err(AppError::BlockedDomain));
+    }
+
+    #[tokio::test]
+    async fn availability_reflects_keyspace() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert(
+            "taken".to_owned(),
+            StoredUrl {
+                disabled: true,
+                ..StoredUrl::new("https://www.google.com/")
+            },
+        );
+        let sut =
+            CreateShortUrlCommand::new(NanoIdProvider::default(), InMemoryRepository::new(store));
+
+        // When
+        let taken = sut.is_available("taken").await;
+        let free = sut.is_available("free").await;
+        let reserved = sut.is_available("admin").await;
+        let invalid = sut.is_available("not valid").await;
+
+        // Then
+        assert_eq!(taken, Ok(false));
+        assert_eq!(free, Ok(true));
+        assert_eq!(reserved, Ok(false));
+        assert_eq!(invalid, Err(AppError::InvalidAlias));
+    }
+
+    #[tokio::test]
+    async fn retry_on_reserved_id() {
+        // Given
+        let mut stub_id_provider = MockIdProvider::new();
+        let mut ids = vec!["free", "metrics"];
+        stub_id_provider
+            .expect_provide()
+            .returning(move |_| ShortId::parse(ids.pop().unwrap()).unwrap())
+            .times(2);
 
         let mut mock_repo = MockCreateShortUrlRepository::new();
-        mock_repo.expect_save().returning(|_, _| Ok(())).times(1);
+        mock_repo
+            .expect_exists()
+            .withf(|id| id == "free")
+            .returning(|_| Ok(false))
+            .times(1);
+        mock_repo
+            .expect_save_if_absent()
+            .withf(|_, id, _, _, _, _| id == "free")
+            .returning(|_, _, _, _, _, _| Ok(true))
+            .times(1);
         let sut = CreateShortUrlCommand::new(stub_id_provider, mock_repo);
 
         // When
-        let result = sut.execute("https://www.google.com").await;
+        let result = sut.execute("https://www.google.com", None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "free");
+    }
+
+    #[tokio::test]
+    async fn retry_on_id_collision() {
+        // Given
+        let mut stub_id_provider = MockIdProvider::new();
+        let mut ids = vec!["free", "taken", "taken"];
+        stub_id_provider
+            .expect_provide()
+            .returning(move |_| ShortId::parse(ids.pop().unwrap()).unwrap())
+            .times(3);
+
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo
+            .expect_exists()
+            .returning(|id| Ok(id == "taken"))
+            .times(3);
+        mock_repo
+            .expect_save_if_absent()
+            .withf(|_, id, _, _, _, _| id == "free")
+            .returning(|_, _, _, _, _, _| Ok(true))
+            .times(1);
+        let sut = CreateShortUrlCommand::new(stub_id_provider, mock_repo);
+
+        // When
+        let result = sut.execute("https://www.google.com", None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "free");
+    }
+
+    #[tokio::test]
+    async fn hash_ids_are_stable_and_retry_on_collision() {
+        // Given
+        let full_url = "https://www.google.com/";
+        let hashed_id = HashIdProvider::default().provide(Some(full_url));
+        let store = Arc::new(DashMap::new());
+        store.insert(
+            hashed_id.to_string(),
+            StoredUrl::new("https://other.example/"),
+        );
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(HashIdProvider::default(), repository);
+
+        // When
+        let first = sut.execute(full_url, None, None).await.unwrap();
+        store.remove(first.as_str());
+        let second = sut.execute(full_url, None, None).await.unwrap();
+
+        // Then
+        assert_ne!(first, hashed_id);
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn give_up_after_max_attempts() {
+        // Given
+        let mut stub_id_provider = MockIdProvider::new();
+        stub_id_provider
+            .expect_provide()
+            .returning(|_| ShortId::parse("taken").unwrap())
+            .times(DEFAULT_MAX_ATTEMPTS);
+
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo
+            .expect_exists()
+            .returning(|_| Ok(true))
+            .times(DEFAULT_MAX_ATTEMPTS);
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(stub_id_provider, mock_repo);
+
+        // When
+        let result = sut.execute("https://www.google.com", None, None).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::IdGenerationFailed));
+    }
+
+    #[tokio::test]
+    async fn give_up_after_configured_attempts() {
+        // Given
+        let mut stub_id_provider = MockIdProvider::new();
+        stub_id_provider
+            .expect_provide()
+            .returning(|_| ShortId::parse("taken").unwrap())
+            .times(2);
+
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_exists().returning(|_| Ok(true)).times(2);
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(stub_id_provider, mock_repo).with_max_attempts(2);
+
+        // When
+        let result = sut.execute("https://www.google.com", None, None).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::IdGenerationFailed));
+    }
+
+    #[tokio::test]
+    async fn create_with_ttl_sets_expiry() {
+        // Given
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let expected_expiry = now + Duration::seconds(60);
+
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_exists().returning(|_| Ok(false));
+        mock_repo
+            .expect_save_if_absent()
+            .withf(move |_, _, _, expires_at, _, _| *expires_at == Some(expected_expiry))
+            .returning(|_, _, _, _, _, _| Ok(true))
+            .times(1);
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), mock_repo)
+            .with_clock(Arc::new(FakeClock::new(now)));
+
+        // When
+        let result = sut
+            .execute("https://www.google.com", None, Some(Duration::seconds(60)))
+            .await;
+
+        // Then
+        assert_eq!(result.unwrap(), "123");
+    }
+
+    #[tokio::test]
+    async fn create_emits_created_event() {
+        // Given
+        let (sink, mut events) = RecordingSink::channel();
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_events(sink);
+
+        // When
+        sut.execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(
+            events.recv().await,
+            Some(Event::Created {
+                id: "123".to_owned(),
+                url: "https://www.google.com/".to_owned(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_create_emits_no_event() {
+        // Given
+        let (sink, mut events) = RecordingSink::channel();
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("taken".to_owned(), StoredUrl::new("https://example.com"));
+        let repository = InMemoryRepository::new(store);
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_events(sink);
+
+        // When
+        let result = sut
+            .execute("https://www.google.com", Some("taken"), None)
+            .await;
+        drop(sut);
+
+        // Then
+        assert_eq!(result, Err(AppError::AliasTaken));
+        assert_eq!(events.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn create_sets_created_at_from_clock() {
+        // Given
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_clock(Arc::new(FakeClock::new(now)));
+
+        // When
+        sut.execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(store.get("123").unwrap().created_at, Some(now));
+    }
+
+    #[tokio::test]
+    async fn reject_unsupported_schemes() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_exists().never();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo);
+
+        // When
+        let ftp = sut.execute("ftp://example.com/file", None, None).await;
+        let javascript = sut.execute("javascript:alert(1)", None, None).await;
+        let data = sut
+            .execute("data:text/html,<script>alert(1)</script>", None, None)
+            .await;
+
+        // Then
+        assert_eq!(ftp, Err(AppError::UnsupportedScheme));
+        assert_eq!(javascript, Err(AppError::UnsupportedScheme));
+        assert_eq!(data, Err(AppError::UnsupportedScheme));
+    }
+
+    #[tokio::test]
+    async fn reject_hostless_urls() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo)
+            .with_allowed_schemes(
+                ["http", "https", "mailto", "file", "urn"]
+                    .map(String::from)
+                    .to_vec(),
+            );
+
+        for input in [
+            "https://",
+            "mailto:foo@bar",
+            "file:///etc/passwd",
+            "urn:isbn:0451450523",
+            "/relative/path",
+            "//example.com/no-scheme",
+            "example.com",
+        ] {
+            // When
+            let result = sut.execute(input, None, None).await;
+
+            // Then
+            assert!(
+                matches!(result, Err(AppError::UrlParseError(_))),
+                "{input} should be rejected, got {result:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn accept_absolute_urls_with_host() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), repository)
+            .with_allowed_schemes(["http", "https", "file"].map(String::from).to_vec());
+
+        for input in [
+            "https://example.com",
+            "http://127.0.0.1:8080/path",
+            "https://[::1]/",
+            "https://user@example.com/?q=1#frag",
+            "file://server/share/doc.txt",
+        ] {
+            // When
+            let result = sut.execute(input, None, None).await;
+
+            // Then
+            assert!(result.is_ok(), "{input} should be accepted, got {result:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn reject_malformed_urls_with_reason() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo);
+
+        // When
+        let relative = sut.execute("not a url", None, None).await;
+        let empty_host = sut.execute("https://", None, None).await;
+        let bad_ipv6 = sut.execute("https://[::1", None, None).await;
+
+        // Then
+        assert_eq!(
+            relative,
+            Err(AppError::UrlParseError(
+                "relative URL without a base".to_owned()
+            ))
+        );
+        assert_eq!(
+            empty_host,
+            Err(AppError::UrlParseError("empty host".to_owned()))
+        );
+        assert_eq!(
+            bad_ipv6,
+            Err(AppError::UrlParseError("invalid IPv6 address".to_owned()))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_url_saves_constructed_url() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository);
+        let mut url = Url::parse("https://www.google.com").unwrap();
+        url.set_path("search");
+        url.query_pairs_mut().append_pair("q", "rust");
+
+        // When
+        let result = sut.execute_url(url, None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "123");
+        assert_eq!(
+            store.get("123").unwrap().full_url,
+            "https://www.google.com/search?q=rust"
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_valid_round_trips_url() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_exists().returning(|_| Ok(false)).times(1);
+        mock_repo
+            .expect_save_if_absent()
+            .withf(|url, id, _, _, _, _| url.as_str() == "https://www.google.com/" && id == "123")
+            .returning(|_, _, _, _, _, _| Ok(true))
+            .times(1);
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), mock_repo);
+        let full_url = ValidUrl::parse("https://www.google.com").unwrap();
+
+        // When
+        let result = sut.execute_valid(full_url, None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "123");
+    }
+
+    #[tokio::test]
+    async fn execute_url_rejects_unsupported_scheme() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo);
+        let url = Url::parse("ftp://example.com/file").unwrap();
+
+        // When
+        let result = sut.execute_url(url, Some("file"), None).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::UnsupportedScheme));
+    }
+
+    #[tokio::test]
+    async fn accept_url_of_max_length() {
+        // Given
+        let full_url = format!("https://example.com/{}", "a".repeat(12));
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_max_url_length(full_url.len());
+
+        // When
+        let result = sut.execute(&full_url, None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "123");
+    }
+
+    #[tokio::test]
+    async fn reject_url_over_max_length() {
+        // Given
+        let full_url = format!("https://example.com/{}", "a".repeat(12));
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_exists().never();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo)
+            .with_max_url_length(full_url.len() - 1);
+
+        // When
+        let result = sut.execute(&full_url, None, None).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::UrlTooLong));
+    }
+
+    #[tokio::test]
+    async fn reject_url_over_default_max_length() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), mock_repo);
+        let full_url = format!("https://example.com/{}", "a".repeat(DEFAULT_MAX_URL_LENGTH));
+
+        // When
+        let result = sut.execute(&full_url, None, None).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::UrlTooLong));
+    }
+
+    #[tokio::test]
+    async fn accept_https_scheme() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository);
+
+        // When
+        let result = sut.execute("https://www.google.com", None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "123");
+        assert_eq!(store.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn accept_configured_schemes() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_allowed_schemes(vec!["ftp".to_owned()]);
+
+        // When
+        let ftp = sut.execute("ftp://example.com/file", None, None).await;
+        let https = sut.execute("https://www.google.com", None, None).await;
+
+        // Then
+        assert_eq!(ftp.unwrap(), "123");
+        assert_eq!(https, Err(AppError::UnsupportedScheme));
+    }
+
+    #[tokio::test]
+    async fn dedup_reuses_id_for_identical_urls() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut =
+            CreateShortUrlCommand::new(NanoIdProvider::default(), repository).with_dedup(true);
+
+        // When
+        let first = sut
+            .execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+        let second = sut
+            .execute("https://www.google.com/", None, None)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(first, second);
+        assert_eq!(store.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn without_dedup_identical_urls_get_distinct_ids() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(NanoIdProvider::default(), repository);
+
+        // When
+        let first = sut
+            .execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+        let second = sut
+            .execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+
+        // Then
+        assert_ne!(first, second);
+        assert_eq!(store.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn dedup_with_mock() {
+        // Given
+        let mut stub_id_provider = MockIdProvider::new();
+        stub_id_provider.expect_provide().never();
+
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo
+            .expect_find_by_url()
+            .withf(|url| url == "https://www.google.com/")
+            .returning(|_| Ok(Some("existing".to_owned())))
+            .times(1);
+        mock_repo.expect_save_if_absent().never();
+        let sut = CreateShortUrlCommand::new(stub_id_provider, mock_repo).with_dedup(true);
+
+        // When
+        let result = sut.execute("https://www.google.com", None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "existing");
+    }
+
+    #[tokio::test]
+    async fn assume_https_prepends_missing_scheme() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_assume_https(true);
+
+        // When
+        let id = sut.execute("example.com", None, None).await.unwrap();
+
+        // Then
+        assert_eq!(
+            store.get(id.as_str()).unwrap().full_url,
+            "https://example.com/"
+        );
+    }
+
+    #[tokio::test]
+    async fn bare_host_is_normalized_by_default() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository);
+
+        // When
+        let id = sut
+            .execute("https://example.com", None, None)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(
+            store.get(id.as_str()).unwrap().full_url,
+            "https://example.com/"
+        );
+    }
+
+    #[tokio::test]
+    async fn preserve_input_keeps_bare_host_as_given() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_preserve_input(true)
+            .with_assume_https(true);
+
+        // When
+        let id = sut
+            .execute("https://example.com", None, None)
+            .await
+            .unwrap();
+        let schemeless = sut.execute("example.org", Some("org"), None).await.unwrap();
+
+        // Then
+        assert_eq!(
+            store.get(id.as_str()).unwrap().full_url,
+            "https://example.com"
+        );
+        assert_eq!(
+            store.get(schemeless.as_str()).unwrap().full_url,
+            "https://example.org"
+        );
+    }
+
+    #[tokio::test]
+    async fn without_assume_https_missing_scheme_fails() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository);
+
+        // When
+        let result = sut.execute("example.com", None, None).await;
+
+        // Then
+        assert_eq!(
+            result,
+            Err(AppError::UrlParseError(
+                "relative URL without a base".to_owned()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn assume_https_keeps_other_parse_errors() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_assume_https(true);
+
+        // When
+        let result = sut.execute("https://exa mple.com", None, None).await;
+
+        // Then
+        assert_eq!(
+            result,
+            Err(AppError::UrlParseError(
+                "invalid international domain name".to_owned()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn case_insensitive_stores_lowercase_ids() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("AbC".to_owned()), repository)
+            .with_case_insensitive(true);
+
+        // When
+        let generated = sut.execute("https://www.google.com", None, None).await;
+        let aliased = sut
+            .execute("https://www.rust-lang.org", Some("My-Brand"), None)
+            .await;
+        let taken = sut
+            .execute("https://www.example.com", Some("MY-BRAND"), None)
+            .await;
+
+        // Then
+        assert_eq!(generated.unwrap(), "abc");
+        assert_eq!(aliased.unwrap(), "my-brand");
+        assert_eq!(taken, Err(AppError::AliasTaken));
+        assert!(store.contains_key("abc"));
+        assert!(store.contains_key("my-brand"));
+    }
+
+    #[tokio::test]
+    async fn case_sensitive_keeps_ids_as_given() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("AbC".to_owned()), repository);
+
+        // When
+        let generated = sut.execute("https://www.google.com", None, None).await;
+        let aliased = sut
+            .execute("https://www.rust-lang.org", Some("My-Brand"), None)
+            .await;
+        let other_case = sut
+            .execute("https://www.example.com", Some("my-brand"), None)
+            .await;
+
+        // Then
+        assert_eq!(generated.unwrap(), "AbC");
+        assert_eq!(aliased.unwrap(), "My-Brand");
+        assert_eq!(other_case.unwrap(), "my-brand");
+        assert_eq!(store.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn dry_run_validates_without_saving() {
+        // Given
+        let mut mock_repo = MockCreateShortUrlRepository::new();
+        mock_repo.expect_save_if_absent().never();
+        mock_repo.expect_exists().returning(|_| Ok(false));
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), mock_repo);
+
+        // When
+        let generated = sut.dry_run("https://www.google.com", None).await;
+        let aliased = sut
+            .dry_run("https://www.google.com", Some("my-brand"))
+            .await;
+        let reserved = sut.dry_run("https://www.google.com", Some("admin")).await;
+        let invalid = sut.dry_run("ftp://www.google.com", None).await;
+
+        // Then
+        let (full_url, id) = generated.unwrap();
+        assert_eq!(full_url.as_str(), "https://www.google.com/");
+        assert_eq!(id, "123");
+        assert_eq!(aliased.unwrap().1, "my-brand");
+        assert_eq!(reserved, Err(AppError::ReservedId));
+        assert_eq!(invalid, Err(AppError::UnsupportedScheme));
+    }
+
+    #[tokio::test]
+    async fn create_with_password_stores_hash() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("open".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = CreateShortUrlCommand::new(FakeIdProvider::new("123".to_owned()), repository)
+            .with_dedup(true);
+
+        // When
+        let result = sut
+            .execute_with_password("https://www.google.com", None, None, Some("secret"))
+            .await;
 
         // Then
-        assert_eq!(result, Ok("123".to_owned()));
+        assert_eq!(result.unwrap(), "123");
+        let password_hash = store.get("123").unwrap().password_hash.clone().unwrap();
+        assert_ne!(password_hash, "secret");
+        assert!(password::verify("secret", &password_hash));
     }
 }
@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+
+use crate::{app::tombstones::Tombstones, error::AppError};
+
+#[mockall::automock]
+#[async_trait]
+pub trait DeleteShortUrlRepository {
+    async fn delete(&self, id: &str) -> Result<(), AppError>;
+}
+
+pub struct DeleteShortUrlCommand<R>
+where
+    R: DeleteShortUrlRepository,
+{
+    repository: R,
+    tombstones: Option<Tombstones>,
+}
+
+impl<R> DeleteShortUrlCommand<R>
+where
+    R: DeleteShortUrlRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            tombstones: None,
+        }
+    }
+
+    pub fn with_tombstones(mut self, tombstones: Option<Tombstones>) -> Self {
+        self.tombstones = tombstones;
+        self
+    }
+
+    pub async fn execute(&self, id: &str) -> Result<(), AppError> {
+        self.repository.delete(id).await?;
+        if let Some(tombstones) = &self.tombstones {
+            tombstones.bury(id);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::{adapters::in_memory::InMemoryRepository, app::domain::StoredUrl};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn delete_existing_id() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = DeleteShortUrlCommand::new(repository);
+
+        // When
+        let result = sut.execute("123").await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn delete_missing_id() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = DeleteShortUrlCommand::new(repository);
+
+        // When
+        let result = sut.execute("456").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn delete_with_mock() {
+        // Given
+        let mut mock_repo = MockDeleteShortUrlRepository::new();
+        mock_repo
+            .expect_delete()
+            .withf(|id| id == "123")
+            .returning(|_| Ok(()))
+            .times(1);
+        let sut = DeleteShortUrlCommand::new(mock_repo);
+
+        // When
+        let result = sut.execute("123").await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn delete_buries_only_existing_ids() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let tombstones = Tombstones::new(None);
+        let sut = DeleteShortUrlCommand::new(InMemoryRepository::new(store))
+            .with_tombstones(Some(tombstones.clone()));
+
+        // When
+        sut.execute("123").await.unwrap();
+        sut.execute("456").await.unwrap_err();
+
+        // Then
+        assert!(tombstones.is_buried("123"));
+        assert!(!tombstones.is_buried("456"));
+    }
+}
@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::{
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository, update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, ValidUrl},
+    },
+    clock::{Clock, SystemClock},
+    error::AppError,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    #[default]
+    Skip,
+    Overwrite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportOutcome {
+    Imported,
+    Skipped,
+}
+
+pub struct ImportUrlsCommand<R>
+where
+    R: CreateShortUrlRepository + UpdateShortUrlRepository,
+{
+    repository: R,
+    clock: Arc<dyn Clock>,
+}
+
+impl<R> ImportUrlsCommand<R>
+where
+    R: CreateShortUrlRepository + UpdateShortUrlRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub async fn execute(
+        &self,
+        id: &str,
+        full_url: &str,
+        mode: ImportMode,
+    ) -> Result<ImportOutcome, AppError> {
+        let id = ShortId::parse(id)?;
+        let full_url = ValidUrl::parse(full_url)?;
+
+        if self.repository.exists(&id).await? {
+            return match mode {
+                ImportMode::Skip => Ok(ImportOutcome::Skipped),
+                ImportMode::Overwrite => {
+                    self.repository.update(&id, full_url.into_string()).await?;
+                    Ok(ImportOutcome::Imported)
+                }
+            };
+        }
+
+        self.repository
+            .save(
+                full_url,
+                id,
+                self.clock.now(),
+                None,
+                None,
+                LinkMetadata::default(),
+            )
+            .await?;
+
+        Ok(ImportOutcome::Imported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::{adapters::in_memory::InMemoryRepository, app::domain::StoredUrl};
+
+    use super::*;
+
+    fn get_command() -> (
+        ImportUrlsCommand<InMemoryRepository>,
+        Arc<DashMap<String, StoredUrl>>,
+    ) {
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+
+        (
+            ImportUrlsCommand::new(InMemoryRepository::new(store.clone())),
+            store,
+        )
+    }
+
+    #[tokio::test]
+    async fn imports_new_id() {
+        // Given
+        let (sut, store) = get_command();
+
+        // When
+        let result = sut
+            .execute("456", "https://www.rust-lang.org", ImportMode::Skip)
+            .await;
+
+        // Then
+        assert_eq!(result, Ok(ImportOutcome::Imported));
+        assert_eq!(
+            store.get("456").unwrap().full_url,
+            "https://www.rust-lang.org/"
+        );
+    }
+
+    #[tokio::test]
+    async fn skip_mode_keeps_existing_id() {
+        // Given
+        let (sut, store) = get_command();
+
+        // When
+        let result = sut
+            .execute("123", "https://www.rust-lang.org", ImportMode::Skip)
+            .await;
+
+        // Then
+        assert_eq!(result, Ok(ImportOutcome::Skipped));
+        assert_eq!(
+            store.get("123").unwrap().full_url,
+            "https://www.google.com/"
+        );
+    }
+
+    #[tokio::test]
+    async fn overwrite_mode_replaces_existing_id() {
+        // Given
+        let (sut, store) = get_command();
+
+        // When
+        let result = sut
+            .execute("123", "https://www.rust-lang.org", ImportMode::Overwrite)
+            .await;
+
+        // Then
+        assert_eq!(result, Ok(ImportOutcome::Imported));
+        assert_eq!(
+            store.get("123").unwrap().full_url,
+            "https://www.rust-lang.org/"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_entries() {
+        // Given
+        let (sut, store) = get_command();
+
+        // When
+        let invalid_id = sut
+            .execute("bad.id", "https://www.rust-lang.org", ImportMode::Skip)
+            .await;
+        let invalid_url = sut.execute("456", "not a url", ImportMode::Skip).await;
+
+        // Then
+        assert_eq!(invalid_id, Err(AppError::InvalidId));
+        assert_eq!(
+            invalid_url,
+            Err(AppError::UrlParseError(
+                "relative URL without a base".to_owned()
+            ))
+        );
+        assert_eq!(store.len(), 1);
+    }
+}
@@ -1 +1,9 @@
 pub mod create_short_url;
+pub mod delete_short_url;
+pub mod import_urls;
+pub mod patch_short_url;
+pub mod purge_expired;
+pub mod record_access;
+pub mod record_click;
+pub mod set_enabled;
+pub mod update_short_url;
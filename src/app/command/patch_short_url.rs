@@ -0,0 +1,246 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    app::url_policy::UrlPolicy,
+    clock::{Clock, SystemClock},
+    error::AppError,
+};
+
+/// Fields to change on a stored link. `None` leaves a field untouched;
+/// `expires_at: Some(None)` clears the expiry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UrlPatch {
+    pub full_url: Option<String>,
+    pub disabled: Option<bool>,
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+}
+
+#[mockall::automock]
+#[async_trait]
+pub trait PatchShortUrlRepository {
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError>;
+}
+
+/// A partial update as requested by a caller. `ttl: Some(None)` removes the
+/// expiry; `ttl: Some(Some(_))` sets it relative to now.
+#[derive(Debug, Clone, Default)]
+pub struct ShortUrlPatch {
+    pub url: Option<String>,
+    pub disabled: Option<bool>,
+    pub ttl: Option<Option<Duration>>,
+}
+
+pub struct PatchShortUrlCommand<R>
+where
+    R: PatchShortUrlRepository,
+{
+    repository: R,
+    url_policy: UrlPolicy,
+    clock: Arc<dyn Clock>,
+}
+
+impl<R> PatchShortUrlCommand<R>
+where
+    R: PatchShortUrlRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            url_policy: UrlPolicy::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Checks new targets against the same rules as creation.
+    pub fn with_url_policy(mut self, url_policy: UrlPolicy) -> Self {
+        self.url_policy = url_policy;
+        self
+    }
+
+    pub fn with_allowed_schemes(mut self, allowed_schemes: Vec<String>) -> Self {
+        self.url_policy = self.url_policy.with_allowed_schemes(allowed_schemes);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub async fn execute(&self, id: &str, patch: ShortUrlPatch) -> Result<(), AppError> {
+        let full_url = patch
+            .url
+            .map(|url| {
+                let url = self.url_policy.parse(&url)?;
+                self.url_policy.check_domain(&url)?;
+                Ok::<_, AppError>(url.into_string())
+            })
+            .transpose()?;
+        let now = self.clock.now();
+        let expires_at = patch.ttl.map(|ttl| ttl.map(|ttl| now + ttl));
+
+        self.repository
+            .patch(
+                id,
+                UrlPatch {
+                    full_url,
+                    disabled: patch.disabled,
+                    expires_at,
+                },
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::TimeZone;
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository, app::domain::StoredUrl, clock::FakeClock,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn patch_only_disabled_keeps_url() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let sut = PatchShortUrlCommand::new(InMemoryRepository::new(store.clone()));
+
+        // When
+        let result = sut
+            .execute(
+                "123",
+                ShortUrlPatch {
+                    disabled: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+        let record = store.get("123").unwrap();
+        assert!(record.disabled);
+        assert_eq!(record.full_url, "https://www.google.com/");
+        assert_eq!(record.expires_at, None);
+    }
+
+    #[tokio::test]
+    async fn patch_ttl_extends_and_clears_expiry() {
+        // Given
+        let now = Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap();
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert(
+            "123".to_owned(),
+            StoredUrl {
+                expires_at: Some(now + Duration::minutes(1)),
+                ..StoredUrl::new("https://www.google.com/")
+            },
+        );
+        let sut = PatchShortUrlCommand::new(InMemoryRepository::new(store.clone()))
+            .with_clock(Arc::new(FakeClock::new(now)));
+
+        // When
+        sut.execute(
+            "123",
+            ShortUrlPatch {
+                ttl: Some(Some(Duration::hours(1))),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let extended = store.get("123").unwrap().expires_at;
+        sut.execute(
+            "123",
+            ShortUrlPatch {
+                ttl: Some(None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let cleared = store.get("123").unwrap().expires_at;
+
+        // Then
+        assert_eq!(extended, Some(now + Duration::hours(1)));
+        assert_eq!(cleared, None);
+    }
+
+    #[tokio::test]
+    async fn patch_validates_url() {
+        // Given
+        let mut mock_repo = MockPatchShortUrlRepository::new();
+        mock_repo.expect_patch().never();
+        let sut = PatchShortUrlCommand::new(mock_repo);
+
+        // When
+        let result = sut
+            .execute(
+                "123",
+                ShortUrlPatch {
+                    url: Some("javascript:alert(1)".to_owned()),
+                    disabled: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Then
+        assert_eq!(result, Err(AppError::UnsupportedScheme));
+    }
+
+    #[tokio::test]
+    async fn patch_rejects_blocked_domain() {
+        // Given
+        let mut mock_repo = MockPatchShortUrlRepository::new();
+        mock_repo.expect_patch().never();
+        let sut = PatchShortUrlCommand::new(mock_repo).with_url_policy(
+            UrlPolicy::default().with_domain_blocklist(HashSet::from(["evil.com".to_owned()])),
+        );
+
+        // When
+        let result = sut
+            .execute(
+                "123",
+                ShortUrlPatch {
+                    url: Some("https://www.evil.com/login".to_owned()),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Then
+        assert_eq!(result, Err(AppError::BlockedDomain));
+    }
+
+    #[tokio::test]
+    async fn patch_missing_id() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let sut = PatchShortUrlCommand::new(InMemoryRepository::new(store));
+
+        // When
+        let result = sut
+            .execute(
+                "123",
+                ShortUrlPatch {
+                    disabled: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+    }
+}
@@ -0,0 +1,214 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{
+    clock::{Clock, SystemClock},
+    error::AppError,
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait PurgeExpiredRepository {
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError>;
+}
+
+pub struct PurgeExpiredCommand<R>
+where
+    R: PurgeExpiredRepository,
+{
+    repository: R,
+    clock: Arc<dyn Clock>,
+}
+
+impl<R> PurgeExpiredCommand<R>
+where
+    R: PurgeExpiredRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub async fn execute(&self) -> Result<usize, AppError> {
+        self.repository.purge_expired(self.clock.now()).await
+    }
+}
+
+/// Purges expired links every `interval` until the returned handle is
+/// aborted. A failed sweep is logged and retried on the next tick.
+pub fn start_expiry_sweeper<R>(
+    repository: R,
+    interval: Duration,
+    clock: Arc<dyn Clock>,
+) -> JoinHandle<()>
+where
+    R: PurgeExpiredRepository + Send + Sync + 'static,
+{
+    let command = PurgeExpiredCommand::new(repository).with_clock(clock);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match command.execute().await {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!("purged {} expired links", purged),
+                Err(e) => tracing::warn!("expiry sweep failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use chrono::TimeZone;
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository, app::domain::StoredUrl, clock::FakeClock,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn purges_only_expired_entries() {
+        // Given
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        for (id, expires_at) in [
+            ("past", Some(now - chrono::Duration::hours(1))),
+            ("deadline", Some(now)),
+            ("future", Some(now + chrono::Duration::hours(1))),
+            ("forever", None),
+        ] {
+            store.insert(
+                id.to_owned(),
+                StoredUrl {
+                    expires_at,
+                    ..StoredUrl::new("https://www.google.com/")
+                },
+            );
+        }
+        let sut = PurgeExpiredCommand::new(InMemoryRepository::new(store.clone()))
+            .with_clock(Arc::new(FakeClock::new(now)));
+
+        // When
+        let purged = sut.execute().await;
+        let purged_again = sut.execute().await;
+
+        // Then
+        assert_eq!(purged, Ok(2));
+        assert_eq!(purged_again, Ok(0));
+        assert!(!store.contains_key("past"));
+        assert!(!store.contains_key("deadline"));
+        assert!(store.contains_key("future"));
+        assert!(store.contains_key("forever"));
+    }
+
+    #[tokio::test]
+    async fn passes_clock_time_to_repository() {
+        // Given
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let mut mock_repo = MockPurgeExpiredRepository::new();
+        mock_repo
+            .expect_purge_expired()
+            .withf(move |at| *at == now)
+            .returning(|_| Ok(3))
+            .times(1);
+        let sut = PurgeExpiredCommand::new(mock_repo).with_clock(Arc::new(FakeClock::new(now)));
+
+        // When
+        let result = sut.execute().await;
+
+        // Then
+        assert_eq!(result, Ok(3));
+    }
+
+    #[tokio::test]
+    async fn sweeper_purges_once_clock_passes_expiry() {
+        // Given
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let clock = FakeClock::new(now);
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert(
+            "123".to_owned(),
+            StoredUrl {
+                expires_at: Some(now + chrono::Duration::hours(1)),
+                ..StoredUrl::new("https://www.google.com/")
+            },
+        );
+        let sweeper = start_expiry_sweeper(
+            InMemoryRepository::new(store.clone()),
+            Duration::from_millis(10),
+            Arc::new(clock.clone()),
+        );
+
+        // When
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let before_expiry = store.contains_key("123");
+        clock.advance(chrono::Duration::hours(1));
+        let removed = tokio::time::timeout(Duration::from_secs(1), async {
+            while store.contains_key("123") {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+        sweeper.abort();
+
+        // Then
+        assert!(before_expiry);
+        assert!(removed.is_ok());
+    }
+
+    #[tokio::test]
+    async fn sweeper_keeps_running_after_errors() {
+        // Given
+        struct FailingRepository {
+            calls: Arc<AtomicUsize>,
+        }
+
+        #[async_trait]
+        impl PurgeExpiredRepository for FailingRepository {
+            async fn purge_expired(&self, _now: DateTime<Utc>) -> Result<usize, AppError> {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                Err(AppError::RepositoryError("db down".to_owned()))
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let sweeper = start_expiry_sweeper(
+            FailingRepository {
+                calls: calls.clone(),
+            },
+            Duration::from_millis(5),
+            Arc::new(SystemClock),
+        );
+
+        // When
+        let retried = tokio::time::timeout(Duration::from_secs(1), async {
+            while calls.load(Ordering::SeqCst) < 3 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+        let finished = sweeper.is_finished();
+        sweeper.abort();
+
+        // Then
+        assert!(retried.is_ok());
+        assert!(!finished);
+    }
+}
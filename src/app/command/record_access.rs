@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::{Clock, SystemClock},
+    error::AppError,
+};
+
+/// Most entries kept per link; adapters drop the oldest beyond it, so a
+/// hot link cannot grow its log without bound.
+pub const MAX_ACCESS_LOG_LENGTH: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessEntry {
+    pub at: DateTime<Utc>,
+    pub user_agent: Option<String>,
+    pub referer: Option<String>,
+}
+
+#[mockall::automock]
+#[async_trait]
+pub trait AccessLogRepository {
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError>;
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError>;
+}
+
+pub struct RecordAccessCommand<R>
+where
+    R: AccessLogRepository,
+{
+    repository: R,
+    clock: Arc<dyn Clock>,
+}
+
+impl<R> RecordAccessCommand<R>
+where
+    R: AccessLogRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub async fn execute(
+        &self,
+        id: &str,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        self.repository
+            .record(id, self.clock.now(), user_agent, referer)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository, app::domain::StoredUrl, clock::FakeClock,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn records_accesses_newest_first() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store);
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let clock = FakeClock::new(start);
+        let sut = RecordAccessCommand::new(repository.clone()).with_clock(Arc::new(clock.clone()));
+
+        // When
+        sut.execute("123", Some("curl/8.0".to_owned()), None)
+            .await
+            .unwrap();
+        clock.advance(chrono::Duration::seconds(1));
+        sut.execute(
+            "123",
+            Some("Mozilla/5.0".to_owned()),
+            Some("https://news.example/".to_owned()),
+        )
+        .await
+        .unwrap();
+        let result = repository.recent("123", 10).await;
+
+        // Then
+        assert_eq!(
+            result,
+            Ok(vec![
+                AccessEntry {
+                    at: start + chrono::Duration::seconds(1),
+                    user_agent: Some("Mozilla/5.0".to_owned()),
+                    referer: Some("https://news.example/".to_owned()),
+                },
+                AccessEntry {
+                    at: start,
+                    user_agent: Some("curl/8.0".to_owned()),
+                    referer: None,
+                },
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn record_access_with_mock() {
+        // Given
+        let mut mock_repo = MockAccessLogRepository::new();
+        mock_repo
+            .expect_record()
+            .withf(|id, _, user_agent, referer| {
+                id == "123" && user_agent.as_deref() == Some("curl/8.0") && referer.is_none()
+            })
+            .returning(|_, _, _, _| Ok(()))
+            .times(1);
+        let sut = RecordAccessCommand::new(mock_repo);
+
+        // When
+        let result = sut.execute("123", Some("curl/8.0".to_owned()), None).await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+    }
+}
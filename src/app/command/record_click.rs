@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+
+use crate::error::AppError;
+
+#[mockall::automock]
+#[async_trait]
+pub trait RecordClickRepository {
+    async fn increment(&self, id: &str) -> Result<u64, AppError>;
+}
+
+pub struct RecordClickCommand<R>
+where
+    R: RecordClickRepository,
+{
+    repository: R,
+}
+
+impl<R> RecordClickCommand<R>
+where
+    R: RecordClickRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    pub async fn execute(&self, id: &str) -> Result<u64, AppError> {
+        self.repository.increment(id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::{adapters::in_memory::InMemoryRepository, app::domain::StoredUrl};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn increments_on_each_click() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store);
+        let sut = RecordClickCommand::new(repository);
+
+        // When
+        let first = sut.execute("123").await;
+        let second = sut.execute("123").await;
+
+        // Then
+        assert_eq!(first, Ok(1));
+        assert_eq!(second, Ok(2));
+    }
+
+    #[tokio::test]
+    async fn record_click_with_mock() {
+        // Given
+        let mut mock_repo = MockRecordClickRepository::new();
+        mock_repo
+            .expect_increment()
+            .withf(|id| id == "123")
+            .returning(|_| Ok(42))
+            .times(1);
+        let sut = RecordClickCommand::new(mock_repo);
+
+        // When
+        let result = sut.execute("123").await;
+
+        // Then
+        assert_eq!(result, Ok(42));
+    }
+}
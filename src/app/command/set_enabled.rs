@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+
+use crate::error::AppError;
+
+#[mockall::automock]
+#[async_trait]
+pub trait SetEnabledRepository {
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError>;
+}
+
+pub struct SetEnabledCommand<R>
+where
+    R: SetEnabledRepository,
+{
+    repository: R,
+}
+
+impl<R> SetEnabledCommand<R>
+where
+    R: SetEnabledRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    pub async fn execute(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        self.repository.set_enabled(id, enabled).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::{domain::StoredUrl, query::get_full_url::GetFullUrlQuery},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn disable_and_enable() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store);
+        let sut = SetEnabledCommand::new(repository.clone());
+        let get_full_url = GetFullUrlQuery::new(repository);
+
+        // When
+        sut.execute("123", false).await.unwrap();
+        let disabled = get_full_url.execute("123").await;
+        sut.execute("123", true).await.unwrap();
+        let enabled = get_full_url.execute("123").await;
+
+        // Then
+        assert_eq!(disabled, Err(AppError::Disabled));
+        assert_eq!(enabled, Ok("https://www.google.com/".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn unknown_id_is_not_found() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = SetEnabledCommand::new(repository);
+
+        // When
+        let result = sut.execute("123", false).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn set_enabled_with_mock() {
+        // Given
+        let mut mock_repo = MockSetEnabledRepository::new();
+        mock_repo
+            .expect_set_enabled()
+            .withf(|id, enabled| id == "123" && !*enabled)
+            .returning(|_, _| Ok(()))
+            .times(1);
+        let sut = SetEnabledCommand::new(mock_repo);
+
+        // When
+        let result = sut.execute("123", false).await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+    }
+}
@@ -0,0 +1,111 @@
+use async_trait::async_trait;
+
+use crate::{app::url_policy::UrlPolicy, error::AppError};
+
+#[mockall::automock]
+#[async_trait]
+pub trait UpdateShortUrlRepository {
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError>;
+}
+
+pub struct UpdateShortUrlCommand<R>
+where
+    R: UpdateShortUrlRepository,
+{
+    repository: R,
+    url_policy: UrlPolicy,
+}
+
+impl<R> UpdateShortUrlCommand<R>
+where
+    R: UpdateShortUrlRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            url_policy: UrlPolicy::default(),
+        }
+    }
+
+    pub fn with_url_policy(mut self, url_policy: UrlPolicy) -> Self {
+        self.url_policy = url_policy;
+        self
+    }
+
+    pub fn with_allowed_schemes(mut self, allowed_schemes: Vec<String>) -> Self {
+        self.url_policy = self.url_policy.with_allowed_schemes(allowed_schemes);
+        self
+    }
+
+    pub async fn execute(&self, id: &str, full_url: &str) -> Result<(), AppError> {
+        let full_url = self.url_policy.parse(full_url)?;
+        self.url_policy.check_domain(&full_url)?;
+        self.repository.update(id, full_url.into_string()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::{adapters::in_memory::InMemoryRepository, app::domain::StoredUrl};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn update_existing_id() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = UpdateShortUrlCommand::new(repository);
+
+        // When
+        let result = sut.execute("123", "https://www.example.com").await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            store.get("123").unwrap().full_url,
+            "https://www.example.com/"
+        );
+    }
+
+    #[tokio::test]
+    async fn update_missing_id() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store.clone());
+        let sut = UpdateShortUrlCommand::new(repository);
+
+        // When
+        let result = sut.execute("123", "https://www.example.com").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn update_validates_url() {
+        // Given
+        let mut mock_repo = MockUpdateShortUrlRepository::new();
+        mock_repo.expect_update().never();
+        let sut = UpdateShortUrlCommand::new(mock_repo);
+
+        // When
+        let invalid = sut.execute("123", "not a url").await;
+        let unsupported = sut.execute("123", "javascript:alert(1)").await;
+
+        // Then
+        assert_eq!(
+            invalid,
+            Err(AppError::UrlParseError(
+                "relative URL without a base".to_owned()
+            ))
+        );
+        assert_eq!(unsupported, Err(AppError::UnsupportedScheme));
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    fmt::{self, Display},
+    ops::Deref,
+};
+
+use chrono::{DateTime, Utc};
+use url::Url;
+
+use crate::error::AppError;
+
+pub(crate) const DEFAULT_MAX_URL_LENGTH: usize = 2048;
+pub(crate) const DEFAULT_ALLOWED_SCHEMES: [&str; 2] = ["http", "https"];
+
+/// A parsed, normalized URL. Internationalized hosts are stored in their
+/// punycode (`xn--`) form; the original Unicode spelling is not preserved.
+/// [`ValidUrl::parse_verbatim_with`] keeps plain ASCII input as typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidUrl(String);
+
+impl ValidUrl {
+    pub fn parse(full_url: &str) -> Result<Self, AppError> {
+        Self::parse_with(full_url, &DEFAULT_ALLOWED_SCHEMES, DEFAULT_MAX_URL_LENGTH)
+    }
+
+    pub fn parse_with<S>(
+        full_url: &str,
+        allowed_schemes: &[S],
+        max_length: usize,
+    ) -> Result<Self, AppError>
+    where
+        S: AsRef<str>,
+    {
+        if full_url.len() > max_length {
+            return Err(AppError::UrlTooLong);
+        }
+
+        Self::from_url(Url::parse(full_url)?, allowed_schemes, max_length)
+    }
+
+    /// Validates like [`ValidUrl::parse_with`] but keeps `full_url` exactly
+    /// as given, e.g. without the trailing slash added to a bare host. Input
+    /// that is not plain visible ASCII falls back to the normalized form, as
+    /// it must fit in a `Location` header.
+    pub fn parse_verbatim_with<S>(
+        full_url: &str,
+        allowed_schemes: &[S],
+        max_length: usize,
+    ) -> Result<Self, AppError>
+    where
+        S: AsRef<str>,
+    {
+        let normalized = Self::parse_with(full_url, allowed_schemes, max_length)?;
+
+        match full_url.bytes().all(|b| b.is_ascii_graphic()) {
+            true => Ok(Self(full_url.to_owned())),
+            false => Ok(normalized),
+        }
+    }
+
+    pub fn from_url<S>(url: Url, allowed_schemes: &[S], max_length: usize) -> Result<Self, AppError>
+    where
+        S: AsRef<str>,
+    {
+        if !allowed_schemes
+            .iter()
+            .any(|scheme| scheme.as_ref() == url.scheme())
+        {
+            return Err(AppError::UnsupportedScheme);
+        }
+
+        // Hostless URLs such as `mailto:` or `file:///` parse fine but make no
+        // sense as redirect targets.
+        if url.host_str().is_none_or(str::is_empty) {
+            return Err(AppError::UrlParseError("empty host".to_owned()));
+        }
+
+        let full_url = String::from(url);
+        if full_url.len() > max_length {
+            return Err(AppError::UrlTooLong);
+        }
+
+        Ok(Self(full_url))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl AsRef<str> for ValidUrl {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for ValidUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+pub(crate) const MAX_ID_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShortId(String);
+
+impl ShortId {
+    pub fn parse(id: &str) -> Result<Self, AppError> {
+        if id.is_empty() || id.len() > MAX_ID_LENGTH || !id.chars().all(Self::is_valid_char) {
+            return Err(AppError::InvalidId);
+        }
+
+        Ok(Self(id.to_owned()))
+    }
+
+    pub fn is_valid_char(c: char) -> bool {
+        c.is_ascii_alphanumeric() || c == '-' || c == '_'
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    pub fn to_ascii_lowercase(&self) -> Self {
+        Self(self.0.to_ascii_lowercase())
+    }
+}
+
+impl Deref for ShortId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ShortId {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for ShortId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for ShortId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Display for ShortId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Human-friendly labels shown alongside a link.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredUrl {
+    pub full_url: String,
+    pub clicks: u64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub disabled: bool,
+    pub password_hash: Option<String>,
+    pub metadata: LinkMetadata,
+}
+
+impl StoredUrl {
+    pub fn new(full_url: impl Into<String>) -> Self {
+        Self {
+            full_url: full_url.into(),
+            clicks: 0,
+            created_at: None,
+            expires_at: None,
+            disabled: false,
+            password_hash: None,
+            metadata: LinkMetadata::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_normalizes_url() {
+        // When
+        let result = ValidUrl::parse("HTTPS://www.Google.com");
+
+        // Then
+        assert_eq!(result.unwrap().as_str(), "https://www.google.com/");
+    }
+
+    #[test]
+    fn parse_rejects_malformed_url() {
+        // When
+        let result = ValidUrl::parse("not a url");
+
+        // Then
+        assert_eq!(
+            result,
+            Err(AppError::UrlParseError(
+                "relative URL without a base".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unsupported_scheme() {
+        // When
+        let ftp = ValidUrl::parse("ftp://example.com/file");
+        let javascript = ValidUrl::parse("javascript:alert(1)");
+
+        // Then
+        assert_eq!(ftp, Err(AppError::UnsupportedScheme));
+        assert_eq!(javascript, Err(AppError::UnsupportedScheme));
+    }
+
+    #[test]
+    fn parse_with_configured_schemes() {
+        // When
+        let result = ValidUrl::parse_with("ftp://example.com/file", &["ftp"], 2048);
+
+        // Then
+        assert_eq!(result.unwrap().as_str(), "ftp://example.com/file");
+    }
+
+    #[test]
+    fn parse_enforces_max_length() {
+        // Given
+        let full_url = "https://example.com/abc";
+
+        // When
+        let exact = ValidUrl::parse_with(full_url, &["https"], full_url.len());
+        let over = ValidUrl::parse_with(full_url, &["https"], full_url.len() - 1);
+
+        // Then
+        assert_eq!(exact.unwrap().as_str(), full_url);
+        assert_eq!(over, Err(AppError::UrlTooLong));
+    }
+
+    #[test]
+    fn from_url_checks_normalized_length() {
+        // Given
+        let url = Url::parse("https://example.com").unwrap();
+
+        // When
+        let result = ValidUrl::from_url(url, &["https"], "https://example.com".len());
+
+        // Then
+        assert_eq!(result, Err(AppError::UrlTooLong));
+    }
+
+    #[test]
+    fn parse_verbatim_keeps_input() {
+        // When
+        let normalized = ValidUrl::parse("https://example.com").unwrap();
+        let verbatim =
+            ValidUrl::parse_verbatim_with("https://example.com", &DEFAULT_ALLOWED_SCHEMES, 2048)
+                .unwrap();
+        let spaced = ValidUrl::parse_verbatim_with(
+            "https://example.com/a b",
+            &DEFAULT_ALLOWED_SCHEMES,
+            2048,
+        )
+        .unwrap();
+        let rejected =
+            ValidUrl::parse_verbatim_with("ftp://example.com", &DEFAULT_ALLOWED_SCHEMES, 2048);
+
+        // Then
+        assert_eq!(normalized.as_str(), "https://example.com/");
+        assert_eq!(verbatim.as_str(), "https://example.com");
+        assert_eq!(spaced.as_str(), "https://example.com/a%20b");
+        assert_eq!(rejected, Err(AppError::UnsupportedScheme));
+    }
+
+    #[test]
+    fn short_id_accepts_url_safe_chars() {
+        // When
+        let result = ShortId::parse("Ab3_x-9");
+
+        // Then
+        assert_eq!(result.unwrap().as_str(), "Ab3_x-9");
+    }
+
+    #[test]
+    fn short_id_rejects_illegal_chars() {
+        // When
+        let dotted = ShortId::parse("release.notes");
+        let slashed = ShortId::parse("a/b");
+        let spaced = ShortId::parse("a b");
+        let unicode = ShortId::parse("ссылка");
+        let plus = ShortId::parse("abc+");
+
+        // Then
+        assert_eq!(dotted, Err(AppError::InvalidId));
+        assert_eq!(slashed, Err(AppError::InvalidId));
+        assert_eq!(spaced, Err(AppError::InvalidId));
+        assert_eq!(unicode, Err(AppError::InvalidId));
+        assert_eq!(plus, Err(AppError::InvalidId));
+    }
+
+    #[test]
+    fn short_id_enforces_length() {
+        // When
+        let empty = ShortId::parse("");
+        let longest = ShortId::parse(&"a".repeat(MAX_ID_LENGTH));
+        let too_long = ShortId::parse(&"a".repeat(MAX_ID_LENGTH + 1));
+
+        // Then
+        assert_eq!(empty, Err(AppError::InvalidId));
+        assert!(longest.is_ok());
+        assert_eq!(too_long, Err(AppError::InvalidId));
+    }
+
+    #[test]
+    fn stored_url_defaults() {
+        // When
+        let record = StoredUrl::new("https://www.google.com/");
+
+        // Then
+        assert_eq!(record.full_url, "https://www.google.com/");
+        assert_eq!(record.clicks, 0);
+        assert_eq!(record.created_at, None);
+        assert_eq!(record.expires_at, None);
+        assert!(!record.disabled);
+        assert_eq!(record.password_hash, None);
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    async fn on_created(&self, id: &str, url: &str);
+    async fn on_resolved(&self, id: &str);
+}
+
+pub struct NoopEventSink;
+
+#[async_trait]
+impl EventSink for NoopEventSink {
+    async fn on_created(&self, _id: &str, _url: &str) {}
+
+    async fn on_resolved(&self, _id: &str) {}
+}
+
+/// Dispatches on a spawned task so a slow sink never delays the caller.
+pub fn emit_created(sink: &Arc<dyn EventSink>, id: &str, url: &str) {
+    let sink = sink.clone();
+    let (id, url) = (id.to_owned(), url.to_owned());
+    tokio::spawn(async move { sink.on_created(&id, &url).await });
+}
+
+pub fn emit_resolved(sink: &Arc<dyn EventSink>, id: &str) {
+    let sink = sink.clone();
+    let id = id.to_owned();
+    tokio::spawn(async move { sink.on_resolved(&id).await });
+}
+
+#[cfg(test)]
+pub mod testing {
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Event {
+        Created { id: String, url: String },
+        Resolved { id: String },
+    }
+
+    pub struct RecordingSink {
+        events: mpsc::UnboundedSender<Event>,
+    }
+
+    impl RecordingSink {
+        pub fn channel() -> (Arc<dyn EventSink>, mpsc::UnboundedReceiver<Event>) {
+            let (events, receiver) = mpsc::unbounded_channel();
+
+            (Arc::new(Self { events }), receiver)
+        }
+    }
+
+    #[async_trait]
+    impl EventSink for RecordingSink {
+        async fn on_created(&self, id: &str, url: &str) {
+            let _ = self.events.send(Event::Created {
+                id: id.to_owned(),
+                url: url.to_owned(),
+            });
+        }
+
+        async fn on_resolved(&self, id: &str) {
+            let _ = self.events.send(Event::Resolved { id: id.to_owned() });
+        }
+    }
+}
@@ -1,5 +1,9 @@
 pub mod command;
+pub mod domain;
+pub mod events;
 pub mod query;
+pub mod tombstones;
+pub mod url_policy;
 
 #[cfg(test)]
 mod tests {
@@ -8,8 +12,11 @@ mod tests {
         app::{
             command::create_short_url::CreateShortUrlCommand, query::get_full_url::GetFullUrlQuery,
         },
+        clock::FakeClock,
+        error::AppError,
         id_provider::FakeIdProvider,
     };
+    use chrono::{Duration, TimeZone, Utc};
     use dashmap::DashMap;
     use std::sync::Arc;
 
@@ -24,11 +31,36 @@ mod tests {
 
         // When
         let short_url = create_command
-            .execute("https://www.google.com")
+            .execute("https://www.google.com", None, None)
             .await;
         let full_url = get_query.execute(&short_url.unwrap()).await.unwrap();
 
         // Then
         assert_eq!(full_url, "https://www.google.com/");
     }
+
+    #[tokio::test]
+    async fn short_url_with_ttl_expires() {
+        // Given
+        let clock = FakeClock::new(Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap());
+        let store = Arc::new(DashMap::new());
+        let repository = InMemoryRepository::new(store);
+        let id_provider = FakeIdProvider::new("123".to_owned());
+        let create_command = CreateShortUrlCommand::new(id_provider, repository.clone())
+            .with_clock(Arc::new(clock.clone()));
+        let get_query = GetFullUrlQuery::new(repository).with_clock(Arc::new(clock.clone()));
+
+        // When
+        let id = create_command
+            .execute("https://www.google.com", None, Some(Duration::seconds(1)))
+            .await
+            .unwrap();
+        let before_deadline = get_query.execute(&id).await;
+        clock.advance(Duration::seconds(1));
+        let after_deadline = get_query.execute(&id).await;
+
+        // Then
+        assert_eq!(before_deadline, Ok("https://www.google.com/".to_owned()));
+        assert_eq!(after_deadline, Err(AppError::Expired));
+    }
 }
@@ -0,0 +1,122 @@
+use std::future::Future;
+
+use futures::{Stream, TryStreamExt, future};
+
+use crate::{error::AppError, id_provider::decode_base62};
+
+pub const EXPORT_BATCH_SIZE: usize = 500;
+
+pub trait ExportRepository {
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static;
+
+    /// Highest id that decodes as a base62 sequence number, or 0. Ids that
+    /// are not base62, such as aliases with `-`, are skipped.
+    fn max_id(&self) -> impl Future<Output = Result<u64, AppError>> + Send {
+        self.export().try_fold(0, |max, (id, _)| {
+            future::ok(decode_base62(&id).map_or(max, |value| max.max(value)))
+        })
+    }
+}
+
+pub struct ExportQuery<R>
+where
+    R: ExportRepository,
+{
+    repository: R,
+}
+
+impl<R> ExportQuery<R>
+where
+    R: ExportRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    pub fn execute(
+        &self,
+    ) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        self.repository.export()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc};
+
+    use dashmap::DashMap;
+    use futures::TryStreamExt;
+
+    use crate::{adapters::in_memory::InMemoryRepository, app::domain::StoredUrl};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn exports_every_entry() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("a".to_owned(), StoredUrl::new("https://a.example/"));
+        store.insert("b".to_owned(), StoredUrl::new("https://b.example/"));
+        let sut = ExportQuery::new(InMemoryRepository::new(store));
+
+        // When
+        let result: HashSet<(String, String)> = sut.execute().try_collect().await.unwrap();
+
+        // Then
+        assert_eq!(
+            result,
+            HashSet::from([
+                ("a".to_owned(), "https://a.example/".to_owned()),
+                ("b".to_owned(), "https://b.example/".to_owned()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn max_id_finds_highest_sequence() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        for id in ["5", "zz", "my-alias", "1A"] {
+            store.insert(id.to_owned(), StoredUrl::new("https://a.example/"));
+        }
+        let sut = InMemoryRepository::new(store);
+
+        // When
+        let result = sut.max_id().await;
+
+        // Then
+        assert_eq!(result, Ok(3843));
+    }
+
+    #[tokio::test]
+    async fn max_id_of_empty_repository_is_zero() {
+        // Given
+        let sut = InMemoryRepository::new(Arc::new(DashMap::new()));
+
+        // When
+        let result = sut.max_id().await;
+
+        // Then
+        assert_eq!(result, Ok(0));
+    }
+
+    #[tokio::test]
+    async fn skips_entries_removed_during_export() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("a".to_owned(), StoredUrl::new("https://a.example/"));
+        store.insert("b".to_owned(), StoredUrl::new("https://b.example/"));
+        let sut = ExportQuery::new(InMemoryRepository::new(store.clone()));
+
+        // When
+        let export = sut.execute();
+        store.remove("b");
+        let result: Vec<(String, String)> = export.try_collect().await.unwrap();
+
+        // Then
+        assert_eq!(
+            result,
+            vec![("a".to_owned(), "https://a.example/".to_owned())]
+        );
+    }
+}
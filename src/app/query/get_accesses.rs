@@ -0,0 +1,74 @@
+use crate::{
+    app::command::record_access::{AccessEntry, AccessLogRepository},
+    error::AppError,
+};
+
+pub struct GetAccessesQuery<R>
+where
+    R: AccessLogRepository,
+{
+    repository: R,
+}
+
+impl<R> GetAccessesQuery<R>
+where
+    R: AccessLogRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    pub async fn execute(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        self.repository.recent(id, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+    use dashmap::DashMap;
+
+    use crate::{adapters::in_memory::InMemoryRepository, app::domain::StoredUrl};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn limits_recent_accesses() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store);
+        for user_agent in ["a", "b", "c"] {
+            repository
+                .record("123", Utc::now(), Some(user_agent.to_owned()), None)
+                .await
+                .unwrap();
+        }
+        let sut = GetAccessesQuery::new(repository);
+
+        // When
+        let result = sut.execute("123", 2).await.unwrap();
+
+        // Then
+        let user_agents: Vec<_> = result
+            .iter()
+            .map(|entry| entry.user_agent.as_deref().unwrap())
+            .collect();
+        assert_eq!(user_agents, vec!["c", "b"]);
+    }
+
+    #[tokio::test]
+    async fn unknown_id_is_not_found() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = GetAccessesQuery::new(repository);
+
+        // When
+        let result = sut.execute("123", 10).await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+    }
+}
@@ -1,7 +1,21 @@
-use crate::error::AppError;
+use std::{future::Future, sync::Arc};
 
-pub trait GetFullUrlRepository {
-    fn get(&self, id: &str) -> impl std::future::Future<Output = Result<String, AppError>> + std::marker::Send;
+use crate::{
+    app::{
+        domain::StoredUrl,
+        events::{self, EventSink, NoopEventSink},
+        tombstones::Tombstones,
+    },
+    clock::{Clock, SystemClock},
+    error::AppError,
+    password,
+};
+
+pub trait GetFullUrlRepository: Sync {
+    fn get_record(&self, id: &str) -> impl Future<Output = Result<StoredUrl, AppError>> + Send;
+    fn get(&self, id: &str) -> impl Future<Output = Result<String, AppError>> + Send {
+        async move { self.get_record(id).await.map(|record| record.full_url) }
+    }
 }
 
 pub struct GetFullUrlQuery<R>
@@ -9,6 +23,10 @@ where
     R: GetFullUrlRepository,
 {
     repository: R,
+    case_insensitive: bool,
+    tombstones: Option<Tombstones>,
+    clock: Arc<dyn Clock>,
+    events: Arc<dyn EventSink>,
 }
 
 impl<R> GetFullUrlQuery<R>
@@ -16,21 +34,94 @@ where
     R: GetFullUrlRepository,
 {
     pub fn new(repository: R) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            case_insensitive: false,
+            tombstones: None,
+            clock: Arc::new(SystemClock),
+            events: Arc::new(NoopEventSink),
+        }
+    }
+
+    pub fn with_case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    pub fn with_tombstones(mut self, tombstones: Option<Tombstones>) -> Self {
+        self.tombstones = tombstones;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_events(mut self, events: Arc<dyn EventSink>) -> Self {
+        self.events = events;
+        self
     }
 
     pub async fn execute(&self, id: &str) -> Result<String, AppError> {
-        self.repository.get(id).await
+        self.execute_with_password(id, None).await
+    }
+
+    pub async fn execute_with_password(
+        &self,
+        id: &str,
+        password: Option<&str>,
+    ) -> Result<String, AppError> {
+        let record = match self.case_insensitive {
+            true => self.repository.get_record(&id.to_ascii_lowercase()).await,
+            false => self.repository.get_record(id).await,
+        };
+        let record = match record {
+            Err(AppError::NotFound) if self.is_buried(id) => return Err(AppError::Gone),
+            record => record?,
+        };
+
+        if record.disabled {
+            return Err(AppError::Disabled);
+        }
+
+        if let Some(expires_at) = record.expires_at
+            && self.clock.now() >= expires_at
+        {
+            return Err(AppError::Expired);
+        }
+
+        if let Some(password_hash) = &record.password_hash
+            && !password.is_some_and(|password| password::verify(password, password_hash))
+        {
+            return Err(AppError::Unauthorized);
+        }
+
+        events::emit_resolved(&self.events, id);
+
+        Ok(record.full_url)
+    }
+
+    fn is_buried(&self, id: &str) -> bool {
+        self.tombstones
+            .as_ref()
+            .is_some_and(|tombstones| match self.case_insensitive {
+                true => tombstones.is_buried(&id.to_ascii_lowercase()),
+                false => tombstones.is_buried(id),
+            })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
-
+    use chrono::{DateTime, Duration, TimeZone, Utc};
     use dashmap::DashMap;
 
-    use crate::adapters::in_memory::InMemoryRepository;
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::events::testing::{Event, RecordingSink},
+        clock::FakeClock,
+    };
 
     use super::*;
 
@@ -40,8 +131,8 @@ mod tests {
         struct FakeRepository;
 
         impl GetFullUrlRepository for FakeRepository {
-            async fn get(&self, _id: &str) -> Result<String, AppError> {
-                Ok("https://www.google.com".to_owned())
+            async fn get_record(&self, _id: &str) -> Result<StoredUrl, AppError> {
+                Ok(StoredUrl::new("https://www.google.com"))
             }
         }
         let repository = FakeRepository;
@@ -57,8 +148,8 @@ mod tests {
     #[tokio::test]
     async fn get_from_in_memory_repository() {
         // Given
-        let store: Arc<DashMap<String, String>> = Arc::new(DashMap::new());
-        store.insert("123".to_owned(), "https://www.google.com".to_owned());
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com"));
         let repository = InMemoryRepository::new(store);
         let get_full_url = GetFullUrlQuery::new(repository);
 
@@ -72,9 +163,9 @@ mod tests {
     #[tokio::test]
     async fn get_two_different_full_urls() {
         // Given
-        let store: Arc<DashMap<String, String>> = Arc::new(DashMap::new());
-        store.insert("123".to_owned(), "https://www.google.com".to_owned());
-        store.insert("456".to_owned(), "https://www.example.com".to_owned());
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com"));
+        store.insert("456".to_owned(), StoredUrl::new("https://www.example.com"));
         let repository = InMemoryRepository::new(store);
         let get_full_url = GetFullUrlQuery::new(repository);
 
@@ -86,4 +177,141 @@ mod tests {
         assert_eq!(result1, Ok("https://www.google.com".to_owned()));
         assert_eq!(result2, Ok("https://www.example.com".to_owned()));
     }
+
+    #[tokio::test]
+    async fn expired_url() {
+        // Given
+        struct FakeRepository {
+            expires_at: DateTime<Utc>,
+        }
+
+        impl GetFullUrlRepository for FakeRepository {
+            async fn get_record(&self, _id: &str) -> Result<StoredUrl, AppError> {
+                Ok(StoredUrl {
+                    expires_at: Some(self.expires_at),
+                    ..StoredUrl::new("https://www.google.com")
+                })
+            }
+        }
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let clock = FakeClock::new(now);
+        let repository = FakeRepository {
+            expires_at: now + Duration::seconds(1),
+        };
+        let get_full_url = GetFullUrlQuery::new(repository).with_clock(Arc::new(clock.clone()));
+
+        // When
+        let before_expiry = get_full_url.execute("123").await;
+        clock.advance(Duration::seconds(1));
+        let after_expiry = get_full_url.execute("123").await;
+
+        // Then
+        assert_eq!(before_expiry, Ok("https://www.google.com".to_owned()));
+        assert_eq!(after_expiry, Err(AppError::Expired));
+    }
+
+    #[tokio::test]
+    async fn case_insensitive_lookup_matches_mixed_case_ids() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert(
+            "abc123".to_owned(),
+            StoredUrl::new("https://www.google.com"),
+        );
+        let repository = InMemoryRepository::new(store);
+        let get_full_url = GetFullUrlQuery::new(repository).with_case_insensitive(true);
+
+        // When
+        let lower = get_full_url.execute("abc123").await;
+        let mixed = get_full_url.execute("AbC123").await;
+
+        // Then
+        assert_eq!(lower, Ok("https://www.google.com".to_owned()));
+        assert_eq!(mixed, Ok("https://www.google.com".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn case_sensitive_lookup_rejects_mixed_case_ids() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert(
+            "abc123".to_owned(),
+            StoredUrl::new("https://www.google.com"),
+        );
+        let repository = InMemoryRepository::new(store);
+        let get_full_url = GetFullUrlQuery::new(repository);
+
+        // When
+        let lower = get_full_url.execute("abc123").await;
+        let mixed = get_full_url.execute("AbC123").await;
+
+        // Then
+        assert_eq!(lower, Ok("https://www.google.com".to_owned()));
+        assert_eq!(mixed, Err(AppError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn protected_url_requires_matching_password() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert(
+            "123".to_owned(),
+            StoredUrl {
+                password_hash: Some(password::hash("secret").unwrap()),
+                ..StoredUrl::new("https://www.google.com")
+            },
+        );
+        let repository = InMemoryRepository::new(store);
+        let get_full_url = GetFullUrlQuery::new(repository);
+
+        // When
+        let without_password = get_full_url.execute("123").await;
+        let wrong_password = get_full_url
+            .execute_with_password("123", Some("guess"))
+            .await;
+        let right_password = get_full_url
+            .execute_with_password("123", Some("secret"))
+            .await;
+
+        // Then
+        assert_eq!(without_password, Err(AppError::Unauthorized));
+        assert_eq!(wrong_password, Err(AppError::Unauthorized));
+        assert_eq!(right_password, Ok("https://www.google.com".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn resolve_emits_resolved_event() {
+        // Given
+        let (sink, mut events) = RecordingSink::channel();
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com"));
+        let sut = GetFullUrlQuery::new(InMemoryRepository::new(store)).with_events(sink);
+
+        // When
+        sut.execute("123").await.unwrap();
+
+        // Then
+        assert_eq!(
+            events.recv().await,
+            Some(Event::Resolved {
+                id: "123".to_owned()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_resolve_emits_no_event() {
+        // Given
+        let (sink, mut events) = RecordingSink::channel();
+        let sut = GetFullUrlQuery::new(InMemoryRepository::new(Arc::new(DashMap::new())))
+            .with_events(sink);
+
+        // When
+        let result = sut.execute("missing").await;
+        drop(sut);
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+        assert_eq!(events.recv().await, None);
+    }
 }
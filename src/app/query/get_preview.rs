@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use url::Url;
+
+use crate::{
+    app::{domain::LinkMetadata, query::get_full_url::GetFullUrlRepository},
+    clock::{Clock, SystemClock},
+    error::AppError,
+};
+
+/// What a link points to and its state. The target (`url`, `scheme` and
+/// `host`) is left out for password-protected links.
+#[derive(Debug, PartialEq)]
+pub struct UrlPreview {
+    pub url: Option<String>,
+    pub scheme: Option<String>,
+    pub host: Option<String>,
+    pub protected: bool,
+    pub disabled: bool,
+    pub expired: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub clicks: u64,
+    pub metadata: LinkMetadata,
+}
+
+pub struct GetPreviewQuery<R>
+where
+    R: GetFullUrlRepository,
+{
+    repository: R,
+    clock: Arc<dyn Clock>,
+}
+
+impl<R> GetPreviewQuery<R>
+where
+    R: GetFullUrlRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub async fn execute(&self, id: &str) -> Result<UrlPreview, AppError> {
+        let record = self.repository.get_record(id).await?;
+        let protected = record.password_hash.is_some();
+        let (url, scheme, host) = match protected {
+            true => (None, None, None),
+            false => {
+                let parsed = Url::parse(&record.full_url)?;
+                (
+                    Some(record.full_url),
+                    Some(parsed.scheme().to_owned()),
+                    parsed.host_str().map(str::to_owned),
+                )
+            }
+        };
+
+        Ok(UrlPreview {
+            url,
+            scheme,
+            host,
+            protected,
+            disabled: record.disabled,
+            expired: record
+                .expires_at
+                .is_some_and(|expires_at| self.clock.now() >= expires_at),
+            created_at: record.created_at,
+            expires_at: record.expires_at,
+            clicks: record.clicks,
+            metadata: record.metadata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::{
+            command::{
+                create_short_url::CreateShortUrlRepository, record_click::RecordClickRepository,
+                set_enabled::SetEnabledRepository,
+            },
+            domain::{ShortId, ValidUrl},
+        },
+        clock::FakeClock,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn preview_known_link() {
+        // Given
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let created_at = now - Duration::days(1);
+        let expires_at = now - Duration::seconds(1);
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/search").unwrap(),
+                ShortId::parse("123").unwrap(),
+                created_at,
+                Some(expires_at),
+                None,
+                LinkMetadata {
+                    title: Some("Search".to_owned()),
+                    description: None,
+                },
+            )
+            .await
+            .unwrap();
+        repository.increment("123").await.unwrap();
+        repository.set_enabled("123", false).await.unwrap();
+        let sut = GetPreviewQuery::new(repository).with_clock(Arc::new(FakeClock::new(now)));
+
+        // When
+        let result = sut.execute("123").await;
+
+        // Then
+        assert_eq!(
+            result,
+            Ok(UrlPreview {
+                url: Some("https://www.google.com/search".to_owned()),
+                scheme: Some("https".to_owned()),
+                host: Some("www.google.com".to_owned()),
+                protected: false,
+                disabled: true,
+                expired: true,
+                created_at: Some(created_at),
+                expires_at: Some(expires_at),
+                clicks: 1,
+                metadata: LinkMetadata {
+                    title: Some("Search".to_owned()),
+                    description: None,
+                },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn protected_link_hides_target() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        repository
+            .save(
+                ValidUrl::parse("https://www.google.com/search").unwrap(),
+                ShortId::parse("123").unwrap(),
+                Utc::now(),
+                None,
+                Some(crate::password::hash("secret").unwrap()),
+                LinkMetadata::default(),
+            )
+            .await
+            .unwrap();
+        let sut = GetPreviewQuery::new(repository);
+
+        // When
+        let preview = sut.execute("123").await.unwrap();
+
+        // Then
+        assert!(preview.protected);
+        assert_eq!(preview.url, None);
+        assert_eq!(preview.scheme, None);
+        assert_eq!(preview.host, None);
+    }
+
+    #[tokio::test]
+    async fn unknown_id_is_not_found() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = GetPreviewQuery::new(repository);
+
+        // When
+        let result = sut.execute("123").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+    }
+}
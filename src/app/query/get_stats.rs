@@ -0,0 +1,119 @@
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    app::{domain::LinkMetadata, query::get_full_url::GetFullUrlRepository},
+    error::AppError,
+};
+
+pub trait GetStatsRepository {
+    fn clicks(&self, id: &str) -> impl Future<Output = Result<u64, AppError>> + Send;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UrlStats {
+    pub clicks: u64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub metadata: LinkMetadata,
+}
+
+pub struct GetStatsQuery<R>
+where
+    R: GetStatsRepository + GetFullUrlRepository,
+{
+    repository: R,
+}
+
+impl<R> GetStatsQuery<R>
+where
+    R: GetStatsRepository + GetFullUrlRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    pub async fn execute(&self, id: &str) -> Result<UrlStats, AppError> {
+        let clicks = self.repository.clicks(id).await?;
+        let record = self.repository.get_record(id).await?;
+
+        Ok(UrlStats {
+            clicks,
+            created_at: record.created_at,
+            metadata: record.metadata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::{command::record_click::RecordClickRepository, domain::StoredUrl},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn unclicked_url_has_zero_clicks() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store);
+        let get_stats = GetStatsQuery::new(repository);
+
+        // When
+        let result = get_stats.execute("123").await;
+
+        // Then
+        assert_eq!(
+            result,
+            Ok(UrlStats {
+                clicks: 0,
+                created_at: None,
+                metadata: LinkMetadata::default(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn clicked_url_reports_clicks() {
+        // Given
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        store.insert("123".to_owned(), StoredUrl::new("https://www.google.com/"));
+        let repository = InMemoryRepository::new(store);
+        repository.increment("123").await.unwrap();
+        repository.increment("123").await.unwrap();
+        let get_stats = GetStatsQuery::new(repository);
+
+        // When
+        let result = get_stats.execute("123").await;
+
+        // Then
+        assert_eq!(
+            result,
+            Ok(UrlStats {
+                clicks: 2,
+                created_at: None,
+                metadata: LinkMetadata::default(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_id_is_not_found() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let get_stats = GetStatsQuery::new(repository);
+
+        // When
+        let result = get_stats.execute("123").await;
+
+        // Then
+        assert_eq!(result, Err(AppError::NotFound));
+    }
+}
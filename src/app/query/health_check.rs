@@ -0,0 +1,73 @@
+use std::future::Future;
+
+use crate::error::AppError;
+
+pub trait HealthCheckRepository {
+    fn ping(&self) -> impl Future<Output = Result<(), AppError>> + Send;
+}
+
+pub struct HealthCheckQuery<R>
+where
+    R: HealthCheckRepository,
+{
+    repository: R,
+}
+
+impl<R> HealthCheckQuery<R>
+where
+    R: HealthCheckRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    pub async fn execute(&self) -> Result<(), AppError> {
+        self.repository.ping().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::adapters::in_memory::InMemoryRepository;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn healthy_repository() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let health_check = HealthCheckQuery::new(repository);
+
+        // When
+        let result = health_check.execute().await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn unhealthy_repository() {
+        // Given
+        struct FakeRepository;
+
+        impl HealthCheckRepository for FakeRepository {
+            async fn ping(&self) -> Result<(), AppError> {
+                Err(AppError::RepositoryError("connection refused".to_owned()))
+            }
+        }
+        let health_check = HealthCheckQuery::new(FakeRepository);
+
+        // When
+        let result = health_check.execute().await;
+
+        // Then
+        assert_eq!(
+            result,
+            Err(AppError::RepositoryError("connection refused".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::future::Future;
+
+use crate::error::AppError;
+
+pub trait ListUrlsRepository {
+    fn list(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<(String, String)>, AppError>> + Send;
+    fn count(&self) -> impl Future<Output = Result<usize, AppError>> + Send;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UrlPage {
+    pub items: Vec<(String, String)>,
+    pub total: usize,
+}
+
+pub struct ListUrlsQuery<R>
+where
+    R: ListUrlsRepository,
+{
+    repository: R,
+}
+
+impl<R> ListUrlsQuery<R>
+where
+    R: ListUrlsRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    pub async fn execute(&self, offset: usize, limit: usize) -> Result<UrlPage, AppError> {
+        let items = self.repository.list(offset, limit).await?;
+        let total = self.repository.count().await?;
+
+        Ok(UrlPage { items, total })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::{adapters::in_memory::InMemoryRepository, app::domain::StoredUrl};
+
+    use super::*;
+
+    fn get_query(ids: &[&str]) -> ListUrlsQuery<InMemoryRepository> {
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        for id in ids {
+            store.insert(
+                id.to_string(),
+                StoredUrl::new(format!("https://{id}.example/")),
+            );
+        }
+
+        ListUrlsQuery::new(InMemoryRepository::new(store))
+    }
+
+    #[tokio::test]
+    async fn empty_store_lists_nothing() {
+        // Given
+        let sut = get_query(&[]);
+
+        // When
+        let result = sut.execute(0, 10).await;
+
+        // Then
+        assert_eq!(
+            result,
+            Ok(UrlPage {
+                items: vec![],
+                total: 0
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn lists_partial_page_in_id_order() {
+        // Given
+        let sut = get_query(&["c", "a", "d", "b"]);
+
+        // When
+        let result = sut.execute(1, 2).await;
+
+        // Then
+        assert_eq!(
+            result,
+            Ok(UrlPage {
+                items: vec![
+                    ("b".to_owned(), "https://b.example/".to_owned()),
+                    ("c".to_owned(), "https://c.example/".to_owned()),
+                ],
+                total: 4
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn offset_beyond_end_lists_nothing() {
+        // Given
+        let sut = get_query(&["a", "b"]);
+
+        // When
+        let result = sut.execute(5, 10).await;
+
+        // Then
+        assert_eq!(
+            result,
+            Ok(UrlPage {
+                items: vec![],
+                total: 2
+            })
+        );
+    }
+}
@@ -1 +1,8 @@
+pub mod export;
+pub mod get_accesses;
 pub mod get_full_url;
+pub mod get_preview;
+pub mod get_stats;
+pub mod health_check;
+pub mod list_urls;
+pub mod reverse_lookup;
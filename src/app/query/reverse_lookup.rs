@@ -0,0 +1,88 @@
+use std::future::Future;
+
+use crate::{app::domain::ValidUrl, error::AppError};
+
+pub trait ReverseLookupRepository {
+    fn ids_for_url(&self, url: &str) -> impl Future<Output = Result<Vec<String>, AppError>> + Send;
+}
+
+pub struct ReverseLookupQuery<R>
+where
+    R: ReverseLookupRepository,
+{
+    repository: R,
+}
+
+impl<R> ReverseLookupQuery<R>
+where
+    R: ReverseLookupRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// Ids pointing at `url`, in id order. Takes a [`ValidUrl`] so the
+    /// lookup matches the normalized form stored on creation.
+    pub async fn execute(&self, url: &ValidUrl) -> Result<Vec<String>, AppError> {
+        let mut ids = self.repository.ids_for_url(url.as_str()).await?;
+        ids.sort();
+
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+
+    use crate::{adapters::in_memory::InMemoryRepository, app::domain::StoredUrl};
+
+    use super::*;
+
+    fn get_query(entries: &[(&str, &str)]) -> ReverseLookupQuery<InMemoryRepository> {
+        let store: Arc<DashMap<String, StoredUrl>> = Arc::new(DashMap::new());
+        for (id, url) in entries {
+            store.insert(id.to_string(), StoredUrl::new(*url));
+        }
+
+        ReverseLookupQuery::new(InMemoryRepository::new(store))
+    }
+
+    #[tokio::test]
+    async fn finds_every_id_for_url() {
+        // Given
+        let sut = get_query(&[
+            ("c", "https://example.com/"),
+            ("a", "https://example.com/"),
+            ("b", "https://other.example/"),
+            ("d", "https://example.com/"),
+        ]);
+
+        // When
+        let result = sut
+            .execute(&ValidUrl::parse("https://example.com").unwrap())
+            .await;
+
+        // Then
+        assert_eq!(
+            result,
+            Ok(vec!["a".to_owned(), "c".to_owned(), "d".to_owned()])
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_url_finds_nothing() {
+        // Given
+        let sut = get_query(&[("a", "https://example.com/")]);
+
+        // When
+        let result = sut
+            .execute(&ValidUrl::parse("https://unknown.example").unwrap())
+            .await;
+
+        // Then
+        assert_eq!(result, Ok(vec![]));
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+
+use crate::clock::{Clock, SystemClock};
+
+#[derive(Clone)]
+pub struct Tombstones {
+    entries: Arc<DashMap<String, Option<DateTime<Utc>>>>,
+    ttl: Option<Duration>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Tombstones {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            entries: Arc::new(DashMap::new()),
+            ttl,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn bury(&self, id: &str) {
+        let until = self.ttl.map(|ttl| self.clock.now() + ttl);
+        self.entries.insert(id.to_owned(), until);
+    }
+
+    pub fn is_buried(&self, id: &str) -> bool {
+        let now = self.clock.now();
+        self.entries
+            .remove_if(id, |_, until| until.is_some_and(|until| now >= until));
+
+        self.entries.contains_key(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::clock::FakeClock;
+
+    use super::*;
+
+    #[test]
+    fn buried_ids_are_remembered() {
+        // Given
+        let sut = Tombstones::new(None);
+
+        // When
+        sut.bury("123");
+
+        // Then
+        assert!(sut.is_buried("123"));
+        assert!(!sut.is_buried("456"));
+    }
+
+    #[test]
+    fn tombstones_expire_after_ttl() {
+        // Given
+        let clock = FakeClock::new(Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap());
+        let sut = Tombstones::new(Some(Duration::hours(1))).with_clock(Arc::new(clock.clone()));
+        sut.bury("123");
+
+        // When
+        let before_ttl = sut.is_buried("123");
+        clock.advance(Duration::hours(1));
+        let after_ttl = sut.is_buried("123");
+
+        // Then
+        assert!(before_ttl);
+        assert!(!after_ttl);
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use url::Url;
+
+use crate::{
+    app::domain::{DEFAULT_ALLOWED_SCHEMES, DEFAULT_MAX_URL_LENGTH, ValidUrl},
+    error::AppError,
+};
+
+/// Which targets may be stored: allowed schemes, maximum length and
+/// blocked or allowed domains. Every command that writes a target checks
+/// it against the same policy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlPolicy {
+    max_url_length: usize,
+    allowed_schemes: Vec<String>,
+    domain_blocklist: HashSet<String>,
+    domain_allowlist: Option<HashSet<String>>,
+}
+
+impl Default for UrlPolicy {
+    fn default() -> Self {
+        Self {
+            max_url_length: DEFAULT_MAX_URL_LENGTH,
+            allowed_schemes: DEFAULT_ALLOWED_SCHEMES.map(String::from).to_vec(),
+            domain_blocklist: HashSet::new(),
+            domain_allowlist: None,
+        }
+    }
+}
+
+fn normalize_domain(domain: &str) -> String {
+    let domain = domain.trim().trim_end_matches('.').to_lowercase();
+    let domain = match url::Host::parse(&domain) {
+        Ok(url::Host::Domain(ascii)) => ascii,
+        _ => domain,
+    };
+
+    match domain.strip_prefix("www.") {
+        Some(domain) => domain.to_owned(),
+        None => domain,
+    }
+}
+
+fn matches_domain(domains: &HashSet<String>, host: &str) -> bool {
+    let mut domain = host;
+    loop {
+        if domains.contains(domain) {
+            return true;
+        }
+        match domain.split_once('.') {
+            Some((_, parent)) => domain = parent,
+            None => return false,
+        }
+    }
+}
+
+impl UrlPolicy {
+    pub fn with_max_url_length(mut self, max_url_length: usize) -> Self {
+        self.max_url_length = max_url_length;
+        self
+    }
+
+    pub fn with_allowed_schemes(mut self, allowed_schemes: Vec<String>) -> Self {
+        self.allowed_schemes = allowed_schemes;
+        self
+    }
+
+    pub fn with_domain_blocklist(mut self, domain_blocklist: HashSet<String>) -> Self {
+        self.domain_blocklist = domain_blocklist
+            .iter()
+            .map(|domain| normalize_domain(domain))
+            .collect();
+        self
+    }
+
+    pub fn with_domain_allowlist(mut self, domain_allowlist: Option<HashSet<String>>) -> Self {
+        self.domain_allowlist = domain_allowlist.map(|domains| {
+            domains
+                .iter()
+                .map(|domain| normalize_domain(domain))
+                .collect()
+        });
+        self
+    }
+
+    pub fn max_url_length(&self) -> usize {
+        self.max_url_length
+    }
+
+    pub fn parse(&self, full_url: &str) -> Result<ValidUrl, AppError> {
+        ValidUrl::parse_with(full_url, &self.allowed_schemes, self.max_url_length)
+    }
+
+    pub fn parse_verbatim(&self, full_url: &str) -> Result<ValidUrl, AppError> {
+        ValidUrl::parse_verbatim_with(full_url, &self.allowed_schemes, self.max_url_length)
+    }
+
+    pub fn from_url(&self, url: Url) -> Result<ValidUrl, AppError> {
+        ValidUrl::from_url(url, &self.allowed_schemes, self.max_url_length)
+    }
+
+    pub fn check_domain(&self, full_url: &ValidUrl) -> Result<(), AppError> {
+        if self.domain_blocklist.is_empty() && self.domain_allowlist.is_none() {
+            return Ok(());
+        }
+        let host = Url::parse(full_url.as_str())?
+            .host_str()
+            .map(normalize_domain)
+            .unwrap_or_default();
+
+        let blocked = matches_domain(&self.domain_blocklist, &host);
+        let allowed = self
+            .domain_allowlist
+            .as_ref()
+            .is_none_or(|domains| matches_domain(domains, &host));
+        match !blocked && allowed {
+            true => Ok(()),
+            false => Err(AppError::BlockedDomain),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+#[derive(Clone)]
+pub struct FakeClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl FakeClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += duration;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn fake_clock_is_fixed_until_moved() {
+        // Given
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let sut = FakeClock::new(start);
+
+        // When
+        let first = sut.now();
+        let second = sut.now();
+
+        // Then
+        assert_eq!(first, start);
+        assert_eq!(second, start);
+    }
+
+    #[test]
+    fn fake_clock_can_be_set_and_advanced() {
+        // Given
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let later = Utc.with_ymd_and_hms(2030, 6, 1, 0, 0, 0).unwrap();
+        let sut = FakeClock::new(start);
+        let shared = sut.clone();
+
+        // When
+        sut.advance(Duration::minutes(5));
+        let advanced = shared.now();
+        sut.set(later);
+        let set = shared.now();
+
+        // Then
+        assert_eq!(advanced, start + Duration::minutes(5));
+        assert_eq!(set, later);
+    }
+}
@@ -0,0 +1,674 @@
+use std::fmt::{self, Display};
+
+use crate::{
+    app::domain::{MAX_ID_LENGTH, ShortId},
+    id_provider::{DEFAULT_ID_SIZE, IdStrategy, UNAMBIGUOUS_ALPHABET},
+};
+
+const DEFAULT_PORT: u16 = 3000;
+const DEFAULT_BIND_ADDR: &str = "0.0.0.0";
+const DEFAULT_BASE_URL: &str = "http://localhost:3000";
+const DEFAULT_RATE_LIMIT_REQUESTS: u32 = 60;
+const DEFAULT_RATE_LIMIT_WINDOW_SECONDS: u64 = 60;
+const DEFAULT_MAX_LIST_LIMIT: usize = 100;
+const DEFAULT_MAX_BODY_BYTES: usize = 8 * 1024;
+const DEFAULT_REQUEST_TIMEOUT_SECONDS: u64 = 5;
+const DEFAULT_EXPIRY_SWEEP_INTERVAL_SECONDS: u64 = 60;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ConfigError {
+    InvalidVar { name: String, value: String },
+    Unsupported { name: String, reason: String },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::InvalidVar { name, value } => {
+                write!(f, "Invalid value for {}: {}", name, value)
+            }
+            ConfigError::Unsupported { name, reason } => {
+                write!(f, "{} is not supported: {}", name, reason)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Pretty,
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub port: u16,
+    pub bind_addr: String,
+    pub base_url: String,
+    pub log_format: LogFormat,
+    pub rate_limit_requests: u32,
+    pub rate_limit_window_seconds: u64,
+    pub allowed_origins: Vec<String>,
+    pub max_list_limit: usize,
+    pub api_token: Option<String>,
+    pub max_body_bytes: usize,
+    pub assume_https: bool,
+    pub preserve_url_input: bool,
+    pub case_insensitive_ids: bool,
+    pub id_strategy: IdStrategy,
+    pub id_checksum: bool,
+    pub opaque_not_found: bool,
+    pub problem_json: bool,
+    pub legacy_create_status: bool,
+    pub tombstones: bool,
+    pub tombstone_ttl_seconds: Option<u64>,
+    pub max_entries: Option<usize>,
+    pub request_timeout_seconds: u64,
+    pub max_concurrent_requests: Option<usize>,
+    pub expiry_sweep_interval_seconds: u64,
+    pub webhook_url: Option<String>,
+    pub log_resolved_urls: bool,
+    pub redact_query_params: Vec<String>,
+    pub blocked_paths: Vec<String>,
+    pub upstream_url: Option<String>,
+    pub root_redirect: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_PORT,
+            bind_addr: DEFAULT_BIND_ADDR.to_owned(),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            log_format: LogFormat::default(),
+            rate_limit_requests: DEFAULT_RATE_LIMIT_REQUESTS,
+            rate_limit_window_seconds: DEFAULT_RATE_LIMIT_WINDOW_SECONDS,
+            allowed_origins: Vec::new(),
+            max_list_limit: DEFAULT_MAX_LIST_LIMIT,
+            api_token: None,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            assume_https: false,
+            preserve_url_input: false,
+            case_insensitive_ids: false,
+            id_strategy: IdStrategy::default(),
+            id_checksum: false,
+            opaque_not_found: false,
+            problem_json: false,
+            legacy_create_status: false,
+            tombstones: false,
+            tombstone_ttl_seconds: None,
+            max_entries: None,
+            request_timeout_seconds: DEFAULT_REQUEST_TIMEOUT_SECONDS,
+            max_concurrent_requests: None,
+            expiry_sweep_interval_seconds: DEFAULT_EXPIRY_SWEEP_INTERVAL_SECONDS,
+            webhook_url: None,
+            log_resolved_urls: false,
+            redact_query_params: Vec::new(),
+            blocked_paths: Vec::new(),
+            upstream_url: None,
+            root_redirect: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_env() -> Result<Config, ConfigError> {
+        let defaults = Config::default();
+
+        let config = Config {
+            port: parse_var("PORT", defaults.port)?,
+            bind_addr: std::env::var("BIND_ADDR").unwrap_or(defaults.bind_addr),
+            base_url: std::env::var("BASE_URL").unwrap_or(defaults.base_url),
+            log_format: parse_var("LOG_FORMAT", defaults.log_format)?,
+            rate_limit_requests: parse_var("RATE_LIMIT_REQUESTS", defaults.rate_limit_requests)?,
+            rate_limit_window_seconds: parse_var(
+                "RATE_LIMIT_WINDOW_SECONDS",
+                defaults.rate_limit_window_seconds,
+            )?,
+            allowed_origins: parse_origins("ALLOWED_ORIGINS", defaults.allowed_origins)?,
+            max_list_limit: parse_var("MAX_LIST_LIMIT", defaults.max_list_limit)?,
+            api_token: std::env::var("API_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty())
+                .or(defaults.api_token),
+            max_body_bytes: parse_var("MAX_BODY_BYTES", defaults.max_body_bytes)?,
+            assume_https: parse_var("ASSUME_HTTPS", defaults.assume_https)?,
+            preserve_url_input: parse_var("PRESERVE_URL_INPUT", defaults.preserve_url_input)?,
+            case_insensitive_ids: parse_var("CASE_INSENSITIVE_IDS", defaults.case_insensitive_ids)?,
+            id_strategy: parse_id_strategy(defaults.id_strategy)?,
+            id_checksum: parse_var("ID_CHECKSUM", defaults.id_checksum)?,
+            opaque_not_found: parse_var("OPAQUE_NOT_FOUND", defaults.opaque_not_found)?,
+            problem_json: parse_var("PROBLEM_JSON", defaults.problem_json)?,
+            legacy_create_status: parse_var("LEGACY_CREATE_STATUS", defaults.legacy_create_status)?,
+            tombstones: parse_var("TOMBSTONES", defaults.tombstones)?,
+            tombstone_ttl_seconds: parse_optional_var("TOMBSTONE_TTL_SECONDS")?
+                .or(defaults.tombstone_ttl_seconds),
+            max_entries: parse_optional_var("MAX_ENTRIES")?.or(defaults.max_entries),
+            request_timeout_seconds: parse_var(
+                "REQUEST_TIMEOUT_SECONDS",
+                defaults.request_timeout_seconds,
+            )?,
+            max_concurrent_requests: parse_optional_var("MAX_CONCURRENT_REQUESTS")?
+                .or(defaults.max_concurrent_requests),
+            expiry_sweep_interval_seconds: parse_var(
+                "EXPIRY_SWEEP_INTERVAL_SECONDS",
+                defaults.expiry_sweep_interval_seconds,
+            )?,
+            webhook_url: std::env::var("WEBHOOK_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .or(defaults.webhook_url),
+            log_resolved_urls: parse_var("LOG_RESOLVED_URLS", defaults.log_resolved_urls)?,
+            redact_query_params: parse_list("REDACT_QUERY_PARAMS", defaults.redact_query_params),
+            blocked_paths: parse_list("BLOCKED_PATHS", defaults.blocked_paths),
+            upstream_url: std::env::var("UPSTREAM_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .or(defaults.upstream_url),
+            root_redirect: std::env::var("ROOT_REDIRECT")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .or(defaults.root_redirect),
+        };
+        check_id_checksum(&config)?;
+
+        Ok(config)
+    }
+}
+
+/// Check characters need an alphabet of at least two chars and room for
+/// one more char after the id, judged on the strategy that will run.
+fn check_id_checksum(config: &Config) -> Result<(), ConfigError> {
+    let strategy = match config.case_insensitive_ids {
+        true => config.id_strategy.clone().lowercase(),
+        false => config.id_strategy.clone(),
+    };
+    if !config.id_checksum || strategy.checksum().is_some() {
+        return Ok(());
+    }
+
+    Err(ConfigError::Unsupported {
+        name: "ID_CHECKSUM".to_owned(),
+        reason: format!(
+            "ids need an alphabet of at least two characters and ID_SIZE below {}",
+            MAX_ID_LENGTH
+        ),
+    })
+}
+
+fn parse_var<T>(name: &str, default: T) -> Result<T, ConfigError>
+where
+    T: std::str::FromStr,
+{
+    match std::env::var(name) {
+        Ok(value) => value.parse().map_err(|_| ConfigError::InvalidVar {
+            name: name.to_owned(),
+            value,
+        }),
+        Err(_) => Ok(default),
+    }
+}
+
+fn parse_optional_var<T>(name: &str) -> Result<Option<T>, ConfigError>
+where
+    T: std::str::FromStr,
+{
+    match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| ConfigError::InvalidVar {
+                name: name.to_owned(),
+                value,
+            }),
+        Err(_) => Ok(None),
+    }
+}
+
+/// `ID_STRATEGY` picks `nano`, `sequential` or `hash`. Without it the older
+/// `SEQUENTIAL_IDS` and `UNAMBIGUOUS_IDS` flags still apply. `ID_SIZE` and
+/// `ID_ALPHABET` only shape nano ids.
+fn parse_id_strategy(default: IdStrategy) -> Result<IdStrategy, ConfigError> {
+    let name = match std::env::var("ID_STRATEGY") {
+        Ok(name) => name,
+        Err(_) if parse_var("SEQUENTIAL_IDS", false)? => "sequential".to_owned(),
+        Err(_) if parse_var("UNAMBIGUOUS_IDS", false)? => "nano".to_owned(),
+        Err(_) => return Ok(default),
+    };
+
+    match name.to_ascii_lowercase().as_str() {
+        "nano" => {
+            let size = parse_var("ID_SIZE", DEFAULT_ID_SIZE)?;
+            if !(1..=MAX_ID_LENGTH).contains(&size) {
+                return Err(ConfigError::InvalidVar {
+                    name: "ID_SIZE".to_owned(),
+                    value: size.to_string(),
+                });
+            }
+            let alphabet: Vec<char> = match std::env::var("ID_ALPHABET") {
+                Ok(value) => value.chars().collect(),
+                Err(_) if parse_var("UNAMBIGUOUS_IDS", false)? => UNAMBIGUOUS_ALPHABET.to_vec(),
+                Err(_) => nanoid::alphabet::SAFE.to_vec(),
+            };
+            if alphabet.is_empty() || !alphabet.iter().all(|c| ShortId::is_valid_char(*c)) {
+                return Err(ConfigError::InvalidVar {
+                    name: "ID_ALPHABET".to_owned(),
+                    value: alphabet.into_iter().collect(),
+                });
+            }
+
+            Ok(IdStrategy::Nano { size, alphabet })
+        }
+        "sequential" => Ok(IdStrategy::Sequential),
+        "hash" => Ok(IdStrategy::Hash),
+        _ => Err(ConfigError::InvalidVar {
+            name: "ID_STRATEGY".to_owned(),
+            value: name,
+        }),
+    }
+}
+
+fn parse_list(name: &str, default: Vec<String>) -> Vec<String> {
+    let Ok(value) = std::env::var(name) else {
+        return default;
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+fn parse_origins(name: &str, default: Vec<String>) -> Result<Vec<String>, ConfigError> {
+    let Ok(value) = std::env::var(name) else {
+        return Ok(default);
+    };
+
+    let origins: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(|origin| origin.trim_end_matches('/').to_owned())
+        .collect();
+    if origins
+        .iter()
+        .any(|origin| url::Url::parse(origin).is_err())
+    {
+        return Err(ConfigError::InvalidVar {
+            name: name.to_owned(),
+            value,
+        });
+    }
+
+    Ok(origins)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn with_env<F>(vars: &[(&str, Option<&str>)], f: F)
+    where
+        F: FnOnce(),
+    {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        for (name, value) in vars {
+            // SAFETY: tests touching the environment are serialized by ENV_LOCK.
+            unsafe {
+                match value {
+                    Some(value) => std::env::set_var(name, value),
+                    None => std::env::remove_var(name),
+                }
+            }
+        }
+
+        f();
+
+        for (name, _) in vars {
+            // SAFETY: tests touching the environment are serialized by ENV_LOCK.
+            unsafe { std::env::remove_var(name) };
+        }
+    }
+
+    #[test]
+    fn from_env_reads_vars() {
+        with_env(
+            &[
+                ("PORT", Some("8080")),
+                ("BIND_ADDR", Some("127.0.0.1")),
+                ("BASE_URL", Some("https://sho.rt")),
+                ("LOG_FORMAT", Some("json")),
+                ("RATE_LIMIT_REQUESTS", Some("10")),
+                ("RATE_LIMIT_WINDOW_SECONDS", Some("30")),
+                (
+                    "ALLOWED_ORIGINS",
+                    Some("https://app.sho.rt, https://admin.sho.rt/"),
+                ),
+                ("MAX_LIST_LIMIT", Some("25")),
+                ("API_TOKEN", Some("secret")),
+                ("MAX_BODY_BYTES", Some("1024")),
+                ("ASSUME_HTTPS", Some("true")),
+                ("PRESERVE_URL_INPUT", Some("true")),
+                ("CASE_INSENSITIVE_IDS", Some("true")),
+                ("ID_STRATEGY", Some("hash")),
+                ("ID_CHECKSUM", Some("true")),
+                ("OPAQUE_NOT_FOUND", Some("true")),
+                ("PROBLEM_JSON", Some("true")),
+                ("LEGACY_CREATE_STATUS", Some("true")),
+                ("TOMBSTONES", Some("true")),
+                ("TOMBSTONE_TTL_SECONDS", Some("86400")),
+                ("MAX_ENTRIES", Some("100000")),
+                ("REQUEST_TIMEOUT_SECONDS", Some("2")),
+                ("MAX_CONCURRENT_REQUESTS", Some("64")),
+                ("EXPIRY_SWEEP_INTERVAL_SECONDS", Some("0")),
+                ("WEBHOOK_URL", Some("https://hooks.sho.rt/created")),
+                ("LOG_RESOLVED_URLS", Some("true")),
+                ("REDACT_QUERY_PARAMS", Some("token, sig")),
+                ("BLOCKED_PATHS", Some(".php, /.env")),
+                ("UPSTREAM_URL", Some("https://upstream.sho.rt")),
+                ("ROOT_REDIRECT", Some("https://about.sho.rt")),
+            ],
+            || {
+                // When
+                let config = Config::from_env().unwrap();
+
+                // Then
+                assert_eq!(config.port, 8080);
+                assert_eq!(config.bind_addr, "127.0.0.1");
+                assert_eq!(config.base_url, "https://sho.rt");
+                assert_eq!(config.log_format, LogFormat::Json);
+                assert_eq!(config.rate_limit_requests, 10);
+                assert_eq!(config.rate_limit_window_seconds, 30);
+                assert_eq!(
+                    config.allowed_origins,
+                    vec!["https://app.sho.rt", "https://admin.sho.rt"]
+                );
+                assert_eq!(config.max_list_limit, 25);
+                assert_eq!(config.api_token, Some("secret".to_owned()));
+                assert_eq!(config.max_body_bytes, 1024);
+                assert!(config.assume_https);
+                assert!(config.preserve_url_input);
+                assert!(config.case_insensitive_ids);
+                assert_eq!(config.id_strategy, IdStrategy::Hash);
+                assert!(config.id_checksum);
+                assert!(config.opaque_not_found);
+                assert!(config.problem_json);
+                assert!(config.legacy_create_status);
+                assert!(config.tombstones);
+                assert_eq!(config.tombstone_ttl_seconds, Some(86400));
+                assert_eq!(config.max_entries, Some(100000));
+                assert_eq!(config.request_timeout_seconds, 2);
+                assert_eq!(config.max_concurrent_requests, Some(64));
+                assert_eq!(config.expiry_sweep_interval_seconds, 0);
+                assert_eq!(
+                    config.webhook_url,
+                    Some("https://hooks.sho.rt/created".to_owned())
+                );
+                assert!(config.log_resolved_urls);
+                assert_eq!(config.redact_query_params, vec!["token", "sig"]);
+                assert_eq!(config.blocked_paths, vec![".php", "/.env"]);
+                assert_eq!(
+                    config.upstream_url,
+                    Some("https://upstream.sho.rt".to_owned())
+                );
+                assert_eq!(
+                    config.root_redirect,
+                    Some("https://about.sho.rt".to_owned())
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn from_env_falls_back_to_defaults() {
+        with_env(
+            &[
+                ("PORT", None),
+                ("BIND_ADDR", None),
+                ("BASE_URL", None),
+                ("LOG_FORMAT", None),
+                ("RATE_LIMIT_REQUESTS", None),
+                ("RATE_LIMIT_WINDOW_SECONDS", None),
+                ("ALLOWED_ORIGINS", None),
+                ("MAX_LIST_LIMIT", None),
+                ("API_TOKEN", None),
+                ("MAX_BODY_BYTES", None),
+                ("ASSUME_HTTPS", None),
+                ("PRESERVE_URL_INPUT", None),
+                ("CASE_INSENSITIVE_IDS", None),
+                ("ID_STRATEGY", None),
+                ("ID_CHECKSUM", None),
+                ("SEQUENTIAL_IDS", None),
+                ("UNAMBIGUOUS_IDS", None),
+                ("OPAQUE_NOT_FOUND", None),
+                ("PROBLEM_JSON", None),
+                ("LEGACY_CREATE_STATUS", None),
+                ("TOMBSTONES", None),
+                ("TOMBSTONE_TTL_SECONDS", None),
+                ("MAX_ENTRIES", None),
+                ("REQUEST_TIMEOUT_SECONDS", None),
+                ("MAX_CONCURRENT_REQUESTS", None),
+                ("EXPIRY_SWEEP_INTERVAL_SECONDS", None),
+                ("WEBHOOK_URL", None),
+                ("LOG_RESOLVED_URLS", None),
+                ("REDACT_QUERY_PARAMS", None),
+                ("BLOCKED_PATHS", None),
+                ("UPSTREAM_URL", None),
+                ("ROOT_REDIRECT", None),
+            ],
+            || {
+                // When
+                let config = Config::from_env().unwrap();
+
+                // Then
+                assert_eq!(config.port, 3000);
+                assert_eq!(config.bind_addr, "0.0.0.0");
+                assert_eq!(config.base_url, "http://localhost:3000");
+                assert_eq!(config.log_format, LogFormat::Pretty);
+                assert_eq!(config.rate_limit_requests, 60);
+                assert_eq!(config.rate_limit_window_seconds, 60);
+                assert!(config.allowed_origins.is_empty());
+                assert_eq!(config.max_list_limit, 100);
+                assert_eq!(config.api_token, None);
+                assert_eq!(config.max_body_bytes, 8192);
+                assert!(!config.assume_https);
+                assert!(!config.preserve_url_input);
+                assert!(!config.case_insensitive_ids);
+                assert_eq!(config.id_strategy, IdStrategy::default());
+                assert!(!config.id_checksum);
+                assert!(!config.opaque_not_found);
+                assert!(!config.problem_json);
+                assert!(!config.legacy_create_status);
+                assert!(!config.tombstones);
+                assert_eq!(config.tombstone_ttl_seconds, None);
+                assert_eq!(config.max_entries, None);
+                assert_eq!(config.request_timeout_seconds, 5);
+                assert_eq!(config.max_concurrent_requests, None);
+                assert_eq!(config.expiry_sweep_interval_seconds, 60);
+                assert_eq!(config.webhook_url, None);
+                assert!(!config.log_resolved_urls);
+                assert!(config.redact_query_params.is_empty());
+                assert!(config.blocked_paths.is_empty());
+                assert_eq!(config.upstream_url, None);
+                assert_eq!(config.root_redirect, None);
+            },
+        );
+    }
+
+    #[test]
+    fn from_env_rejects_invalid_port() {
+        with_env(&[("PORT", Some("not-a-port")), ("BIND_ADDR", None)], || {
+            // When
+            let config = Config::from_env();
+
+            // Then
+            assert_eq!(
+                config,
+                Err(ConfigError::InvalidVar {
+                    name: "PORT".to_owned(),
+                    value: "not-a-port".to_owned(),
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn from_env_rejects_invalid_log_format() {
+        with_env(&[("PORT", None), ("LOG_FORMAT", Some("xml"))], || {
+            // When
+            let config = Config::from_env();
+
+            // Then
+            assert_eq!(
+                config,
+                Err(ConfigError::InvalidVar {
+                    name: "LOG_FORMAT".to_owned(),
+                    value: "xml".to_owned(),
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn from_env_rejects_invalid_allowed_origins() {
+        with_env(
+            &[("PORT", None), ("ALLOWED_ORIGINS", Some("not an origin"))],
+            || {
+                // When
+                let config = Config::from_env();
+
+                // Then
+                assert_eq!(
+                    config,
+                    Err(ConfigError::InvalidVar {
+                        name: "ALLOWED_ORIGINS".to_owned(),
+                        value: "not an origin".to_owned(),
+                    })
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn from_env_reads_nano_id_strategy() {
+        with_env(
+            &[
+                ("PORT", None),
+                ("ID_STRATEGY", Some("nano")),
+                ("ID_SIZE", Some("10")),
+                ("ID_ALPHABET", Some("abc")),
+            ],
+            || {
+                // When
+                let config = Config::from_env().unwrap();
+
+                // Then
+                assert_eq!(
+                    config.id_strategy,
+                    IdStrategy::Nano {
+                        size: 10,
+                        alphabet: vec!['a', 'b', 'c'],
+                    }
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn from_env_keeps_legacy_id_flags() {
+        with_env(
+            &[
+                ("PORT", None),
+                ("ID_STRATEGY", None),
+                ("SEQUENTIAL_IDS", Some("true")),
+            ],
+            || {
+                // When
+                let config = Config::from_env().unwrap();
+
+                // Then
+                assert_eq!(config.id_strategy, IdStrategy::Sequential);
+            },
+        );
+    }
+
+    #[test]
+    fn from_env_rejects_invalid_id_strategy() {
+        with_env(
+            &[
+                ("PORT", None),
+                ("ID_STRATEGY", Some("nano")),
+                ("ID_ALPHABET", Some("a.b")),
+            ],
+            || {
+                // When
+                let config = Config::from_env();
+
+                // Then
+                assert_eq!(
+                    config,
+                    Err(ConfigError::InvalidVar {
+                        name: "ID_ALPHABET".to_owned(),
+                        value: "a.b".to_owned(),
+                    })
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn from_env_rejects_checksum_without_room_or_alphabet() {
+        let cases = [
+            ("64", "abc", "false"),
+            ("7", "a", "false"),
+            ("7", "AB", "true"),
+        ];
+
+        for (size, alphabet, case_insensitive) in cases {
+            with_env(
+                &[
+                    ("PORT", None),
+                    ("ID_STRATEGY", Some("nano")),
+                    ("ID_SIZE", Some(size)),
+                    ("ID_ALPHABET", Some(alphabet)),
+                    ("CASE_INSENSITIVE_IDS", Some(case_insensitive)),
+                    ("ID_CHECKSUM", Some("true")),
+                ],
+                || {
+                    // When
+                    let config = Config::from_env();
+
+                    // Then
+                    assert_eq!(
+                        config,
+                        Err(ConfigError::Unsupported {
+                            name: "ID_CHECKSUM".to_owned(),
+                            reason: "ids need an alphabet of at least two characters and ID_SIZE below 64"
+                                .to_owned(),
+                        }),
+                        "ID_SIZE={size} ID_ALPHABET={alphabet}"
+                    );
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{
+    FutureExt, StreamExt,
+    future::BoxFuture,
+    stream::{BoxStream, Stream},
+};
+
+use super::{CommandRepository, Container, QueryRepository};
+use crate::{
+    app::{
+        command::{
+            create_short_url::CreateShortUrlRepository,
+            delete_short_url::DeleteShortUrlRepository,
+            patch_short_url::{PatchShortUrlRepository, UrlPatch},
+            purge_expired::PurgeExpiredRepository,
+            record_access::{AccessEntry, AccessLogRepository},
+            record_click::RecordClickRepository,
+            set_enabled::SetEnabledRepository,
+            update_short_url::UpdateShortUrlRepository,
+        },
+        domain::{LinkMetadata, ShortId, StoredUrl, ValidUrl},
+        query::{
+            export::ExportRepository, get_full_url::GetFullUrlRepository,
+            get_stats::GetStatsRepository, health_check::HealthCheckRepository,
+            list_urls::ListUrlsRepository, reverse_lookup::ReverseLookupRepository,
+        },
+    },
+    error::AppError,
+    id_provider::IdProvider,
+};
+
+pub trait DynCommandRepository:
+    AccessLogRepository
+    + CreateShortUrlRepository
+    + DeleteShortUrlRepository
+    + PatchShortUrlRepository
+    + PurgeExpiredRepository
+    + RecordClickRepository
+    + SetEnabledRepository
+    + UpdateShortUrlRepository
+    + Send
+    + Sync
+{
+}
+
+impl<T> DynCommandRepository for T where
+    T: AccessLogRepository
+        + CreateShortUrlRepository
+        + DeleteShortUrlRepository
+        + PatchShortUrlRepository
+        + PurgeExpiredRepository
+        + RecordClickRepository
+        + SetEnabledRepository
+        + UpdateShortUrlRepository
+        + Send
+        + Sync
+{
+}
+
+/// Object-safe mirror of [`QueryRepository`], whose methods return
+/// `impl Future` and therefore cannot be called through `dyn`.
+pub trait DynQueryRepository: AccessLogRepository + Send + Sync {
+    fn export_boxed(&self) -> BoxStream<'static, Result<(String, String), AppError>>;
+    fn get_record_boxed<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<StoredUrl, AppError>>;
+    fn clicks_boxed<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<u64, AppError>>;
+    fn ping_boxed(&self) -> BoxFuture<'_, Result<(), AppError>>;
+    fn list_boxed(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<Vec<(String, String)>, AppError>>;
+    fn count_boxed(&self) -> BoxFuture<'_, Result<usize, AppError>>;
+    fn ids_for_url_boxed<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>, AppError>>;
+}
+
+impl<T> DynQueryRepository for T
+where
+    T: QueryRepository,
+{
+    fn export_boxed(&self) -> BoxStream<'static, Result<(String, String), AppError>> {
+        self.export().boxed()
+    }
+
+    fn get_record_boxed<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<StoredUrl, AppError>> {
+        self.get_record(id).boxed()
+    }
+
+    fn clicks_boxed<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<u64, AppError>> {
+        self.clicks(id).boxed()
+    }
+
+    fn ping_boxed(&self) -> BoxFuture<'_, Result<(), AppError>> {
+        self.ping().boxed()
+    }
+
+    fn list_boxed(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<Vec<(String, String)>, AppError>> {
+        self.list(offset, limit).boxed()
+    }
+
+    fn count_boxed(&self) -> BoxFuture<'_, Result<usize, AppError>> {
+        self.count().boxed()
+    }
+
+    fn ids_for_url_boxed<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>, AppError>> {
+        self.ids_for_url(url).boxed()
+    }
+}
+
+pub type DynIdProvider = Box<dyn IdProvider + Send + Sync>;
+pub type DynCreateRepo = Arc<dyn CreateShortUrlRepository + Send + Sync>;
+pub type DynCommandRepo = Arc<dyn DynCommandRepository>;
+pub type DynQueryRepo = Arc<dyn DynQueryRepository>;
+pub type DynContainer = Container<DynIdProvider, DynCommandRepo, DynQueryRepo>;
+
+impl DynContainer {
+    pub fn new_dyn(
+        id_provider: impl IdProvider + Send + Sync + 'static,
+        repository: impl CommandRepository,
+        query: impl QueryRepository,
+    ) -> Self {
+        Container::new(Box::new(id_provider), Arc::new(repository), Arc::new(query))
+    }
+}
+
+impl<T> IdProvider for Box<T>
+where
+    T: IdProvider + ?Sized,
+{
+    fn provide(&self, seed: Option<&str>) -> ShortId {
+        (**self).provide(seed)
+    }
+
+    fn seed_from(&self, max: u64) {
+        (**self).seed_from(max)
+    }
+}
+
+#[async_trait]
+impl<T> AccessLogRepository for Arc<T>
+where
+    T: AccessLogRepository + Send + Sync + ?Sized,
+{
+    async fn record(
+        &self,
+        id: &str,
+        at: DateTime<Utc>,
+        user_agent: Option<String>,
+        referer: Option<String>,
+    ) -> Result<(), AppError> {
+        (**self).record(id, at, user_agent, referer).await
+    }
+
+    async fn recent(&self, id: &str, limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+        (**self).recent(id, limit).await
+    }
+}
+
+#[async_trait]
+impl<T> CreateShortUrlRepository for Arc<T>
+where
+    T: CreateShortUrlRepository + Send + Sync + ?Sized,
+{
+    async fn save<'a>(
+        &'a self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<(), AppError> {
+        (**self)
+            .save(
+                full_url,
+                id,
+                created_at,
+                expires_at,
+                password_hash,
+                metadata,
+            )
+            .await
+    }
+
+    async fn save_if_absent(
+        &self,
+        full_url: ValidUrl,
+        id: ShortId,
+        created_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        password_hash: Option<String>,
+        metadata: LinkMetadata,
+    ) -> Result<bool, AppError> {
+        (**self)
+            .save_if_absent(
+                full_url,
+                id,
+                created_at,
+                expires_at,
+                password_hash,
+                metadata,
+            )
+            .await
+    }
+
+    async fn exists(&self, id: &str) -> Result<bool, AppError> {
+        (**self).exists(id).await
+    }
+
+    async fn find_by_url(&self, url: &str) -> Result<Option<String>, AppError> {
+        (**self).find_by_url(url).await
+    }
+}
+
+#[async_trait]
+impl<T> DeleteShortUrlRepository for Arc<T>
+where
+    T: DeleteShortUrlRepository + Send + Sync + ?Sized,
+{
+    async fn delete(&self, id: &str) -> Result<(), AppError> {
+        (**self).delete(id).await
+    }
+}
+
+#[async_trait]
+impl<T> PurgeExpiredRepository for Arc<T>
+where
+    T: PurgeExpiredRepository + Send + Sync + ?Sized,
+{
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        (**self).purge_expired(now).await
+    }
+}
+
+#[async_trait]
+impl<T> RecordClickRepository for Arc<T>
+where
+    T: RecordClickRepository + Send + Sync + ?Sized,
+{
+    async fn increment(&self, id: &str) -> Result<u64, AppError> {
+        (**self).increment(id).await
+    }
+}
+
+#[async_trait]
+impl<T> SetEnabledRepository for Arc<T>
+where
+    T: SetEnabledRepository + Send + Sync + ?Sized,
+{
+    async fn set_enabled(&self, id: &str, enabled: bool) -> Result<(), AppError> {
+        (**self).set_enabled(id, enabled).await
+    }
+}
+
+#[async_trait]
+impl<T> UpdateShortUrlRepository for Arc<T>
+where
+    T: UpdateShortUrlRepository + Send + Sync + ?Sized,
+{
+    async fn update(&self, id: &str, full_url: String) -> Result<(), AppError> {
+        (**self).update(id, full_url).await
+    }
+}
+
+#[async_trait]
+impl<T> PatchShortUrlRepository for Arc<T>
+where
+    T: PatchShortUrlRepository + Send + Sync + ?Sized,
+{
+    async fn patch(&self, id: &str, patch: UrlPatch) -> Result<(), AppError> {
+        (**self).patch(id, patch).await
+    }
+}
+
+impl ExportRepository for DynQueryRepo {
+    fn export(&self) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+        (**self).export_boxed()
+    }
+}
+
+impl GetFullUrlRepository for DynQueryRepo {
+    async fn get_record(&self, id: &str) -> Result<StoredUrl, AppError> {
+        (**self).get_record_boxed(id).await
+    }
+}
+
+impl GetStatsRepository for DynQueryRepo {
+    async fn clicks(&self, id: &str) -> Result<u64, AppError> {
+        (**self).clicks_boxed(id).await
+    }
+}
+
+impl HealthCheckRepository for DynQueryRepo {
+    async fn ping(&self) -> Result<(), AppError> {
+        (**self).ping_boxed().await
+    }
+}
+
+impl ListUrlsRepository for DynQueryRepo {
+    async fn list(&self, offset: usize, limit: usize) -> Result<Vec<(String, String)>, AppError> {
+        (**self).list_boxed(offset, limit).await
+    }
+
+    async fn count(&self) -> Result<usize, AppError> {
+        (**self).count_boxed().await
+    }
+}
+
+impl ReverseLookupRepository for DynQueryRepo {
+    async fn ids_for_url(&self, url: &str) -> Result<Vec<String>, AppError> {
+        (**self).ids_for_url_boxed(url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::command::create_short_url::CreateShortUrlCommand, id_provider::FakeIdProvider,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn dyn_container_runs_commands_and_queries() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = DynContainer::new_dyn(
+            FakeIdProvider::new("123".to_owned()),
+            repository.clone(),
+            repository,
+        );
+
+        // When
+        let id = sut
+            .short_url_command
+            .execute("https://example.com", None, None)
+            .await
+            .unwrap();
+        let url = sut.get_full_url_query.execute(&id).await;
+
+        // Then
+        assert_eq!(id, "123");
+        assert_eq!(url, Ok("https://example.com/".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn single_trait_objects_drive_commands() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        let repository: DynCreateRepo = Arc::new(InMemoryRepository::new(store.clone()));
+        let id_provider: DynIdProvider = Box::new(FakeIdProvider::new("abc".to_owned()));
+        let sut = CreateShortUrlCommand::new(id_provider, repository);
+
+        // When
+        let id = sut.execute("https://example.com", None, None).await;
+
+        // Then
+        assert_eq!(id.unwrap(), "abc");
+        assert!(store.contains_key("abc"));
+    }
+}
@@ -1,31 +1,741 @@
+use std::sync::Arc;
+
 use crate::{
     app::{
-        command::create_short_url::{CreateShortUrlCommand, CreateShortUrlRepository},
-        query::get_full_url::{GetFullUrlQuery, GetFullUrlRepository},
+        command::{
+            create_short_url::{CreateShortUrlCommand, CreateShortUrlRepository},
+            delete_short_url::{DeleteShortUrlCommand, DeleteShortUrlRepository},
+            import_urls::ImportUrlsCommand,
+            patch_short_url::{PatchShortUrlCommand, PatchShortUrlRepository},
+            purge_expired::{PurgeExpiredCommand, PurgeExpiredRepository},
+            record_access::{AccessLogRepository, RecordAccessCommand},
+            record_click::{RecordClickCommand, RecordClickRepository},
+            set_enabled::{SetEnabledCommand, SetEnabledRepository},
+            update_short_url::{UpdateShortUrlCommand, UpdateShortUrlRepository},
+        },
+        domain::ShortId,
+        events::EventSink,
+        query::{
+            export::{ExportQuery, ExportRepository},
+            get_accesses::GetAccessesQuery,
+            get_full_url::{GetFullUrlQuery, GetFullUrlRepository},
+            get_preview::GetPreviewQuery,
+            get_stats::{GetStatsQuery, GetStatsRepository},
+            health_check::{HealthCheckQuery, HealthCheckRepository},
+            list_urls::{ListUrlsQuery, ListUrlsRepository},
+            reverse_lookup::{ReverseLookupQuery, ReverseLookupRepository},
+        },
+        tombstones::Tombstones,
+        url_policy::UrlPolicy,
     },
-    id_provider::IdProvider,
+    clock::Clock,
+    error::AppError,
+    id_provider::{Checksum, IdProvider},
 };
 
+pub mod dynamic;
+
+pub trait CommandRepository:
+    AccessLogRepository
+    + CreateShortUrlRepository
+    + DeleteShortUrlRepository
+    + PatchShortUrlRepository
+    + PurgeExpiredRepository
+    + RecordClickRepository
+    + SetEnabledRepository
+    + UpdateShortUrlRepository
+    + Clone
+    + Send
+    + Sync
+    + 'static
+{
+}
+
+impl<T> CommandRepository for T where
+    T: AccessLogRepository
+        + CreateShortUrlRepository
+        + DeleteShortUrlRepository
+        + PatchShortUrlRepository
+        + PurgeExpiredRepository
+        + RecordClickRepository
+        + SetEnabledRepository
+        + UpdateShortUrlRepository
+        + Clone
+        + Send
+        + Sync
+        + 'static
+{
+}
+
+pub trait QueryRepository:
+    AccessLogRepository
+    + ExportRepository
+    + GetFullUrlRepository
+    + GetStatsRepository
+    + HealthCheckRepository
+    + ListUrlsRepository
+    + ReverseLookupRepository
+    + Clone
+    + Send
+    + Sync
+    + 'static
+{
+}
+
+impl<T> QueryRepository for T where
+    T: AccessLogRepository
+        + ExportRepository
+        + GetFullUrlRepository
+        + GetStatsRepository
+        + HealthCheckRepository
+        + ListUrlsRepository
+        + ReverseLookupRepository
+        + Clone
+        + Send
+        + Sync
+        + 'static
+{
+}
+
 pub struct Container<I, R, Q>
 where
     I: IdProvider,
-    R: CreateShortUrlRepository,
-    Q: GetFullUrlRepository,
+    R: CommandRepository,
+    Q: QueryRepository,
 {
     pub short_url_command: CreateShortUrlCommand<I, R>,
+    pub delete_short_url_command: DeleteShortUrlCommand<R>,
+    pub import_urls_command: ImportUrlsCommand<R>,
+    pub patch_short_url_command: PatchShortUrlCommand<R>,
+    pub purge_expired_command: PurgeExpiredCommand<R>,
+    pub record_access_command: RecordAccessCommand<R>,
+    pub record_click_command: RecordClickCommand<R>,
+    pub set_enabled_command: SetEnabledCommand<R>,
+    pub update_short_url_command: UpdateShortUrlCommand<R>,
+    pub export_query: ExportQuery<Q>,
+    pub get_accesses_query: GetAccessesQuery<Q>,
     pub get_full_url_query: GetFullUrlQuery<Q>,
+    pub get_preview_query: GetPreviewQuery<Q>,
+    pub get_stats_query: GetStatsQuery<Q>,
+    pub health_check_query: HealthCheckQuery<Q>,
+    pub list_urls_query: ListUrlsQuery<Q>,
+    pub reverse_lookup_query: ReverseLookupQuery<Q>,
+    checksum: Option<Checksum>,
+    case_insensitive_ids: bool,
 }
 
 impl<I, R, Q> Container<I, R, Q>
 where
     I: IdProvider,
-    R: CreateShortUrlRepository,
-    Q: GetFullUrlRepository,
+    R: CommandRepository,
+    Q: QueryRepository,
 {
     pub fn new(id_provider: I, repository: R, query: Q) -> Self {
         Self {
-            short_url_command: CreateShortUrlCommand::new(id_provider, repository),
-            get_full_url_query: GetFullUrlQuery::new(query),
+            short_url_command: CreateShortUrlCommand::new(id_provider, repository.clone()),
+            delete_short_url_command: DeleteShortUrlCommand::new(repository.clone()),
+            import_urls_command: ImportUrlsCommand::new(repository.clone()),
+            patch_short_url_command: PatchShortUrlCommand::new(repository.clone()),
+            purge_expired_command: PurgeExpiredCommand::new(repository.clone()),
+            record_access_command: RecordAccessCommand::new(repository.clone()),
+            record_click_command: RecordClickCommand::new(repository.clone()),
+            set_enabled_command: SetEnabledCommand::new(repository.clone()),
+            update_short_url_command: UpdateShortUrlCommand::new(repository),
+            export_query: ExportQuery::new(query.clone()),
+            get_accesses_query: GetAccessesQuery::new(query.clone()),
+            get_full_url_query: GetFullUrlQuery::new(query.clone()),
+            get_preview_query: GetPreviewQuery::new(query.clone()),
+            get_stats_query: GetStatsQuery::new(query.clone()),
+            health_check_query: HealthCheckQuery::new(query.clone()),
+            list_urls_query: ListUrlsQuery::new(query.clone()),
+            reverse_lookup_query: ReverseLookupQuery::new(query),
+            checksum: None,
+            case_insensitive_ids: false,
+        }
+    }
+
+    /// The form of `id` every route looks up: lowercased when ids are
+    /// case-insensitive, and rejected if its check character does not
+    /// match, so every route fails the same way before the store is queried.
+    pub fn canonical_id(&self, id: ShortId) -> Result<ShortId, AppError> {
+        let id = match self.case_insensitive_ids {
+            true => id.to_ascii_lowercase(),
+            false => id,
+        };
+
+        match self
+            .checksum
+            .as_ref()
+            .is_none_or(|checksum| checksum.verify(&id))
+        {
+            true => Ok(id),
+            false => Err(AppError::InvalidChecksum),
+        }
+    }
+
+    /// Readiness of the backing store, as reported by its `ping`.
+    pub async fn health(&self) -> Result<(), AppError> {
+        self.health_check_query.execute().await
+    }
+}
+
+pub struct ContainerBuilder<I, R, Q> {
+    id_provider: I,
+    repository: R,
+    query: Q,
+    assume_https: bool,
+    preserve_url_input: bool,
+    case_insensitive_ids: bool,
+    checksum: Option<Checksum>,
+    url_policy: Option<UrlPolicy>,
+    tombstones: Option<Tombstones>,
+    clock: Option<Arc<dyn Clock>>,
+    events: Option<Arc<dyn EventSink>>,
+}
+
+impl ContainerBuilder<(), (), ()> {
+    pub fn new() -> Self {
+        Self {
+            id_provider: (),
+            repository: (),
+            query: (),
+            assume_https: false,
+            preserve_url_input: false,
+            case_insensitive_ids: false,
+            checksum: None,
+            url_policy: None,
+            tombstones: None,
+            clock: None,
+            events: None,
+        }
+    }
+}
+
+impl Default for ContainerBuilder<(), (), ()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I, R, Q> ContainerBuilder<I, R, Q> {
+    pub fn with_id_provider<I2>(self, id_provider: I2) -> ContainerBuilder<I2, R, Q>
+    where
+        I2: IdProvider,
+    {
+        ContainerBuilder {
+            id_provider,
+            repository: self.repository,
+            query: self.query,
+            assume_https: self.assume_https,
+            preserve_url_input: self.preserve_url_input,
+            case_insensitive_ids: self.case_insensitive_ids,
+            checksum: self.checksum,
+            url_policy: self.url_policy,
+            tombstones: self.tombstones,
+            clock: self.clock,
+            events: self.events,
+        }
+    }
+
+    pub fn with_command_repository<R2>(self, repository: R2) -> ContainerBuilder<I, R2, Q>
+    where
+        R2: CommandRepository,
+    {
+        ContainerBuilder {
+            id_provider: self.id_provider,
+            repository,
+            query: self.query,
+            assume_https: self.assume_https,
+            preserve_url_input: self.preserve_url_input,
+            case_insensitive_ids: self.case_insensitive_ids,
+            checksum: self.checksum,
+            url_policy: self.url_policy,
+            tombstones: self.tombstones,
+            clock: self.clock,
+            events: self.events,
+        }
+    }
+
+    pub fn with_query_repository<Q2>(self, query: Q2) -> ContainerBuilder<I, R, Q2>
+    where
+        Q2: QueryRepository,
+    {
+        ContainerBuilder {
+            id_provider: self.id_provider,
+            repository: self.repository,
+            query,
+            assume_https: self.assume_https,
+            preserve_url_input: self.preserve_url_input,
+            case_insensitive_ids: self.case_insensitive_ids,
+            checksum: self.checksum,
+            url_policy: self.url_policy,
+            tombstones: self.tombstones,
+            clock: self.clock,
+            events: self.events,
+        }
+    }
+
+    pub fn with_assume_https(mut self, assume_https: bool) -> Self {
+        self.assume_https = assume_https;
+        self
+    }
+
+    pub fn with_preserve_url_input(mut self, preserve_url_input: bool) -> Self {
+        self.preserve_url_input = preserve_url_input;
+        self
+    }
+
+    pub fn with_case_insensitive_ids(mut self, case_insensitive_ids: bool) -> Self {
+        self.case_insensitive_ids = case_insensitive_ids;
+        self
+    }
+
+    /// Verifies check characters in [`Container::canonical_id`] and appends them
+    /// to aliases. Pair
+    /// with a [`ChecksumIdProvider`](crate::id_provider::ChecksumIdProvider)
+    /// so generated ids carry one too.
+    pub fn with_checksum(mut self, checksum: Option<Checksum>) -> Self {
+        self.checksum = checksum;
+        self
+    }
+
+    /// Applies the same scheme, length and domain rules to creating and
+    /// patching links.
+    pub fn with_url_policy(mut self, url_policy: UrlPolicy) -> Self {
+        self.url_policy = Some(url_policy);
+        self
+    }
+
+    pub fn with_tombstones(mut self, tombstones: Option<Tombstones>) -> Self {
+        self.tombstones = tombstones;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    pub fn with_events(mut self, events: Arc<dyn EventSink>) -> Self {
+        self.events = Some(events);
+        self
+    }
+}
+
+impl<I, R, Q> ContainerBuilder<I, R, Q>
+where
+    I: IdProvider,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    pub fn build(self) -> Container<I, R, Q> {
+        let mut container = Container::new(self.id_provider, self.repository, self.query);
+        container.short_url_command = container
+            .short_url_command
+            .with_assume_https(self.assume_https)
+            .with_preserve_input(self.preserve_url_input)
+            .with_case_insensitive(self.case_insensitive_ids)
+            .with_checksum(self.checksum.clone());
+        container.get_full_url_query = container
+            .get_full_url_query
+            .with_case_insensitive(self.case_insensitive_ids)
+            .with_tombstones(self.tombstones.clone());
+        container.checksum = self.checksum;
+        container.case_insensitive_ids = self.case_insensitive_ids;
+        container.delete_short_url_command = container
+            .delete_short_url_command
+            .with_tombstones(self.tombstones);
+        if let Some(url_policy) = self.url_policy {
+            container.short_url_command = container
+                .short_url_command
+                .with_url_policy(url_policy.clone());
+            container.update_short_url_command = container
+                .update_short_url_command
+                .with_url_policy(url_policy.clone());
+            container.patch_short_url_command = container
+                .patch_short_url_command
+                .with_url_policy(url_policy);
+        }
+        if let Some(clock) = self.clock {
+            container.short_url_command = container.short_url_command.with_clock(clock.clone());
+            container.import_urls_command = container.import_urls_command.with_clock(clock.clone());
+            container.patch_short_url_command =
+                container.patch_short_url_command.with_clock(clock.clone());
+            container.purge_expired_command =
+                container.purge_expired_command.with_clock(clock.clone());
+            container.record_access_command =
+                container.record_access_command.with_clock(clock.clone());
+            container.get_full_url_query = container.get_full_url_query.with_clock(clock.clone());
+            container.get_preview_query = container.get_preview_query.with_clock(clock);
+        }
+        if let Some(events) = self.events {
+            container.short_url_command = container.short_url_command.with_events(events.clone());
+            container.get_full_url_query = container.get_full_url_query.with_events(events);
+        }
+
+        container
+    }
+}
+
+#[cfg(test)]
+pub mod testing {
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use futures::{Stream, stream};
+
+    use super::*;
+    use crate::app::{command::record_access::AccessEntry, domain::StoredUrl};
+
+    /// A query store whose backend cannot be reached.
+    #[derive(Clone)]
+    pub struct UnreachableRepository;
+
+    pub fn unreachable_error() -> AppError {
+        AppError::RepositoryError("connection refused".to_owned())
+    }
+
+    #[async_trait]
+    impl AccessLogRepository for UnreachableRepository {
+        async fn record(
+            &self,
+            _id: &str,
+            _at: DateTime<Utc>,
+            _user_agent: Option<String>,
+            _referer: Option<String>,
+        ) -> Result<(), AppError> {
+            Err(unreachable_error())
+        }
+
+        async fn recent(&self, _id: &str, _limit: usize) -> Result<Vec<AccessEntry>, AppError> {
+            Err(unreachable_error())
+        }
+    }
+
+    impl ExportRepository for UnreachableRepository {
+        fn export(
+            &self,
+        ) -> impl Stream<Item = Result<(String, String), AppError>> + Send + 'static {
+            stream::once(async { Err(unreachable_error()) })
+        }
+    }
+
+    impl GetFullUrlRepository for UnreachableRepository {
+        async fn get_record(&self, _id: &str) -> Result<StoredUrl, AppError> {
+            Err(unreachable_error())
+        }
+    }
+
+    impl GetStatsRepository for UnreachableRepository {
+        async fn clicks(&self, _id: &str) -> Result<u64, AppError> {
+            Err(unreachable_error())
+        }
+    }
+
+    impl HealthCheckRepository for UnreachableRepository {
+        async fn ping(&self) -> Result<(), AppError> {
+            Err(unreachable_error())
         }
     }
+
+    impl ListUrlsRepository for UnreachableRepository {
+        async fn list(
+            &self,
+            _offset: usize,
+            _limit: usize,
+        ) -> Result<Vec<(String, String)>, AppError> {
+            Err(unreachable_error())
+        }
+
+        async fn count(&self) -> Result<usize, AppError> {
+            Err(unreachable_error())
+        }
+    }
+
+    impl ReverseLookupRepository for UnreachableRepository {
+        async fn ids_for_url(&self, _url: &str) -> Result<Vec<String>, AppError> {
+            Err(unreachable_error())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::{Duration, TimeZone, Utc};
+    use dashmap::DashMap;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::{
+            command::patch_short_url::ShortUrlPatch,
+            events::testing::{Event, RecordingSink},
+        },
+        clock::FakeClock,
+        di::testing::{UnreachableRepository, unreachable_error},
+        id_provider::FakeIdProvider,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn healthy_container() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = Container::new(
+            FakeIdProvider::new("123".to_owned()),
+            repository.clone(),
+            repository,
+        );
+
+        // When
+        let result = sut.health().await;
+
+        // Then
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn unreachable_store_is_not_ready() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = Container::new(
+            FakeIdProvider::new("123".to_owned()),
+            repository,
+            UnreachableRepository,
+        );
+
+        // When
+        let result = sut.health().await;
+
+        // Then
+        assert_eq!(result, Err(unreachable_error()));
+    }
+
+    #[tokio::test]
+    async fn builder_wires_commands_and_queries() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ContainerBuilder::new()
+            .with_query_repository(repository.clone())
+            .with_id_provider(FakeIdProvider::new("123".to_owned()))
+            .with_command_repository(repository)
+            .build();
+
+        // When
+        let id = sut
+            .short_url_command
+            .execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+        let result = sut.get_full_url_query.execute(&id).await;
+
+        // Then
+        assert_eq!(id, "123");
+        assert_eq!(result, Ok("https://www.google.com/".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn builder_applies_clock() {
+        // Given
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let clock = FakeClock::new(now);
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("123".to_owned()))
+            .with_command_repository(repository.clone())
+            .with_query_repository(repository)
+            .with_clock(Arc::new(clock.clone()))
+            .build();
+        let id = sut
+            .short_url_command
+            .execute("https://www.google.com", None, Some(Duration::minutes(1)))
+            .await
+            .unwrap();
+
+        // When
+        let before_expiry = sut.get_full_url_query.execute(&id).await;
+        clock.advance(Duration::minutes(1));
+        let after_expiry = sut.get_full_url_query.execute(&id).await;
+        let preview = sut.get_preview_query.execute(&id).await.unwrap();
+
+        // Then
+        assert_eq!(before_expiry, Ok("https://www.google.com/".to_owned()));
+        assert_eq!(after_expiry, Err(AppError::Expired));
+        assert_eq!(preview.created_at, Some(now));
+        assert!(preview.expired);
+    }
+
+    #[tokio::test]
+    async fn builder_applies_events() {
+        // Given
+        let (sink, mut events) = RecordingSink::channel();
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("123".to_owned()))
+            .with_command_repository(repository.clone())
+            .with_query_repository(repository)
+            .with_events(sink)
+            .build();
+
+        // When
+        let id = sut
+            .short_url_command
+            .execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+        sut.get_full_url_query.execute(&id).await.unwrap();
+
+        // Then
+        assert_eq!(
+            events.recv().await,
+            Some(Event::Created {
+                id: "123".to_owned(),
+                url: "https://www.google.com/".to_owned(),
+            })
+        );
+        assert_eq!(
+            events.recv().await,
+            Some(Event::Resolved {
+                id: "123".to_owned()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn builder_applies_assume_https() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("123".to_owned()))
+            .with_command_repository(repository.clone())
+            .with_query_repository(repository)
+            .with_assume_https(true)
+            .build();
+
+        // When
+        let id = sut
+            .short_url_command
+            .execute("example.com", None, None)
+            .await
+            .unwrap();
+        let result = sut.get_full_url_query.execute(&id).await;
+
+        // Then
+        assert_eq!(result, Ok("https://example.com/".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn builder_applies_url_policy_to_create_and_patch() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("123".to_owned()))
+            .with_command_repository(repository.clone())
+            .with_query_repository(repository)
+            .with_url_policy(
+                UrlPolicy::default().with_domain_blocklist(HashSet::from(["evil.com".to_owned()])),
+            )
+            .build();
+
+        // When
+        let created = sut
+            .short_url_command
+            .execute("https://evil.com/", None, None)
+            .await;
+        sut.short_url_command
+            .execute("https://example.com/", None, None)
+            .await
+            .unwrap();
+        let patched = sut
+            .patch_short_url_command
+            .execute(
+                "123",
+                ShortUrlPatch {
+                    url: Some("https://evil.com/".to_owned()),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Then
+        assert_eq!(created, Err(AppError::BlockedDomain));
+        assert_eq!(patched, Err(AppError::BlockedDomain));
+    }
+
+    #[tokio::test]
+    async fn builder_applies_case_insensitive_ids() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("AbC".to_owned()))
+            .with_command_repository(repository.clone())
+            .with_query_repository(repository)
+            .with_case_insensitive_ids(true)
+            .build();
+
+        // When
+        let id = sut
+            .short_url_command
+            .execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+        let result = sut.get_full_url_query.execute("ABC").await;
+
+        // Then
+        assert_eq!(id, "abc");
+        assert_eq!(result, Ok("https://www.google.com/".to_owned()));
+    }
+
+    #[test]
+    fn builder_applies_checksum_to_id_checks() {
+        // Given
+        let checksum =
+            Checksum::new("abcdefghijklmnopqrstuvwxyz0123456789".chars().collect()).unwrap();
+        let id = checksum.append("abc").unwrap();
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("123".to_owned()))
+            .with_command_repository(repository.clone())
+            .with_query_repository(repository)
+            .with_case_insensitive_ids(true)
+            .with_checksum(Some(checksum))
+            .build();
+
+        // When
+        let valid = sut.canonical_id(ShortId::parse(&id).unwrap());
+        let uppercase = sut.canonical_id(ShortId::parse(&id.to_ascii_uppercase()).unwrap());
+        let typo = sut.canonical_id(ShortId::parse(&format!("abd{}", &id[3..])).unwrap());
+
+        // Then
+        assert_eq!(valid.as_deref(), Ok(id.as_str()));
+        assert_eq!(uppercase.as_deref(), Ok(id.as_str()));
+        assert_eq!(typo, Err(AppError::InvalidChecksum));
+    }
+
+    #[tokio::test]
+    async fn builder_applies_tombstones() {
+        // Given
+        let repository = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let sut = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("123".to_owned()))
+            .with_command_repository(repository.clone())
+            .with_query_repository(repository)
+            .with_tombstones(Some(Tombstones::new(None)))
+            .build();
+        let id = sut
+            .short_url_command
+            .execute("https://www.google.com", None, None)
+            .await
+            .unwrap();
+
+        // When
+        sut.delete_short_url_command.execute(&id).await.unwrap();
+        let deleted = sut.get_full_url_query.execute(&id).await;
+        let unknown = sut.get_full_url_query.execute("456").await;
+
+        // Then
+        assert_eq!(deleted, Err(AppError::Gone));
+        assert_eq!(unknown, Err(AppError::NotFound));
+    }
 }
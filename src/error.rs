@@ -3,16 +3,136 @@ use std::fmt::{self, Display};
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum AppError {
     NotFound,
-    UrlParseError,
+    Expired,
+    Disabled,
+    Gone,
+    UrlParseError(String),
+    UnsupportedScheme,
+    UrlTooLong,
+    InvalidAlias,
+    InvalidId,
+    InvalidChecksum,
+    AliasTaken,
+    ReservedId,
+    BlockedDomain,
+    IdGenerationFailed,
+    RateLimited,
+    Unauthorized,
+    UnsupportedMediaType,
+    Timeout,
+    Validation(Vec<FieldError>),
+    RepositoryError(String),
+    Transient(String),
+}
+
+/// A rule violated by one field of a request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub code: &'static str,
+    pub message: String,
 }
 
 impl Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             AppError::NotFound => write!(f, "Not found"),
-            AppError::UrlParseError => write!(f, "URL parse error"),
+            AppError::Expired => write!(f, "Expired"),
+            AppError::Disabled => write!(f, "Disabled"),
+            AppError::Gone => write!(f, "Gone"),
+            AppError::UrlParseError(e) => write!(f, "URL parse error: {}", e),
+            AppError::UnsupportedScheme => write!(f, "Unsupported URL scheme"),
+            AppError::UrlTooLong => write!(f, "URL too long"),
+            AppError::InvalidAlias => write!(f, "Invalid alias"),
+            AppError::InvalidId => write!(f, "Invalid id"),
+            AppError::InvalidChecksum => write!(f, "Id checksum does not match"),
+            AppError::AliasTaken => write!(f, "Alias already taken"),
+            AppError::ReservedId => write!(f, "Id is reserved"),
+            AppError::BlockedDomain => write!(f, "Domain is blocked"),
+            AppError::IdGenerationFailed => write!(f, "Failed to generate a free id"),
+            AppError::RateLimited => write!(f, "Too many requests"),
+            AppError::Unauthorized => write!(f, "Unauthorized"),
+            AppError::UnsupportedMediaType => write!(f, "Unsupported media type"),
+            AppError::Timeout => write!(f, "Request timed out"),
+            AppError::Validation(_) => write!(f, "Request validation failed"),
+            AppError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+            AppError::Transient(e) => write!(f, "Transient error: {}", e),
+        }
+    }
+}
+
+impl AppError {
+    /// Stable machine-readable code, unaffected by message wording or locale.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound => "not_found",
+            AppError::Expired => "expired",
+            AppError::Disabled => "disabled",
+            AppError::Gone => "gone",
+            AppError::UrlParseError(_) => "url_parse_error",
+            AppError::UnsupportedScheme => "unsupported_scheme",
+            AppError::UrlTooLong => "url_too_long",
+            AppError::InvalidAlias => "invalid_alias",
+            AppError::InvalidId => "invalid_id",
+            AppError::InvalidChecksum => "invalid_checksum",
+            AppError::AliasTaken => "alias_taken",
+            AppError::ReservedId => "reserved_id",
+            AppError::BlockedDomain => "blocked_domain",
+            AppError::IdGenerationFailed => "id_generation_failed",
+            AppError::RateLimited => "rate_limited",
+            AppError::Unauthorized => "unauthorized",
+            AppError::UnsupportedMediaType => "unsupported_media_type",
+            AppError::Timeout => "timeout",
+            AppError::Validation(_) => "validation_failed",
+            AppError::RepositoryError(_) => "repository_error",
+            AppError::Transient(_) => "transient",
         }
     }
 }
 
+impl From<url::ParseError> for AppError {
+    fn from(e: url::ParseError) -> Self {
+        AppError::UrlParseError(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_variant_has_a_stable_code() {
+        // Given
+        let cases = [
+            (AppError::NotFound, "not_found"),
+            (AppError::Expired, "expired"),
+            (AppError::Disabled, "disabled"),
+            (AppError::Gone, "gone"),
+            (AppError::UrlParseError("bad".to_owned()), "url_parse_error"),
+            (AppError::UnsupportedScheme, "unsupported_scheme"),
+            (AppError::UrlTooLong, "url_too_long"),
+            (AppError::InvalidAlias, "invalid_alias"),
+            (AppError::InvalidId, "invalid_id"),
+            (AppError::InvalidChecksum, "invalid_checksum"),
+            (AppError::AliasTaken, "alias_taken"),
+            (AppError::ReservedId, "reserved_id"),
+            (AppError::BlockedDomain, "blocked_domain"),
+            (AppError::IdGenerationFailed, "id_generation_failed"),
+            (AppError::RateLimited, "rate_limited"),
+            (AppError::Unauthorized, "unauthorized"),
+            (AppError::UnsupportedMediaType, "unsupported_media_type"),
+            (AppError::Timeout, "timeout"),
+            (AppError::Validation(Vec::new()), "validation_failed"),
+            (
+                AppError::RepositoryError("db".to_owned()),
+                "repository_error",
+            ),
+            (AppError::Transient("db".to_owned()), "transient"),
+        ];
 
+        // Then
+        for (error, code) in cases {
+            assert_eq!(error.code(), code);
+        }
+    }
+}
@@ -1,28 +1,660 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use sha2::{Digest, Sha256};
+
+use crate::app::domain::{MAX_ID_LENGTH, ShortId};
+
 #[mockall::automock]
 pub trait IdProvider {
-    fn provide(&self) -> String;
+    #[allow(clippy::needless_lifetimes)]
+    fn provide<'a>(&self, seed: Option<&'a str>) -> ShortId;
+
+    /// Makes the next id follow `max` for providers that count upwards; a
+    /// no-op for random and hashed ids.
+    fn seed_from(&self, _max: u64) {}
+}
+
+pub const DEFAULT_ID_SIZE: usize = 7;
+
+/// Letters and digits without the look-alikes `0`/`O`/`o` and `1`/`l`/`I`,
+/// and without `-`/`_`, for ids that people read off paper and retype.
+pub const UNAMBIGUOUS_ALPHABET: [char; 56] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J', 'K', 'L',
+    'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd', 'e', 'f',
+    'g', 'h', 'i', 'j', 'k', 'm', 'n', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+pub struct NanoIdProvider {
+    size: usize,
+    alphabet: Vec<char>,
+}
+
+impl NanoIdProvider {
+    pub fn new(size: usize, alphabet: Vec<char>) -> Self {
+        assert!(
+            (1..=MAX_ID_LENGTH).contains(&size),
+            "id size must be between 1 and {MAX_ID_LENGTH}"
+        );
+        assert!(
+            alphabet.iter().all(|c| ShortId::is_valid_char(*c)),
+            "id alphabet must only contain ASCII letters, digits, '-' and '_'"
+        );
+
+        Self { size, alphabet }
+    }
 }
 
-pub struct NanoIdProvider;
+impl NanoIdProvider {
+    pub fn lowercase() -> Self {
+        let alphabet = nanoid::alphabet::SAFE
+            .iter()
+            .filter(|c| !c.is_ascii_uppercase())
+            .copied()
+            .collect();
+
+        Self::new(DEFAULT_ID_SIZE, alphabet)
+    }
+
+    pub fn unambiguous() -> Self {
+        Self::new(DEFAULT_ID_SIZE, UNAMBIGUOUS_ALPHABET.to_vec())
+    }
+}
+
+impl Default for NanoIdProvider {
+    fn default() -> Self {
+        Self::new(DEFAULT_ID_SIZE, nanoid::alphabet::SAFE.to_vec())
+    }
+}
 
 impl IdProvider for NanoIdProvider {
-    fn provide(&self) -> String {
-        nanoid::nanoid!(7)
+    fn provide(&self, _seed: Option<&str>) -> ShortId {
+        let size = self.size;
+        ShortId::parse(&nanoid::nanoid!(size, &self.alphabet))
+            .expect("alphabet and size are validated in new")
+    }
+}
+
+const BASE62_ALPHABET: &[u8; 62] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+pub struct HashIdProvider {
+    size: usize,
+    fallback: NanoIdProvider,
+}
+
+impl HashIdProvider {
+    pub fn new(size: usize) -> Self {
+        assert!(
+            (1..=MAX_ID_LENGTH).contains(&size),
+            "id size must be between 1 and {MAX_ID_LENGTH}"
+        );
+
+        Self {
+            size,
+            fallback: NanoIdProvider::new(size, nanoid::alphabet::SAFE.to_vec()),
+        }
+    }
+}
+
+impl Default for HashIdProvider {
+    fn default() -> Self {
+        Self::new(DEFAULT_ID_SIZE)
+    }
+}
+
+impl IdProvider for HashIdProvider {
+    fn provide(&self, seed: Option<&str>) -> ShortId {
+        let Some(seed) = seed else {
+            return self.fallback.provide(None);
+        };
+
+        let digest = Sha256::digest(seed.as_bytes());
+        let mut value = u128::from_be_bytes(digest[..16].try_into().unwrap());
+        let mut id = String::with_capacity(self.size);
+        while id.len() < self.size {
+            id.push(BASE62_ALPHABET[(value % 62) as usize] as char);
+            value /= 62;
+        }
+
+        ShortId::parse(&id).expect("base62 ids are valid short ids")
+    }
+}
+
+fn encode_base62(mut value: u64) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(BASE62_ALPHABET[(value % 62) as usize]);
+        value /= 62;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+
+    String::from_utf8(digits).expect("base62 alphabet is ASCII")
+}
+
+pub(crate) fn decode_base62(id: &str) -> Option<u64> {
+    id.bytes().try_fold(0u64, |value, byte| {
+        let digit = BASE62_ALPHABET.iter().position(|c| *c == byte)?;
+        value.checked_mul(62)?.checked_add(digit as u64)
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct SequentialIdProvider {
+    counter: AtomicU64,
+}
+
+impl SequentialIdProvider {
+    pub fn new(last: u64) -> Self {
+        Self {
+            counter: AtomicU64::new(last),
+        }
+    }
+
+    pub fn seeded_from<'a>(ids: impl IntoIterator<Item = &'a str>) -> Self {
+        let last = ids.into_iter().filter_map(decode_base62).max();
+
+        Self::new(last.unwrap_or(0))
+    }
+}
+
+impl IdProvider for SequentialIdProvider {
+    fn provide(&self, _seed: Option<&str>) -> ShortId {
+        let value = self.counter.fetch_add(1, Ordering::Relaxed) + 1;
+
+        ShortId::parse(&encode_base62(value)).expect("base62 ids are valid short ids")
+    }
+
+    /// Makes the next id follow `max`, unless the counter is already past it.
+    fn seed_from(&self, max: u64) {
+        self.counter.fetch_max(max, Ordering::Relaxed);
+    }
+}
+
+/// A trailing check character computed with the Luhn mod N algorithm over
+/// `alphabet`. It catches every single-character substitution and most
+/// swaps of adjacent characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    alphabet: Vec<char>,
+}
+
+impl Checksum {
+    /// `None` unless `alphabet` has at least two chars, all valid in ids.
+    pub fn new(alphabet: Vec<char>) -> Option<Self> {
+        if alphabet.len() < 2 || !alphabet.iter().all(|c| ShortId::is_valid_char(*c)) {
+            return None;
+        }
+
+        Some(Self { alphabet })
+    }
+
+    fn check_char(&self, payload: &str) -> Option<char> {
+        let n = self.alphabet.len();
+        let mut sum = 0;
+        for (i, c) in payload.chars().rev().enumerate() {
+            let code = self.alphabet.iter().position(|a| *a == c)?;
+            let addend = match i % 2 {
+                0 => code * 2,
+                _ => code,
+            };
+            sum += addend / n + addend % n;
+        }
+
+        Some(self.alphabet[(n - sum % n) % n])
+    }
+
+    /// Appends the check character to `id`, or `None` if `id` has chars
+    /// outside the alphabet or no room is left for another char.
+    pub fn append(&self, id: &str) -> Option<ShortId> {
+        let check = self.check_char(id)?;
+
+        ShortId::parse(&format!("{id}{check}")).ok()
+    }
+
+    /// Whether the last char of `id` is the check character of the rest.
+    pub fn verify(&self, id: &str) -> bool {
+        let mut chars = id.chars();
+        let Some(check) = chars.next_back() else {
+            return false;
+        };
+
+        self.check_char(chars.as_str()) == Some(check)
+    }
+}
+
+/// Appends a [`Checksum`] character to every id `inner` provides.
+pub struct ChecksumIdProvider<I> {
+    inner: I,
+    checksum: Checksum,
+}
+
+impl<I> ChecksumIdProvider<I> {
+    pub fn new(inner: I, checksum: Checksum) -> Self {
+        Self { inner, checksum }
+    }
+}
+
+impl<I> IdProvider for ChecksumIdProvider<I>
+where
+    I: IdProvider,
+{
+    fn provide(&self, seed: Option<&str>) -> ShortId {
+        let id = self.inner.provide(seed);
+
+        self.checksum
+            .append(&id)
+            .expect("IdStrategy::checksum only accepts strategies whose ids fit the checksum")
+    }
+
+    /// Stored ids carry the check character, which `max` decodes as a
+    /// trailing base62 digit; drop it before seeding `inner`.
+    fn seed_from(&self, max: u64) {
+        self.inner.seed_from(max / 62);
+    }
+}
+
+/// How generated ids are made, chosen at startup from `ID_STRATEGY`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdStrategy {
+    Nano { size: usize, alphabet: Vec<char> },
+    Sequential,
+    Hash,
+}
+
+impl Default for IdStrategy {
+    fn default() -> Self {
+        IdStrategy::Nano {
+            size: DEFAULT_ID_SIZE,
+            alphabet: nanoid::alphabet::SAFE.to_vec(),
+        }
+    }
+}
+
+impl IdStrategy {
+    /// The chars ids of this strategy are made of. Hashed ids fall back to
+    /// random nano ids when there is no seed, so they span both alphabets.
+    pub fn alphabet(&self) -> Vec<char> {
+        match self {
+            IdStrategy::Nano { alphabet, .. } => alphabet.clone(),
+            IdStrategy::Sequential => BASE62_ALPHABET.iter().map(|c| *c as char).collect(),
+            IdStrategy::Hash => nanoid::alphabet::SAFE.to_vec(),
+        }
+    }
+
+    /// The check character scheme for ids of this strategy, or `None` if
+    /// its alphabet is too small or its ids leave no room for another char.
+    pub fn checksum(&self) -> Option<Checksum> {
+        if let IdStrategy::Nano { size, .. } = self
+            && *size >= MAX_ID_LENGTH
+        {
+            return None;
+        }
+
+        Checksum::new(self.alphabet())
+    }
+
+    /// Drops uppercase letters from a nano alphabet. Sequential and hashed
+    /// ids are base62, so they give way to the default lowercase nano ids.
+    pub fn lowercase(self) -> Self {
+        let (size, alphabet) = match self {
+            IdStrategy::Nano { size, alphabet } => (size, alphabet),
+            IdStrategy::Sequential | IdStrategy::Hash => {
+                (DEFAULT_ID_SIZE, nanoid::alphabet::SAFE.to_vec())
+            }
+        };
+
+        IdStrategy::Nano {
+            size,
+            alphabet: alphabet
+                .into_iter()
+                .filter(|c| !c.is_ascii_uppercase())
+                .collect(),
+        }
+    }
+}
+
+pub fn build_id_provider(strategy: &IdStrategy) -> Box<dyn IdProvider + Send + Sync> {
+    match strategy {
+        IdStrategy::Nano { size, alphabet } => {
+            Box::new(NanoIdProvider::new(*size, alphabet.clone()))
+        }
+        IdStrategy::Sequential => Box::new(SequentialIdProvider::default()),
+        IdStrategy::Hash => Box::new(HashIdProvider::default()),
     }
 }
 
 pub struct FakeIdProvider {
-    id: String,
+    id: ShortId,
 }
 
 impl FakeIdProvider {
     pub fn new(id: String) -> Self {
-        Self { id }
+        Self {
+            id: ShortId::parse(&id).expect("fake id must be a valid short id"),
+        }
     }
 }
 
 impl IdProvider for FakeIdProvider {
-    fn provide(&self) -> String {
+    fn provide(&self, _seed: Option<&str>) -> ShortId {
         self.id.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_provides_seven_chars() {
+        // Given
+        let sut = NanoIdProvider::default();
+
+        // When
+        let id = sut.provide(None);
+
+        // Then
+        assert_eq!(id.chars().count(), 7);
+        assert!(id.chars().all(|c| nanoid::alphabet::SAFE.contains(&c)));
+    }
+
+    #[test]
+    fn provides_configured_size_and_alphabet() {
+        // Given
+        let alphabet = vec!['a', 'b', 'c'];
+        let sut = NanoIdProvider::new(12, alphabet.clone());
+
+        // When
+        let id = sut.provide(None);
+
+        // Then
+        assert_eq!(id.chars().count(), 12);
+        assert!(id.chars().all(|c| alphabet.contains(&c)));
+    }
+
+    #[test]
+    fn lowercase_provides_no_uppercase_chars() {
+        // Given
+        let sut = NanoIdProvider::lowercase();
+
+        // When
+        let ids: Vec<ShortId> = (0..100).map(|_| sut.provide(None)).collect();
+
+        // Then
+        assert!(ids.iter().all(|id| id.chars().count() == 7));
+        assert!(ids.iter().all(|id| *id == id.to_ascii_lowercase()));
+    }
+
+    #[test]
+    fn unambiguous_provides_no_confusable_chars() {
+        // Given
+        let sut = NanoIdProvider::unambiguous();
+
+        // When
+        let ids: Vec<ShortId> = (0..1000).map(|_| sut.provide(None)).collect();
+
+        // Then
+        assert!(ids.iter().all(|id| id.chars().count() == 7));
+        assert!(
+            ids.iter()
+                .flat_map(|id| id.chars())
+                .all(|c| !['0', 'O', 'o', '1', 'l', 'I', '-', '_'].contains(&c))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "id alphabet must only contain")]
+    fn rejects_alphabet_with_illegal_chars() {
+        // When
+        NanoIdProvider::new(7, vec!['a', '.']);
+    }
+
+    #[test]
+    fn hash_provides_same_id_for_same_seed() {
+        // Given
+        let sut = HashIdProvider::default();
+
+        // When
+        let first = sut.provide(Some("https://example.com/"));
+        let second = sut.provide(Some("https://example.com/"));
+
+        // Then
+        assert_eq!(first, second);
+        assert_eq!(first.chars().count(), 7);
+        assert!(first.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn hash_provides_different_ids_for_different_seeds() {
+        // Given
+        let sut = HashIdProvider::default();
+
+        // When
+        let first = sut.provide(Some("https://example.com/"));
+        let second = sut.provide(Some("https://example.org/"));
+
+        // Then
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn hash_provides_configured_size() {
+        // Given
+        let sut = HashIdProvider::new(12);
+
+        // When
+        let id = sut.provide(Some("https://example.com/"));
+
+        // Then
+        assert_eq!(id.chars().count(), 12);
+    }
+
+    #[test]
+    fn sequential_provides_increasing_base62_ids() {
+        // Given
+        let sut = SequentialIdProvider::default();
+
+        // When
+        let ids: Vec<ShortId> = (0..130).map(|_| sut.provide(None)).collect();
+
+        // Then
+        assert_eq!(ids[0], "1");
+        assert_eq!(ids[8], "9");
+        assert_eq!(ids[9], "A");
+        assert_eq!(ids[35], "a");
+        assert_eq!(ids[60], "z");
+        assert_eq!(ids[61], "10");
+        assert_eq!(ids[123], "20");
+        let values: Vec<u64> = ids.iter().map(|id| decode_base62(id).unwrap()).collect();
+        assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn sequential_continues_after_seeded_max() {
+        // Given
+        let sut = SequentialIdProvider::seeded_from(["5", "zz", "my-alias", "1A"]);
+
+        // When
+        let first = sut.provide(None);
+        let second = sut.provide(None);
+
+        // Then
+        assert_eq!(first, "100");
+        assert_eq!(second, "101");
+    }
+
+    #[test]
+    fn seed_from_resumes_after_max() {
+        // Given
+        let sut = SequentialIdProvider::default();
+
+        // When
+        sut.seed_from(61);
+        let first = sut.provide(None);
+        sut.seed_from(5);
+        let second = sut.provide(None);
+
+        // Then
+        assert_eq!(first, "10");
+        assert_eq!(second, "11");
+    }
+
+    #[test]
+    fn base62_round_trips() {
+        for value in [0, 1, 61, 62, 3843, u64::MAX] {
+            assert_eq!(decode_base62(&encode_base62(value)), Some(value));
+        }
+    }
+
+    #[test]
+    fn checksum_catches_every_single_char_substitution() {
+        // Given
+        let checksum = Checksum::new(nanoid::alphabet::SAFE.to_vec()).unwrap();
+        let id = checksum.append("V1StGXR8").unwrap().into_string();
+        let id = id.as_str();
+
+        // When
+        let corruptions: Vec<String> = (0..id.len())
+            .flat_map(|i| {
+                nanoid::alphabet::SAFE
+                    .iter()
+                    .filter(move |c| id.as_bytes()[i] != **c as u8)
+                    .map(move |c| format!("{}{c}{}", &id[..i], &id[i + 1..]))
+            })
+            .collect();
+
+        // Then
+        assert!(checksum.verify(id));
+        assert!(
+            corruptions
+                .iter()
+                .all(|corrupted| !checksum.verify(corrupted))
+        );
+    }
+
+    #[test]
+    fn checksum_provider_appends_a_valid_check_char() {
+        // Given
+        let checksum = Checksum::new(UNAMBIGUOUS_ALPHABET.to_vec()).unwrap();
+        let sut = ChecksumIdProvider::new(NanoIdProvider::unambiguous(), checksum.clone());
+
+        // When
+        let ids: Vec<ShortId> = (0..100).map(|_| sut.provide(None)).collect();
+
+        // Then
+        assert!(ids.iter().all(|id| id.len() == DEFAULT_ID_SIZE + 1));
+        assert!(ids.iter().all(|id| checksum.verify(id)));
+    }
+
+    #[test]
+    fn checksum_provider_seeds_sequential_ids_without_the_check_char() {
+        // Given
+        let checksum = IdStrategy::Sequential.checksum().unwrap();
+        let stored = checksum.append("zz").unwrap();
+        let sut = ChecksumIdProvider::new(SequentialIdProvider::default(), checksum);
+
+        // When
+        sut.seed_from(decode_base62(&stored).unwrap());
+        let next = sut.provide(None);
+
+        // Then
+        assert_eq!(&next[..next.len() - 1], "100");
+    }
+
+    #[test]
+    fn every_strategy_builds_a_working_provider() {
+        // Given
+        let strategies = [
+            IdStrategy::default(),
+            IdStrategy::Nano {
+                size: 12,
+                alphabet: UNAMBIGUOUS_ALPHABET.to_vec(),
+            },
+            IdStrategy::Sequential,
+            IdStrategy::Hash,
+        ];
+
+        for strategy in strategies {
+            // When
+            let sut = build_id_provider(&strategy);
+            let id = sut.provide(Some("https://example.com/"));
+
+            // Then
+            assert!(!id.is_empty(), "{strategy:?} produced an empty id");
+        }
+    }
+
+    #[test]
+    fn every_strategy_fits_its_checksum() {
+        // Given
+        let strategies = [
+            IdStrategy::default(),
+            IdStrategy::default().lowercase(),
+            IdStrategy::Nano {
+                size: MAX_ID_LENGTH - 1,
+                alphabet: UNAMBIGUOUS_ALPHABET.to_vec(),
+            },
+            IdStrategy::Sequential,
+            IdStrategy::Hash,
+        ];
+
+        for strategy in strategies {
+            // When
+            let checksum = strategy.checksum().unwrap();
+            let sut = ChecksumIdProvider::new(build_id_provider(&strategy), checksum.clone());
+            let ids = [sut.provide(None), sut.provide(Some("https://example.com/"))];
+
+            // Then
+            assert!(
+                ids.iter().all(|id| checksum.verify(id)),
+                "{strategy:?} produced an id without a valid check char"
+            );
+        }
+    }
+
+    #[test]
+    fn strategies_without_room_have_no_checksum() {
+        // Given
+        let full = IdStrategy::Nano {
+            size: MAX_ID_LENGTH,
+            alphabet: UNAMBIGUOUS_ALPHABET.to_vec(),
+        };
+        let single_char = IdStrategy::Nano {
+            size: 7,
+            alphabet: vec!['a'],
+        };
+
+        // Then
+        assert!(full.checksum().is_none());
+        assert!(single_char.checksum().is_none());
+        assert!(Checksum::new(vec!['a', '.']).is_none());
+    }
+
+    #[test]
+    fn sequential_strategy_can_be_seeded() {
+        // Given
+        let sut = build_id_provider(&IdStrategy::Sequential);
+
+        // When
+        sut.seed_from(61);
+
+        // Then
+        assert_eq!(sut.provide(None), "10");
+    }
+
+    #[test]
+    fn lowercase_strategy_drops_uppercase() {
+        // When
+        let strategy = IdStrategy::Sequential.lowercase();
+
+        // Then
+        let IdStrategy::Nano { size, alphabet } = strategy else {
+            panic!("expected a nano strategy");
+        };
+        assert_eq!(size, DEFAULT_ID_SIZE);
+        assert!(alphabet.iter().all(|c| !c.is_ascii_uppercase()));
+    }
+}
@@ -0,0 +1,121 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::config::{Config, LogFormat};
+
+const DEFAULT_LOG_FILTER: &str = "url_shortener=debug,tower_http=debug";
+const REDACTED: &str = "REDACTED";
+/// Entry in `REDACT_QUERY_PARAMS` that masks every query parameter.
+pub const REDACT_ALL: &str = "*";
+
+pub fn init_tracing(config: &Config) {
+    let registry = tracing_subscriber::registry().with(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| DEFAULT_LOG_FILTER.into()),
+    );
+
+    let result = match config.log_format {
+        LogFormat::Pretty => registry.with(tracing_subscriber::fmt::layer()).try_init(),
+        LogFormat::Json => registry
+            .with(tracing_subscriber::fmt::layer().json())
+            .try_init(),
+    };
+
+    if result.is_err() {
+        tracing::debug!("tracing subscriber already initialized");
+    }
+}
+
+/// Masks the values of the listed query parameters so resolved URLs can be
+/// logged without leaking tokens. The scheme, host, path and fragment are
+/// kept as is; [`REDACT_ALL`] masks every parameter.
+pub fn redact_query(url: &str, params: &[String]) -> String {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return url.to_owned();
+    };
+    if parsed.query().is_none() || params.is_empty() {
+        return url.to_owned();
+    }
+
+    let redact_all = params.iter().any(|param| param == REDACT_ALL);
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(name, value)| {
+            if redact_all || params.iter().any(|param| *param == name) {
+                (name.into_owned(), REDACTED.to_owned())
+            } else {
+                (name.into_owned(), value.into_owned())
+            }
+        })
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+
+    parsed.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_tracing_is_idempotent() {
+        // Given
+        let pretty = Config::default();
+        let json = Config {
+            log_format: LogFormat::Json,
+            ..Config::default()
+        };
+
+        // When
+        init_tracing(&pretty);
+        init_tracing(&json);
+        init_tracing(&pretty);
+
+        // Then
+        tracing::info!("still logging after repeated initialization");
+    }
+
+    #[test]
+    fn redact_query_masks_listed_params() {
+        // Given
+        let params = vec!["token".to_owned(), "sig".to_owned()];
+
+        // When
+        let redacted = redact_query(
+            "https://example.com/a/b?token=secret&page=2&sig=abc#top",
+            &params,
+        );
+
+        // Then
+        assert_eq!(
+            redacted,
+            "https://example.com/a/b?token=REDACTED&page=2&sig=REDACTED#top"
+        );
+    }
+
+    #[test]
+    fn redact_query_masks_all_params() {
+        // Given
+        let params = vec![REDACT_ALL.to_owned()];
+
+        // When
+        let redacted = redact_query("https://example.com/a/b?token=secret&page=2", &params);
+
+        // Then
+        assert_eq!(
+            redacted,
+            "https://example.com/a/b?token=REDACTED&page=REDACTED"
+        );
+    }
+
+    #[test]
+    fn redact_query_keeps_urls_without_query() {
+        // Given
+        let params = vec![REDACT_ALL.to_owned()];
+
+        // When
+        let redacted = redact_query("https://example.com/a/b", &params);
+
+        // Then
+        assert_eq!(redacted, "https://example.com/a/b");
+    }
+}
@@ -1,32 +1,116 @@
 use std::sync::Arc;
 
-use adapters::in_memory::InMemoryRepository;
+use adapters::{
+    fallback::FallbackRepository, in_memory::InMemoryRepository, upstream::UpstreamRepository,
+    webhook::WebhookEventSink,
+};
+use app::{
+    command::purge_expired::start_expiry_sweeper,
+    events::{EventSink, NoopEventSink},
+    query::export::ExportRepository,
+    tombstones::Tombstones,
+};
+use chrono::Duration;
+use clock::SystemClock;
+use config::Config;
 use dashmap::DashMap;
-use di::Container;
-use id_provider::NanoIdProvider;
+use di::{
+    ContainerBuilder,
+    dynamic::{DynCommandRepo, DynIdProvider, DynQueryRepo},
+};
+use id_provider::{ChecksumIdProvider, IdStrategy, build_id_provider};
 use ports::http_api::Server;
 
 pub mod adapters;
 pub mod app;
+pub mod clock;
+pub mod config;
 pub mod di;
 pub mod error;
 pub mod id_provider;
+pub mod logging;
+pub mod password;
 pub mod ports;
 
 #[tokio::main]
 async fn main() {
     println!("Hello, world!");
 
-    let store = Arc::new(DashMap::new());
-    let id_provider = NanoIdProvider;
-    let in_memory_repository = InMemoryRepository::new(store);
-    let container = Arc::new(Container::new(
-        id_provider,
-        in_memory_repository.clone(),
-        in_memory_repository,
-    ));
+    let config = Config::from_env().expect("invalid configuration");
+    logging::init_tracing(&config);
 
-    let server = Server::new(3000, container);
+    let store = Arc::new(DashMap::new());
+    let in_memory_repository = match config.max_entries {
+        Some(max_entries) => InMemoryRepository::new(store).with_max_entries(max_entries),
+        None => InMemoryRepository::new(store),
+    };
+    let command_repository: DynCommandRepo = Arc::new(in_memory_repository.clone());
+    let query_repository: DynQueryRepo = match &config.upstream_url {
+        Some(url) => Arc::new(FallbackRepository::new(
+            in_memory_repository,
+            UpstreamRepository::new(url),
+        )),
+        None => Arc::new(in_memory_repository),
+    };
+    let id_strategy = match (config.case_insensitive_ids, &config.id_strategy) {
+        (false, strategy) => strategy.clone(),
+        (true, strategy @ IdStrategy::Nano { .. }) => strategy.clone().lowercase(),
+        (true, strategy) => {
+            tracing::warn!("ID_STRATEGY is ignored because CASE_INSENSITIVE_IDS is set");
+            strategy.clone().lowercase()
+        }
+    };
+    // Config::from_env rejects ID_CHECKSUM for strategies without a checksum.
+    let checksum = config.id_checksum.then(|| id_strategy.checksum()).flatten();
+    let id_provider: DynIdProvider = match &checksum {
+        Some(checksum) => Box::new(ChecksumIdProvider::new(
+            build_id_provider(&id_strategy),
+            checksum.clone(),
+        )),
+        None => build_id_provider(&id_strategy),
+    };
+    if id_strategy == IdStrategy::Sequential {
+        id_provider.seed_from(
+            query_repository
+                .max_id()
+                .await
+                .expect("failed to read the highest id"),
+        );
+    }
+    let events: Arc<dyn EventSink> = match &config.webhook_url {
+        Some(url) => Arc::new(WebhookEventSink::new(url)),
+        None => Arc::new(NoopEventSink),
+    };
+    let container = Arc::new(
+        ContainerBuilder::new()
+            .with_id_provider(id_provider)
+            .with_command_repository(command_repository.clone())
+            .with_query_repository(query_repository)
+            .with_assume_https(config.assume_https)
+            .with_preserve_url_input(config.preserve_url_input)
+            .with_case_insensitive_ids(config.case_insensitive_ids)
+            .with_checksum(checksum)
+            .with_events(events)
+            .with_tombstones(config.tombstones.then(|| {
+                Tombstones::new(
+                    config
+                        .tombstone_ttl_seconds
+                        .map(|ttl| Duration::seconds(ttl as i64)),
+                )
+            }))
+            .build(),
+    );
+    let sweeper = (config.expiry_sweep_interval_seconds > 0).then(|| {
+        start_expiry_sweeper(
+            command_repository,
+            std::time::Duration::from_secs(config.expiry_sweep_interval_seconds),
+            Arc::new(SystemClock),
+        )
+    });
+    let server = Server::new(config, container);
 
     server.run().await;
+    if let Some(sweeper) = sweeper {
+        sweeper.abort();
+    }
 }
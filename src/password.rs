@@ -0,0 +1,62 @@
+use argon2::{
+    Argon2,
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
+};
+
+use crate::error::AppError;
+
+pub fn hash(password: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::RepositoryError(e.to_string()))
+}
+
+pub fn verify(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_matching_password() {
+        // Given
+        let hash = hash("correct horse").unwrap();
+
+        // When
+        let matching = verify("correct horse", &hash);
+        let wrong = verify("battery staple", &hash);
+
+        // Then
+        assert!(matching);
+        assert!(!wrong);
+        assert!(!hash.contains("correct horse"));
+    }
+
+    #[test]
+    fn salts_each_hash() {
+        // When
+        let first = hash("secret").unwrap();
+        let second = hash("secret").unwrap();
+
+        // Then
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn rejects_malformed_hash() {
+        // When
+        let result = verify("secret", "not-a-hash");
+
+        // Then
+        assert!(!result);
+    }
+}
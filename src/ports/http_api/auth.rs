@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{Method, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+
+const BEARER_PREFIX: &str = "Bearer ";
+const ADMIN_PREFIX: &str = "/admin/";
+const ACCESSES_SUFFIX: &str = "/accesses";
+
+pub async fn require_token(
+    State(api_token): State<Option<Arc<str>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(api_token) = api_token else {
+        return next.run(request).await;
+    };
+    // Writes, every admin route, reads included, since those expose the
+    // whole store, and the access log, which holds visitors' user agents
+    // and referers.
+    let protected = matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    ) || request.uri().path().starts_with(ADMIN_PREFIX)
+        || is_access_log(request.uri().path());
+    if !protected {
+        return next.run(request).await;
+    }
+
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix(BEARER_PREFIX));
+
+    match token {
+        Some(token) if constant_time_eq(token.as_bytes(), api_token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => AppError::Unauthorized.into_response(),
+    }
+}
+
+/// `/{id}/accesses`, but not a link whose id is `accesses`.
+fn is_access_log(path: &str) -> bool {
+    path.strip_suffix(ACCESSES_SUFFIX)
+        .is_some_and(|id| id.len() > 1 && !id[1..].contains('/'))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_tokens() {
+        // When
+        let same = constant_time_eq(b"secret", b"secret");
+        let different = constant_time_eq(b"secret", b"secreT");
+        let shorter = constant_time_eq(b"secret", b"secre");
+
+        // Then
+        assert!(same);
+        assert!(!different);
+        assert!(!shorter);
+    }
+
+    #[test]
+    fn matches_access_log_paths() {
+        // When
+        let log = is_access_log("/abc/accesses");
+        let link = is_access_log("/accesses");
+        let stats = is_access_log("/abc/stats");
+
+        // Then
+        assert!(log);
+        assert!(!link);
+        assert!(!stats);
+    }
+}
@@ -0,0 +1,43 @@
+use axum::{Json, response::Html};
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "URL Shortener"),
+    paths(super::shorten_url, super::get_full_url, super::resolve_short_url),
+    components(schemas(
+        super::CreateShortURLRequest,
+        super::ShortUrlResponse,
+        super::FullUrlResponse,
+        super::ResolveResponse,
+        super::ErrorResponse
+    ))
+)]
+struct ApiDoc;
+
+const SWAGGER_UI_HTML: &str = r##"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>URL Shortener API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.onload = () => {
+      window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+    };
+  </script>
+</body>
+</html>
+"##;
+
+pub async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI_HTML)
+}
@@ -0,0 +1,157 @@
+use axum::{
+    Json,
+    extract::Request,
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::{ErrorResponse, problem::Problem};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    Russian,
+}
+
+impl Locale {
+    fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split('-').next().unwrap_or_default();
+
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::English),
+            "ru" => Some(Locale::Russian),
+            _ => None,
+        }
+    }
+
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let Some(accept_language) = headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Locale::default();
+        };
+
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let tag = parts.next().filter(|tag| !tag.is_empty())?;
+                let quality = parts
+                    .find_map(|param| param.strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse().ok())?;
+
+                Some((tag, quality))
+            })
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .filter(|(_, quality)| *quality > 0.0)
+            .find_map(|(tag, _)| Locale::from_tag(tag))
+            .unwrap_or_default()
+    }
+
+    pub fn message(self, kind: &str) -> Option<&'static str> {
+        match self {
+            Locale::English => None,
+            Locale::Russian => russian(kind),
+        }
+    }
+}
+
+fn russian(kind: &str) -> Option<&'static str> {
+    let message = match kind {
+        "not-found" => "Не найдено",
+        "expired" => "Срок действия ссылки истёк",
+        "disabled" => "Ссылка отключена",
+        "gone" => "Ссылка удалена",
+        "invalid-url" => "Некорректный URL",
+        "unsupported-scheme" => "Неподдерживаемая схема URL",
+        "url-too-long" => "URL слишком длинный",
+        "invalid-alias" => "Некорректный псевдоним",
+        "invalid-id" => "Некорректный идентификатор",
+        "invalid-checksum" => "Контрольный символ идентификатора не совпадает",
+        "alias-taken" => "Псевдоним уже занят",
+        "reserved-id" => "Идентификатор зарезервирован",
+        "blocked-domain" => "Домен заблокирован",
+        "unauthorized" => "Требуется авторизация",
+        "unsupported-media-type" => "Content-Type должен быть application/json",
+        "timeout" => "Превышено время ожидания запроса",
+        "validation-failed" => "Запрос не прошёл проверку",
+        "rate-limited" => "Слишком много запросов",
+        "id-generation-failed" => "Не удалось сгенерировать свободный идентификатор",
+        "internal" => "Внутренняя ошибка сервера",
+        "unavailable" => "Сервис недоступен",
+        _ => return None,
+    };
+
+    Some(message)
+}
+
+pub async fn localize(request: Request, next: Next) -> Response {
+    let locale = Locale::from_headers(request.headers());
+    let response = next.run(request).await;
+
+    let Some(problem) = response.extensions().get::<Problem>().cloned() else {
+        return response;
+    };
+    let Some(message) = locale.message(problem.kind) else {
+        return response;
+    };
+
+    let errors = problem.errors.iter().cloned().map(Into::into).collect();
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.extensions.insert(Problem {
+        detail: message.to_owned(),
+        ..problem
+    });
+    let body = Json(ErrorResponse {
+        code: problem.code.to_owned(),
+        message: message.to_owned(),
+        errors,
+    })
+    .into_response()
+    .into_body();
+
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn locale_of(accept_language: &str) -> Locale {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT_LANGUAGE,
+            HeaderValue::from_str(accept_language).unwrap(),
+        );
+
+        Locale::from_headers(&headers)
+    }
+
+    #[test]
+    fn picks_highest_quality_supported_language() {
+        // Then
+        assert_eq!(locale_of("ru-RU,ru;q=0.9,en;q=0.8"), Locale::Russian);
+        assert_eq!(locale_of("en-US,ru;q=0.5"), Locale::English);
+        assert_eq!(locale_of("de;q=1.0, ru;q=0.7, en;q=0.3"), Locale::Russian);
+        assert_eq!(locale_of("en;q=0.2, RU;q=0.8"), Locale::Russian);
+    }
+
+    #[test]
+    fn falls_back_to_english() {
+        // Then
+        assert_eq!(Locale::from_headers(&HeaderMap::new()), Locale::English);
+        assert_eq!(locale_of("fr-FR, de"), Locale::English);
+        assert_eq!(locale_of("ru;q=0"), Locale::English);
+        assert_eq!(locale_of("ru;q=bogus"), Locale::English);
+    }
+}
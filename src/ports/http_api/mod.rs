@@ -1,81 +1,515 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
-    extract::{MatchedPath, Path, Request, State}, http::StatusCode, response::{IntoResponse, Response}, routing::{get, post}, Json, Router
+    Json, Router,
+    body::Body,
+    extract::{
+        DefaultBodyLimit, FromRequest, FromRequestParts, MatchedPath, Path, Query, Request, State,
+        rejection::JsonRejection,
+    },
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header, request::Parts},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
 };
+use base64::{Engine, prelude::BASE64_STANDARD};
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tower_http::trace::TraceLayer;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tokio::sync::oneshot;
+use tower::{limit::GlobalConcurrencyLimitLayer, util::option_layer};
+use tower_http::{
+    compression::{
+        CompressionLayer,
+        predicate::{DefaultPredicate, Predicate, SizeAbove},
+    },
+    cors::{AllowOrigin, Any, CorsLayer},
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::TraceLayer,
+};
+use utoipa::ToSchema;
 
 use crate::{
     app::{
-        command::create_short_url::CreateShortUrlRepository,
-        query::get_full_url::GetFullUrlRepository,
-    }, di::Container, error::AppError, id_provider::IdProvider
+        command::{
+            create_short_url::validate_alias,
+            import_urls::{ImportMode, ImportOutcome},
+            patch_short_url::ShortUrlPatch,
+            record_access::AccessEntry,
+        },
+        domain::{LinkMetadata, ShortId},
+        query::get_preview::UrlPreview,
+    },
+    config::Config,
+    di::{CommandRepository, Container, QueryRepository},
+    error::AppError,
+    id_provider::IdProvider,
+    logging,
 };
 
-#[derive(Deserialize, Serialize)]
+mod auth;
+mod docs;
+mod locale;
+mod path_filter;
+mod problem;
+mod qr;
+mod rate_limit;
+mod telemetry;
+mod timeout;
+mod validation;
+
+use problem::Problem;
+use rate_limit::RateLimiter;
+use validation::{FieldErrorResponse, FieldErrors, ValidationCode, ValidationError};
+
+#[derive(Deserialize, Serialize, ToSchema)]
 struct ErrorResponse {
+    code: String,
     message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<FieldErrorResponse>,
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
-            AppError::NotFound => (StatusCode::NOT_FOUND, "Not found".to_owned()),
-            AppError::UrlParseError => (StatusCode::BAD_REQUEST, "Invalid URL".to_owned()),
+        let code = self.code();
+        let mut errors = Vec::new();
+        let (status, kind, message) = match self {
+            AppError::NotFound => (StatusCode::NOT_FOUND, "not-found", "Not found".to_owned()),
+            AppError::Expired => (StatusCode::GONE, "expired", "Expired".to_owned()),
+            AppError::Disabled => (StatusCode::FORBIDDEN, "disabled", "Disabled".to_owned()),
+            AppError::Gone => (StatusCode::GONE, "gone", "Gone".to_owned()),
+            AppError::UrlParseError(e) => (
+                StatusCode::BAD_REQUEST,
+                "invalid-url",
+                format!("Invalid URL: {}", e),
+            ),
+            AppError::UnsupportedScheme => (
+                StatusCode::BAD_REQUEST,
+                "unsupported-scheme",
+                "Unsupported URL scheme".to_owned(),
+            ),
+            AppError::UrlTooLong => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "url-too-long",
+                "URL too long".to_owned(),
+            ),
+            AppError::InvalidAlias => (
+                StatusCode::BAD_REQUEST,
+                "invalid-alias",
+                "Invalid alias".to_owned(),
+            ),
+            AppError::InvalidId => (
+                StatusCode::BAD_REQUEST,
+                "invalid-id",
+                "Invalid id".to_owned(),
+            ),
+            AppError::InvalidChecksum => (
+                StatusCode::BAD_REQUEST,
+                "invalid-checksum",
+                "Id checksum does not match".to_owned(),
+            ),
+            AppError::AliasTaken => (
+                StatusCode::CONFLICT,
+                "alias-taken",
+                "Alias already taken".to_owned(),
+            ),
+            AppError::ReservedId => (
+                StatusCode::CONFLICT,
+                "reserved-id",
+                "Id is reserved".to_owned(),
+            ),
+            AppError::BlockedDomain => (
+                StatusCode::FORBIDDEN,
+                "blocked-domain",
+                "Domain is blocked".to_owned(),
+            ),
+            AppError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "unauthorized",
+                "Unauthorized".to_owned(),
+            ),
+            AppError::UnsupportedMediaType => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "unsupported-media-type",
+                "Content-Type must be application/json".to_owned(),
+            ),
+            AppError::Timeout => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "timeout",
+                "Request timed out".to_owned(),
+            ),
+            AppError::Validation(violations) => {
+                errors = violations;
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "validation-failed",
+                    "Request validation failed".to_owned(),
+                )
+            }
+            AppError::RateLimited => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate-limited",
+                "Too many requests".to_owned(),
+            ),
+            AppError::IdGenerationFailed => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "id-generation-failed",
+                "Failed to generate a free id".to_owned(),
+            ),
+            AppError::RepositoryError(e) => {
+                tracing::error!("repository error: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal",
+                    "Internal server error".to_owned(),
+                )
+            }
+            AppError::Transient(e) => {
+                tracing::warn!("transient repository error: {}", e);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "unavailable",
+                    "Service unavailable".to_owned(),
+                )
+            }
         };
 
-        (status, Json(ErrorResponse { message })).into_response()
+        let mut response = (
+            status,
+            Json(ErrorResponse {
+                code: code.to_owned(),
+                message: message.clone(),
+                errors: errors.iter().cloned().map(Into::into).collect(),
+            }),
+        )
+            .into_response();
+        response.extensions_mut().insert(Problem {
+            kind,
+            code,
+            detail: message,
+            errors,
+        });
+
+        response
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RedirectKind {
+    Permanent,
+    #[default]
+    Temporary,
+}
+
+impl RedirectKind {
+    fn status_code(self) -> StatusCode {
+        match self {
+            RedirectKind::Permanent => StatusCode::MOVED_PERMANENTLY,
+            RedirectKind::Temporary => StatusCode::FOUND,
+        }
+    }
+}
+
+struct AppState<I, R, Q>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    container: Arc<Container<I, R, Q>>,
+    redirect: RedirectKind,
+    base_url: String,
+    rate_limiter: Arc<RateLimiter>,
+    cors: CorsLayer,
+    max_list_limit: usize,
+    api_token: Option<Arc<str>>,
+    max_body_bytes: usize,
+    opaque_not_found: bool,
+    problem_json: bool,
+    legacy_create_status: bool,
+    log_resolved_urls: bool,
+    redact_query_params: Arc<[String]>,
+    blocked_paths: Arc<[String]>,
+    max_url_length: usize,
+    request_timeout: std::time::Duration,
+    concurrency_limit: Option<GlobalConcurrencyLimitLayer>,
+    root_redirect: Option<Arc<str>>,
+}
+
+impl<I, R, Q> AppState<I, R, Q>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    fn new(container: Arc<Container<I, R, Q>>, config: &Config) -> Self {
+        let max_url_length = container.short_url_command.url_policy().max_url_length();
+        Self {
+            container,
+            redirect: RedirectKind::default(),
+            base_url: config.base_url.clone(),
+            rate_limiter: Arc::new(RateLimiter::new(
+                config.rate_limit_requests,
+                std::time::Duration::from_secs(config.rate_limit_window_seconds),
+            )),
+            cors: cors_layer(&config.allowed_origins),
+            max_list_limit: config.max_list_limit,
+            api_token: config.api_token.as_deref().map(Arc::from),
+            max_body_bytes: config.max_body_bytes,
+            opaque_not_found: config.opaque_not_found,
+            problem_json: config.problem_json,
+            legacy_create_status: config.legacy_create_status,
+            log_resolved_urls: config.log_resolved_urls,
+            redact_query_params: config.redact_query_params.as_slice().into(),
+            blocked_paths: config
+                .blocked_paths
+                .iter()
+                .map(|pattern| pattern.to_ascii_lowercase())
+                .collect(),
+            max_url_length,
+            request_timeout: std::time::Duration::from_secs(config.request_timeout_seconds),
+            concurrency_limit: config
+                .max_concurrent_requests
+                .map(GlobalConcurrencyLimitLayer::new),
+            root_redirect: config.root_redirect.as_deref().map(Arc::from),
+        }
+    }
+
+    /// Parses an id from the path into its canonical form, shared by every
+    /// route that takes one.
+    fn parse_id(&self, id: &str) -> Result<ShortId, AppError> {
+        self.container.canonical_id(ShortId::parse(id)?)
+    }
+
+    fn conceal(&self, error: AppError) -> AppError {
+        match error {
+            AppError::Expired | AppError::Disabled | AppError::Gone if self.opaque_not_found => {
+                AppError::NotFound
+            }
+            error => error,
+        }
+    }
+
+    /// Hides an expired or disabled link behind a plain 404 in opaque mode,
+    /// for the routes that report on a link rather than resolve it.
+    fn visible(&self, preview: UrlPreview) -> Result<UrlPreview, AppError> {
+        match self.opaque_not_found && (preview.expired || preview.disabled) {
+            true => Err(AppError::NotFound),
+            false => Ok(preview),
+        }
+    }
+
+    async fn check_visible(&self, id: &ShortId) -> Result<(), AppError> {
+        if !self.opaque_not_found {
+            return Ok(());
+        }
+
+        let preview = self.container.get_preview_query.execute(id).await?;
+        self.visible(preview).map(|_| ())
+    }
+}
+
+impl<I, R, Q> Clone for AppState<I, R, Q>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    fn clone(&self) -> Self {
+        Self {
+            container: self.container.clone(),
+            redirect: self.redirect,
+            base_url: self.base_url.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            cors: self.cors.clone(),
+            max_list_limit: self.max_list_limit,
+            api_token: self.api_token.clone(),
+            max_body_bytes: self.max_body_bytes,
+            opaque_not_found: self.opaque_not_found,
+            problem_json: self.problem_json,
+            legacy_create_status: self.legacy_create_status,
+            log_resolved_urls: self.log_resolved_urls,
+            redact_query_params: self.redact_query_params.clone(),
+            blocked_paths: self.blocked_paths.clone(),
+            max_url_length: self.max_url_length,
+            request_timeout: self.request_timeout,
+            concurrency_limit: self.concurrency_limit.clone(),
+            root_redirect: self.root_redirect.clone(),
+        }
     }
 }
 
 pub struct Server<I, R, Q>
 where
     I: IdProvider + Send + Sync + 'static,
-    R: CreateShortUrlRepository + Send + Sync + 'static,
-    Q: GetFullUrlRepository + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
 {
-    port: u16,
+    config: Config,
     container: Arc<Container<I, R, Q>>,
+    redirect: RedirectKind,
+    shutdown: Option<oneshot::Receiver<()>>,
 }
 
 impl<I, R, Q> Server<I, R, Q>
 where
     I: IdProvider + Send + Sync + 'static,
-    R: CreateShortUrlRepository + Send + Sync + 'static,
-    Q: GetFullUrlRepository + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
 {
-    pub fn new(port: u16, container: Arc<Container<I, R, Q>>) -> Self {
-        Self { port, container }
+    pub fn new(config: Config, container: Arc<Container<I, R, Q>>) -> Self {
+        Self {
+            config,
+            container,
+            redirect: RedirectKind::default(),
+            shutdown: None,
+        }
     }
 
-    pub async fn run(self) {
-        tracing_subscriber::registry()
-            .with(
-                tracing_subscriber::EnvFilter::try_from_default_env()
-                    .unwrap_or_else(|_| "url_shortener=debug,tower_http=debug".into()),
-            )
-            .with(tracing_subscriber::fmt::layer())
-            .init();
+    pub fn with_redirect(mut self, redirect: RedirectKind) -> Self {
+        self.redirect = redirect;
+        self
+    }
+
+    pub fn with_shutdown(mut self, shutdown: oneshot::Receiver<()>) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
 
-        let router = get_router(self.container);
-        let addr = format!("0.0.0.0:{}", self.port);
+    pub async fn run(self) {
+        let router = get_router(AppState {
+            redirect: self.redirect,
+            ..AppState::new(self.container, &self.config)
+        });
+        let addr = format!("{}:{}", self.config.bind_addr, self.config.port);
         let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
 
-        axum::serve(listener, router).await.unwrap();
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown_signal(self.shutdown))
+        .await
+        .unwrap();
+    }
+}
+
+async fn shutdown_signal(shutdown: Option<oneshot::Receiver<()>>) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    let requested = async {
+        match shutdown {
+            Some(shutdown) => {
+                let _ = shutdown.await;
+            }
+            None => std::future::pending::<()>().await,
+        }
+    };
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+        _ = requested => {},
+    }
+
+    tracing::info!("shutting down");
+}
+
+fn cors_layer(allowed_origins: &[String]) -> CorsLayer {
+    if allowed_origins.is_empty() {
+        return CorsLayer::new()
+            .allow_origin(Any)
+            .allow_methods(Any)
+            .allow_headers(Any);
     }
+
+    let origins = allowed_origins
+        .iter()
+        .filter_map(|origin| HeaderValue::from_str(origin).ok())
+        .collect::<Vec<_>>();
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
+        .allow_headers([header::CONTENT_TYPE, header::ACCEPT, header::AUTHORIZATION])
 }
 
-fn get_router<I, R, Q>(container: Arc<Container<I, R, Q>>) -> Router
+const REQUEST_ID_HEADER: &str = "x-request-id";
+const COMPRESSION_MIN_BYTES: u16 = 1024;
+
+fn get_router<I, R, Q>(state: AppState<I, R, Q>) -> Router
 where
     I: IdProvider + Send + Sync + 'static,
-    R: CreateShortUrlRepository + Send + Sync + 'static,
-    Q: GetFullUrlRepository + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
 {
-    Router::new()
-        .route("/{id}", get(get_full_url))
-        .route("/", post(shorten_url))
+    let metrics_handle = telemetry::metrics_handle();
+
+    let router = Router::new()
+        .route(
+            "/{id}",
+            get(get_full_url)
+                .head(head_full_url)
+                .put(update_short_url)
+                .patch(patch_short_url)
+                .delete(delete_short_url),
+        )
+        .route("/api/resolve/{id}", get(resolve_short_url))
+        .route("/available/{id}", get(check_availability))
+        .route("/{id}/stats", get(get_stats))
+        .route("/{id}/preview", get(get_preview))
+        .route("/{id}/qr", get(get_qr))
+        .route("/{id}/share", get(get_share))
+        .route("/{id}/accesses", get(get_accesses))
+        .route("/{id}/disable", post(disable_short_url))
+        .route("/{id}/enable", post(enable_short_url))
+        .route(
+            "/",
+            get(service_info).merge(
+                post(shorten_url)
+                    .layer(DefaultBodyLimit::max(state.max_body_bytes))
+                    .layer(middleware::from_fn_with_state(
+                        state.rate_limiter.clone(),
+                        rate_limit::limit,
+                    )),
+            ),
+        )
+        .route(
+            "/batch",
+            post(shorten_batch).layer(DefaultBodyLimit::max(state.max_body_bytes)),
+        )
+        .route("/admin/urls", get(list_urls))
+        .route("/admin/reverse", get(reverse_lookup))
+        .route("/admin/export", get(export_urls))
+        .route("/admin/import", post(import_urls))
+        .route("/admin/purge-expired", post(purge_expired))
+        .route_layer(middleware::from_fn_with_state(
+            state.api_token.clone(),
+            auth::require_token,
+        ))
+        .route_layer(middleware::from_fn(telemetry::track_metrics))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(|req: &Request| {
@@ -85,41 +519,324 @@ where
                         .extensions()
                         .get::<MatchedPath>()
                         .map(|matched_path| matched_path.as_str());
+                    let request_id = req
+                        .headers()
+                        .get(REQUEST_ID_HEADER)
+                        .and_then(|value| value.to_str().ok());
 
-                    tracing::debug_span!("request", %method, %uri, matched_path)
+                    tracing::debug_span!(
+                        "request",
+                        %method,
+                        %uri,
+                        matched_path,
+                        request_id,
+                        target = tracing::field::Empty,
+                    )
                 })
                 .on_failure(()),
         )
-        .with_state(container)
+        .route("/health", get(health))
+        .route("/openapi.json", get(docs::openapi))
+        .route("/docs", get(docs::swagger_ui))
+        .route(
+            "/metrics",
+            get(move || std::future::ready(metrics_handle.render())),
+        )
+        // Requests over the limit wait for a free slot instead of being shed;
+        // the timeout below still bounds the wait and answers 503 after it.
+        .layer(option_layer(state.concurrency_limit.clone()))
+        // Scanner probes are turned away before they take a slot or a lookup.
+        .layer(option_layer((!state.blocked_paths.is_empty()).then(|| {
+            middleware::from_fn_with_state(state.blocked_paths.clone(), path_filter::reject_blocked)
+        })))
+        .layer(middleware::from_fn_with_state(
+            state.request_timeout,
+            timeout::limit,
+        ))
+        .layer(middleware::from_fn(locale::localize))
+        .layer(state.cors.clone())
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid));
+
+    let router = match state.problem_json {
+        true => router.layer(middleware::map_response(problem::render)),
+        false => router,
+    };
+    // Outermost, so no later layer rewrites a body that is already encoded.
+    let router = router.layer(
+        CompressionLayer::new()
+            .compress_when(DefaultPredicate::new().and(SizeAbove::new(COMPRESSION_MIN_BYTES))),
+    );
+
+    router.with_state(state)
+}
+
+#[derive(Deserialize, Serialize)]
+struct ServiceInfoResponse {
+    name: String,
+    version: String,
+    openapi: String,
+    docs: String,
+}
+
+async fn service_info<I, R, Q>(State(state): State<AppState<I, R, Q>>) -> Response
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    if let Some(url) = &state.root_redirect {
+        return (StatusCode::FOUND, [(header::LOCATION, url.as_ref())]).into_response();
+    }
+
+    Json(ServiceInfoResponse {
+        name: env!("CARGO_PKG_NAME").to_owned(),
+        version: env!("CARGO_PKG_VERSION").to_owned(),
+        openapi: "/openapi.json".to_owned(),
+        docs: "/docs".to_owned(),
+    })
+    .into_response()
 }
 
 #[derive(Deserialize, Serialize)]
+struct HealthResponse {
+    status: String,
+}
+
+async fn health<I, R, Q>(
+    State(state): State<AppState<I, R, Q>>,
+) -> (StatusCode, Json<HealthResponse>)
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    match state.container.health().await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(HealthResponse {
+                status: "ok".to_owned(),
+            }),
+        ),
+        Err(e) => {
+            tracing::warn!("health check failed: {}", e);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(HealthResponse {
+                    status: "unavailable".to_owned(),
+                }),
+            )
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
 struct CreateShortURLRequest {
     url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alias: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl_seconds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    dry_run: bool,
 }
 
-#[derive(Deserialize, Serialize)]
+const MAX_TITLE_LENGTH: usize = 200;
+const MAX_DESCRIPTION_LENGTH: usize = 1000;
+
+impl CreateShortURLRequest {
+    /// Checks the request shape up front and reports every invalid field at
+    /// once. Scheme and domain rules stay with the command.
+    fn validate(&self, max_url_length: usize) -> Result<(), AppError> {
+        let mut errors = FieldErrors::default();
+        errors
+            .check(
+                !self.url.trim().is_empty(),
+                "url",
+                ValidationCode::InvalidUrl,
+                "URL is required",
+            )
+            .check(
+                self.url.len() <= max_url_length,
+                "url",
+                ValidationCode::UrlTooLong,
+                format!("URL must be at most {} bytes", max_url_length),
+            )
+            .check(
+                self.alias
+                    .as_deref()
+                    .is_none_or(|a| validate_alias(a).is_ok()),
+                "alias",
+                ValidationCode::InvalidAlias,
+                "Alias must be 1-32 characters of letters, digits, '-' or '_'",
+            )
+            .check_ttl(self.ttl_seconds)
+            .check(
+                self.title
+                    .as_deref()
+                    .is_none_or(|t| t.chars().count() <= MAX_TITLE_LENGTH),
+                "title",
+                ValidationCode::TooLong,
+                format!("Title must be at most {} characters", MAX_TITLE_LENGTH),
+            )
+            .check(
+                self.description
+                    .as_deref()
+                    .is_none_or(|d| d.chars().count() <= MAX_DESCRIPTION_LENGTH),
+                "description",
+                ValidationCode::TooLong,
+                format!(
+                    "Description must be at most {} characters",
+                    MAX_DESCRIPTION_LENGTH
+                ),
+            );
+
+        errors.into_result()
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
 struct ShortUrlResponse {
     id: String,
+    short_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+fn short_url(base_url: &str, id: &str) -> String {
+    format!("{}/{}", base_url.trim_end_matches('/'), id)
 }
 
+#[utoipa::path(
+    post,
+    path = "/",
+    request_body = CreateShortURLRequest,
+    responses(
+        (status = 201, description = "Short URL created", body = ShortUrlResponse,
+            headers(("Location" = String, description = "Path of the new short URL"))),
+        (status = 200, description = "Dry run result, or created with LEGACY_CREATE_STATUS", body = ShortUrlResponse),
+        (status = 400, description = "Invalid URL, scheme or alias", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid API token", body = ErrorResponse),
+        (status = 409, description = "Alias already taken or reserved", body = ErrorResponse),
+        (status = 413, description = "Request body too large", body = ErrorResponse),
+        (status = 415, description = "Body is not JSON", body = ErrorResponse),
+        (status = 422, description = "One or more fields are invalid", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+    )
+)]
 async fn shorten_url<I, R, Q>(
-    State(container): State<Arc<Container<I, R, Q>>>,
-    Json(input): Json<CreateShortURLRequest>,
-) -> Result<Json<ShortUrlResponse>, AppError>
+    State(state): State<AppState<I, R, Q>>,
+    JsonBody(input): JsonBody<CreateShortURLRequest>,
+) -> Result<Response, AppError>
 where
     I: IdProvider + Send + Sync + 'static,
-    R: CreateShortUrlRepository + Send + Sync + 'static,
-    Q: GetFullUrlRepository + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
 {
-    container
+    input.validate(state.max_url_length)?;
+
+    if input.dry_run {
+        let (full_url, id) = state
+            .container
+            .short_url_command
+            .dry_run(&input.url, input.alias.as_deref())
+            .await?;
+
+        return Ok(Json(ShortUrlResponse {
+            short_url: short_url(&state.base_url, &id),
+            id: id.into_string(),
+            url: Some(full_url.into_string()),
+        })
+        .into_response());
+    }
+
+    let id = state
+        .container
         .short_url_command
-        .execute(&input.url)
-        .await
-        .map(|id| Json(ShortUrlResponse { id }))
+        .execute_with_metadata(
+            &input.url,
+            input.alias.as_deref(),
+            input.ttl_seconds.map(|ttl| Duration::seconds(ttl as i64)),
+            input.password.as_deref(),
+            LinkMetadata {
+                title: input.title,
+                description: input.description,
+            },
+        )
+        .await?;
+    metrics::counter!(telemetry::URLS_CREATED_TOTAL).increment(1);
+    let location = format!("/{}", id.as_str());
+    let body = Json(ShortUrlResponse {
+        short_url: short_url(&state.base_url, &id),
+        id: id.into_string(),
+        url: None,
+    });
+    if state.legacy_create_status {
+        return Ok(body.into_response());
+    }
+
+    Ok((StatusCode::CREATED, [(header::LOCATION, location)], body).into_response())
+}
+
+#[derive(Deserialize, Serialize)]
+struct BatchShortenRequest {
+    urls: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(untagged)]
+enum BatchItemResult {
+    Created { url: String, id: String },
+    Failed { url: String, error: ValidationError },
+}
+
+#[derive(Deserialize, Serialize)]
+struct BatchShortenResponse {
+    results: Vec<BatchItemResult>,
+}
+
+async fn shorten_batch<I, R, Q>(
+    State(state): State<AppState<I, R, Q>>,
+    JsonBody(input): JsonBody<BatchShortenRequest>,
+) -> Json<BatchShortenResponse>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let mut results = Vec::with_capacity(input.urls.len());
+    for url in input.urls {
+        let result = match state
+            .container
+            .short_url_command
+            .execute(&url, None, None)
+            .await
+        {
+            Ok(id) => {
+                metrics::counter!(telemetry::URLS_CREATED_TOTAL).increment(1);
+                BatchItemResult::Created {
+                    url,
+                    id: id.into_string(),
+                }
+            }
+            Err(e) => BatchItemResult::Failed {
+                url,
+                error: e.into(),
+            },
+        };
+        results.push(result);
+    }
+
+    Json(BatchShortenResponse { results })
 }
 
-#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(serde::Deserialize, serde::Serialize, ToSchema)]
 struct FullUrlResponse {
     url: String,
 }
@@ -130,132 +847,4026 @@ impl From<String> for FullUrlResponse {
     }
 }
 
+fn wants_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| {
+            accept.contains(mime::APPLICATION_JSON.as_ref())
+                && !accept.contains(mime::TEXT_HTML.as_ref())
+        })
+}
+
+const JSON_EXTENSION: &str = ".json";
+
+/// `/{id}+` shows stats instead of redirecting. `+` is never a valid id
+/// character, so the suffix cannot collide with a real id.
+const STATS_SUFFIX: char = '+';
+
+fn split_json_extension(id: String) -> (String, bool) {
+    match id.strip_suffix(JSON_EXTENSION) {
+        Some(stripped) if !stripped.is_empty() => (stripped.to_owned(), true),
+        _ => (id, false),
+    }
+}
+
+const LINK_PASSWORD_HEADER: &str = "x-link-password";
+
+#[derive(Deserialize)]
+struct LinkPasswordParams {
+    password: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(
+        ("id" = String, Path, description = "Short id, optionally suffixed with .json"),
+        ("password" = Option<String>, Query, description = "Password of a protected link"),
+        ("X-Link-Password" = Option<String>, Header, description = "Password of a protected link"),
+    ),
+    responses(
+        (status = 200, description = "Full URL, when JSON is requested", body = FullUrlResponse),
+        (status = 302, description = "Redirect to the full URL"),
+        (status = 400, description = "Invalid id", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong link password", body = ErrorResponse),
+        (status = 403, description = "Short URL disabled", body = ErrorResponse),
+        (status = 404, description = "Short URL not found", body = ErrorResponse),
+        (status = 410, description = "Short URL expired or deleted", body = ErrorResponse),
+    )
+)]
 async fn get_full_url<I, Q, R>(
     Path(id): Path<String>,
-    State(container): State<Arc<Container<I, R, Q>>>,
-) -> Result<Json<FullUrlResponse>, AppError>
+    Query(params): Query<LinkPasswordParams>,
+    State(state): State<AppState<I, R, Q>>,
+    headers: HeaderMap,
+) -> Result<Response, AppError>
 where
     I: IdProvider + Send + Sync + 'static,
-    R: CreateShortUrlRepository + Send + Sync + 'static,
-    Q: GetFullUrlRepository + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
 {
-    container
-        .get_full_url_query
-        .execute(&id)
-        .await
-        .map(|url| Json(FullUrlResponse::from(url)))
-}
+    if let Some(id) = id.strip_suffix(STATS_SUFFIX) {
+        let stats = stats(&state, state.parse_id(id)?).await?;
+        return Ok(stats.into_response());
+    }
 
-#[cfg(test)]
-mod tests {
-    use axum::{
-        body::Body,
-        extract::Request,
-        http::{Method, StatusCode, header},
-    };
-    use dashmap::DashMap;
-    use http_body_util::BodyExt;
-    use tower::ServiceExt;
+    let (id, json_extension) = split_json_extension(id);
+    let id = state.parse_id(&id)?;
+    let url = resolve(&state, &id, params, &headers).await?;
 
-    use crate::{adapters::in_memory::InMemoryRepository, id_provider::FakeIdProvider};
+    if json_extension || wants_json(&headers) {
+        return Ok(Json(FullUrlResponse::from(url)).into_response());
+    }
 
-    use super::*;
+    Ok((state.redirect.status_code(), [(header::LOCATION, url)]).into_response())
+}
 
-    fn get_router_with_mock_container() -> Router {
-        let store = Arc::new(DashMap::new());
-        store.insert("test-id".to_owned(), "test-url".to_owned());
-        store.insert("test-id-2".to_owned(), "test-url-2".to_owned());
-        let repo = InMemoryRepository::new(store);
+async fn head_full_url<I, Q, R>(
+    Path(id): Path<String>,
+    Query(params): Query<LinkPasswordParams>,
+    State(state): State<AppState<I, R, Q>>,
+    headers: HeaderMap,
+) -> Result<Response, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    if let Some(id) = id.strip_suffix(STATS_SUFFIX) {
+        let stats = stats(&state, state.parse_id(id)?).await?;
+        return Ok(stats.into_response());
+    }
 
-        let container = Container::new(
-            FakeIdProvider::new("test-id".to_owned()),
-            repo.clone(),
-            repo,
-        );
+    let (id, json_extension) = split_json_extension(id);
+    let id = state.parse_id(&id)?;
+    let url = lookup(&state, &id, params, &headers).await?;
 
-        get_router(Arc::new(container))
+    if json_extension || wants_json(&headers) {
+        return Ok(StatusCode::OK.into_response());
     }
 
-    #[tokio::test]
-    async fn test_get_full_url() {
-        // Given
-        let router = get_router_with_mock_container();
+    Ok((state.redirect.status_code(), [(header::LOCATION, url)]).into_response())
+}
 
-        // When
-        let response = router
-            .oneshot(
-                Request::builder()
-                    .uri("/test-id")
-                    .body(Body::empty())
-                    .unwrap(),
-            )
-            .await
-            .unwrap();
+#[derive(Deserialize, Serialize, ToSchema)]
+struct ResolveResponse {
+    id: String,
+    url: String,
+}
 
-        // Then
-        assert_eq!(response.status(), StatusCode::OK);
+#[utoipa::path(
+    get,
+    path = "/api/resolve/{id}",
+    params(
+        ("id" = String, Path, description = "Short id"),
+        ("password" = Option<String>, Query, description = "Password of a protected link"),
+        ("X-Link-Password" = Option<String>, Header, description = "Password of a protected link"),
+    ),
+    responses(
+        (status = 200, description = "Full URL, never redirected", body = ResolveResponse),
+        (status = 400, description = "Invalid id", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong link password", body = ErrorResponse),
+        (status = 403, description = "Short URL disabled", body = ErrorResponse),
+        (status = 404, description = "Short URL not found", body = ErrorResponse),
+        (status = 410, description = "Short URL expired or deleted", body = ErrorResponse),
+    )
+)]
+async fn resolve_short_url<I, Q, R>(
+    id: ShortId,
+    Query(params): Query<LinkPasswordParams>,
+    State(state): State<AppState<I, R, Q>>,
+    headers: HeaderMap,
+) -> Result<Json<ResolveResponse>, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let url = resolve(&state, &id, params, &headers).await?;
 
-        let body = response.into_body().collect().await.unwrap().to_bytes();
-        let body: FullUrlResponse = serde_json::from_slice(&body).unwrap();
-        assert_eq!(body.url, "test-url");
+    Ok(Json(ResolveResponse {
+        id: id.into_string(),
+        url,
+    }))
+}
+
+async fn resolve<I, Q, R>(
+    state: &AppState<I, R, Q>,
+    id: &ShortId,
+    params: LinkPasswordParams,
+    headers: &HeaderMap,
+) -> Result<String, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let url = lookup(state, id, params, headers).await?;
+    metrics::counter!(telemetry::URLS_RESOLVED_TOTAL).increment(1);
+    if state.log_resolved_urls {
+        tracing::Span::current().record(
+            "target",
+            logging::redact_query(&url, &state.redact_query_params),
+        );
     }
 
-    #[tokio::test]
-    async fn get_not_found() {
-        // Given
-        let router = get_router_with_mock_container();
+    match state.container.record_click_command.execute(id).await {
+        // Resolved from an upstream instance, which keeps its own clicks
+        // and accesses; there is nothing stored here to count them on.
+        Err(AppError::NotFound) => return Ok(url),
+        Err(e) => tracing::warn!("failed to record click for {}: {}", id, e),
+        Ok(_) => {}
+    }
+    let user_agent = header_value(headers, header::USER_AGENT);
+    let referer = header_value(headers, header::REFERER);
+    if let Err(e) = state
+        .container
+        .record_access_command
+        .execute(id, user_agent, referer)
+        .await
+    {
+        tracing::warn!("failed to record access for {}: {}", id, e);
+    }
 
-        // When
-        let response = router
-            .oneshot(
-                Request::builder()
-                    .uri("/not-found")
-                    .body(Body::empty())
-                    .unwrap(),
-            )
-            .await
-            .unwrap();
+    Ok(url)
+}
 
-        // Then
-        let body = response.into_body().collect().await.unwrap().to_bytes();
-        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
-        assert_eq!(body.message, "Not found");
-    }
+/// Resolves `id` like [`resolve`] but without counting it as a click, so
+/// link checkers probing with `HEAD` do not skew the stats.
+async fn lookup<I, Q, R>(
+    state: &AppState<I, R, Q>,
+    id: &ShortId,
+    params: LinkPasswordParams,
+    headers: &HeaderMap,
+) -> Result<String, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let password = params.password.or_else(|| {
+        headers
+            .get(LINK_PASSWORD_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned)
+    });
 
-    #[tokio::test]
-    async fn test_get_different_id() {
-        // Given
-        let router = get_router_with_mock_container();
+    state
+        .container
+        .get_full_url_query
+        .execute_with_password(id, password.as_deref())
+        .await
+        .map_err(|e| state.conceal(e))
+}
 
-        // When
-        let response = router
-            .oneshot(
-                Request::builder()
-                    .uri("/test-id-2")
-                    .body(Body::empty())
-                    .unwrap(),
-            )
+impl<I, R, Q> FromRequestParts<AppState<I, R, Q>> for ShortId
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState<I, R, Q>,
+    ) -> Result<Self, Self::Rejection> {
+        let Path(id) = Path::<String>::from_request_parts(parts, state)
             .await
-            .unwrap();
+            .map_err(|_| AppError::InvalidId)?;
 
-        // Then
-        assert_eq!(response.status(), StatusCode::OK);
-        let body = response.into_body().collect().await.unwrap().to_bytes();
-        let body: FullUrlResponse = serde_json::from_slice(&body).unwrap();
-        assert_eq!(body.url, "test-url-2");
+        state.parse_id(&id)
     }
+}
 
-    #[tokio::test]
-    async fn short_url() {
-        // Given
-        let router = get_router_with_mock_container();
+struct JsonBody<T>(T);
 
-        let create_short_url_request = CreateShortURLRequest {
+impl<S, T> FromRequest<S> for JsonBody<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(request, state).await {
+            Ok(Json(value)) => Ok(Self(value)),
+            Err(JsonRejection::MissingJsonContentType(_)) => {
+                Err(AppError::UnsupportedMediaType.into_response())
+            }
+            Err(JsonRejection::JsonDataError(e)) => Err(json_error(&e).into_response()),
+            Err(JsonRejection::JsonSyntaxError(e)) => Err(json_error(&e).into_response()),
+            Err(rejection) => Err(rejection.into_response()),
+        }
+    }
+}
+
+/// Keeps serde's own message, which names the offending field, without
+/// axum's generic prefix.
+fn json_error(rejection: &dyn std::error::Error) -> AppError {
+    let detail = rejection
+        .source()
+        .map_or_else(|| rejection.to_string(), ToString::to_string);
+
+    AppError::UrlParseError(detail)
+}
+
+fn header_value(headers: &HeaderMap, name: header::HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
+const DEFAULT_ACCESSES_LIMIT: usize = 50;
+
+#[derive(Deserialize)]
+struct AccessesParams {
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct AccessesResponse {
+    id: String,
+    accesses: Vec<AccessEntry>,
+}
+
+async fn get_accesses<I, R, Q>(
+    id: ShortId,
+    Query(params): Query<AccessesParams>,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<Json<AccessesResponse>, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_ACCESSES_LIMIT)
+        .min(state.max_list_limit);
+    state.check_visible(&id).await?;
+
+    state
+        .container
+        .get_accesses_query
+        .execute(&id, limit)
+        .await
+        .map(|accesses| {
+            Json(AccessesResponse {
+                id: id.into_string(),
+                accesses,
+            })
+        })
+}
+
+#[derive(Deserialize, Serialize)]
+struct StatsResponse {
+    id: String,
+    clicks: u64,
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct QrParams {
+    #[serde(default)]
+    format: qr::QrFormat,
+    size: Option<u32>,
+}
+
+async fn get_qr<I, R, Q>(
+    id: ShortId,
+    Query(params): Query<QrParams>,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<Response, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    state
+        .container
+        .get_full_url_query
+        .execute(&id)
+        .await
+        .map_err(|e| state.conceal(e))?;
+
+    let size = params.size.unwrap_or(qr::DEFAULT_SIZE).min(qr::MAX_SIZE);
+    let response = match qr::render(&short_url(&state.base_url, &id), params.format, size) {
+        Ok(bytes) => (
+            [(header::CONTENT_TYPE, params.format.content_type())],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("failed to render QR code for {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    };
+
+    Ok(response)
+}
+
+async fn get_stats<I, R, Q>(
+    id: ShortId,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<Json<StatsResponse>, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    stats(&state, id).await
+}
+
+async fn stats<I, R, Q>(
+    state: &AppState<I, R, Q>,
+    id: ShortId,
+) -> Result<Json<StatsResponse>, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    state.check_visible(&id).await?;
+
+    state
+        .container
+        .get_stats_query
+        .execute(&id)
+        .await
+        .map(|stats| {
+            Json(StatsResponse {
+                id: id.into_string(),
+                clicks: stats.clicks,
+                created_at: stats.created_at,
+                title: stats.metadata.title,
+                description: stats.metadata.description,
+            })
+        })
+}
+
+#[derive(Deserialize, Serialize)]
+struct AvailabilityResponse {
+    id: String,
+    available: bool,
+}
+
+async fn check_availability<I, R, Q>(
+    Path(id): Path<String>,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<Json<AvailabilityResponse>, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let available = state.container.short_url_command.is_available(&id).await?;
+
+    Ok(Json(AvailabilityResponse { id, available }))
+}
+
+#[derive(Deserialize, Serialize)]
+struct PreviewResponse {
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scheme: Option<String>,
+    host: Option<String>,
+    protected: bool,
+    disabled: bool,
+    expired: bool,
+    created_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    clicks: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+async fn get_preview<I, R, Q>(
+    id: ShortId,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<Json<PreviewResponse>, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let preview = state.container.get_preview_query.execute(&id).await?;
+    let preview = state.visible(preview)?;
+
+    Ok(Json(PreviewResponse {
+        id: id.into_string(),
+        url: preview.url,
+        scheme: preview.scheme,
+        host: preview.host,
+        protected: preview.protected,
+        disabled: preview.disabled,
+        expired: preview.expired,
+        created_at: preview.created_at,
+        expires_at: preview.expires_at,
+        clicks: preview.clicks,
+        title: preview.metadata.title,
+        description: preview.metadata.description,
+    }))
+}
+
+#[derive(Deserialize)]
+struct ShareParams {
+    size: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ShareResponse {
+    short_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    qr_base64: String,
+    created_at: Option<DateTime<Utc>>,
+}
+
+async fn get_share<I, R, Q>(
+    id: ShortId,
+    Query(params): Query<ShareParams>,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<Response, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let preview = state.container.get_preview_query.execute(&id).await?;
+    let preview = state.visible(preview)?;
+
+    let short_url = short_url(&state.base_url, &id);
+    let size = params.size.unwrap_or(qr::DEFAULT_SIZE).min(qr::MAX_SIZE);
+    let response = match qr::render(&short_url, qr::QrFormat::Png, size) {
+        Ok(bytes) => Json(ShareResponse {
+            short_url,
+            target: preview.url,
+            qr_base64: BASE64_STANDARD.encode(bytes),
+            created_at: preview.created_at,
+        })
+        .into_response(),
+        Err(e) => {
+            tracing::error!("failed to render QR code for {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    };
+
+    Ok(response)
+}
+
+#[derive(Deserialize)]
+struct ListUrlsParams {
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct UrlItem {
+    id: String,
+    url: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ListUrlsResponse {
+    items: Vec<UrlItem>,
+    total: usize,
+}
+
+async fn list_urls<I, R, Q>(
+    Query(params): Query<ListUrlsParams>,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<Json<ListUrlsResponse>, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let limit = params
+        .limit
+        .unwrap_or(state.max_list_limit)
+        .min(state.max_list_limit);
+    let page = state
+        .container
+        .list_urls_query
+        .execute(params.offset, limit)
+        .await?;
+
+    Ok(Json(ListUrlsResponse {
+        items: page
+            .items
+            .into_iter()
+            .map(|(id, url)| UrlItem { id, url })
+            .collect(),
+        total: page.total,
+    }))
+}
+
+#[derive(Deserialize)]
+struct ReverseLookupParams {
+    url: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ReverseLookupResponse {
+    url: String,
+    ids: Vec<String>,
+}
+
+async fn reverse_lookup<I, R, Q>(
+    Query(params): Query<ReverseLookupParams>,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<Json<ReverseLookupResponse>, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let url = state.container.short_url_command.parse_url(&params.url)?;
+    let ids = state.container.reverse_lookup_query.execute(&url).await?;
+
+    Ok(Json(ReverseLookupResponse {
+        url: url.into_string(),
+        ids,
+    }))
+}
+
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+async fn export_urls<I, R, Q>(State(state): State<AppState<I, R, Q>>) -> Response
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let lines = state.container.export_query.execute().map(|entry| {
+        let (id, url) = entry?;
+        let mut line = serde_json::to_vec(&UrlItem { id, url })
+            .map_err(|e| AppError::RepositoryError(e.to_string()))?;
+        line.push(b'\n');
+
+        Ok::<_, AppError>(line)
+    });
+
+    (
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+struct ImportParams {
+    #[serde(default)]
+    mode: ImportMode,
+}
+
+#[derive(Deserialize, Serialize)]
+struct ImportResponse {
+    imported: usize,
+    skipped: usize,
+    errors: Vec<ImportError>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct ImportError {
+    line: usize,
+    #[serde(flatten)]
+    error: ValidationError,
+}
+
+async fn import_urls<I, R, Q>(
+    Query(params): Query<ImportParams>,
+    State(state): State<AppState<I, R, Q>>,
+    body: String,
+) -> Json<ImportResponse>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    let mut response = ImportResponse {
+        imported: 0,
+        skipped: 0,
+        errors: Vec::new(),
+    };
+    for (index, line) in body.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let item = match serde_json::from_str::<UrlItem>(line) {
+            Ok(item) => item,
+            Err(e) => {
+                response.errors.push(ImportError {
+                    line: index + 1,
+                    error: ValidationError::new(
+                        ValidationCode::InvalidJson,
+                        format!("Invalid JSON: {}", e),
+                    ),
+                });
+                continue;
+            }
+        };
+        match state
+            .container
+            .import_urls_command
+            .execute(&item.id, &item.url, params.mode)
+            .await
+        {
+            Ok(ImportOutcome::Imported) => response.imported += 1,
+            Ok(ImportOutcome::Skipped) => response.skipped += 1,
+            Err(e) => response.errors.push(ImportError {
+                line: index + 1,
+                error: e.into(),
+            }),
+        }
+    }
+
+    Json(response)
+}
+
+#[derive(Deserialize, Serialize)]
+struct PurgeExpiredResponse {
+    purged: usize,
+}
+
+async fn purge_expired<I, R, Q>(
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<Json<PurgeExpiredResponse>, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    state
+        .container
+        .purge_expired_command
+        .execute()
+        .await
+        .map(|purged| Json(PurgeExpiredResponse { purged }))
+}
+
+#[derive(Deserialize, Serialize)]
+struct UpdateShortUrlRequest {
+    url: String,
+}
+
+async fn update_short_url<I, R, Q>(
+    id: ShortId,
+    State(state): State<AppState<I, R, Q>>,
+    JsonBody(input): JsonBody<UpdateShortUrlRequest>,
+) -> Result<StatusCode, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    state
+        .container
+        .update_short_url_command
+        .execute(&id, &input.url)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+/// Partial update body. Absent fields are left as they are; `ttl_seconds:
+/// null` removes the expiry, while a null `url` or `disabled` is ignored.
+#[derive(Default, Deserialize, Serialize)]
+struct PatchShortUrlRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    disabled: Option<bool>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    ttl_seconds: Option<Option<u64>>,
+}
+
+impl PatchShortUrlRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = FieldErrors::default();
+        errors.check_ttl(self.ttl_seconds.flatten());
+
+        errors.into_result()
+    }
+}
+
+/// Maps a present field to `Some`, so `null` can be told apart from absent.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+async fn patch_short_url<I, R, Q>(
+    id: ShortId,
+    State(state): State<AppState<I, R, Q>>,
+    JsonBody(input): JsonBody<PatchShortUrlRequest>,
+) -> Result<StatusCode, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    input.validate()?;
+
+    let patch = ShortUrlPatch {
+        url: input.url,
+        disabled: input.disabled,
+        ttl: input
+            .ttl_seconds
+            .map(|ttl| ttl.map(|ttl| Duration::seconds(ttl as i64))),
+    };
+
+    state
+        .container
+        .patch_short_url_command
+        .execute(&id, patch)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+async fn delete_short_url<I, R, Q>(
+    id: ShortId,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<StatusCode, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    state
+        .container
+        .delete_short_url_command
+        .execute(&id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+async fn disable_short_url<I, R, Q>(
+    id: ShortId,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<StatusCode, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    state
+        .container
+        .set_enabled_command
+        .execute(&id, false)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+async fn enable_short_url<I, R, Q>(
+    id: ShortId,
+    State(state): State<AppState<I, R, Q>>,
+) -> Result<StatusCode, AppError>
+where
+    I: IdProvider + Send + Sync + 'static,
+    R: CommandRepository,
+    Q: QueryRepository,
+{
+    state
+        .container
+        .set_enabled_command
+        .execute(&id, true)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use axum::{
+        body::{self, Body},
+        extract::{ConnectInfo, Request},
+        http::{Method, StatusCode, header},
+    };
+    use chrono::TimeZone;
+    use dashmap::DashMap;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use crate::{
+        adapters::in_memory::InMemoryRepository,
+        app::{domain::StoredUrl, tombstones::Tombstones, url_policy::UrlPolicy},
+        di::{ContainerBuilder, testing::UnreachableRepository},
+        id_provider::{Checksum, FakeIdProvider, NanoIdProvider, UNAMBIGUOUS_ALPHABET},
+    };
+
+    use super::*;
+
+    const TEST_BASE_URL: &str = "http://localhost:3000";
+
+    fn get_router_with_mock_container() -> Router {
+        get_router_with_settings(RedirectKind::default(), TEST_BASE_URL)
+    }
+
+    fn get_router_with_redirect(redirect: RedirectKind) -> Router {
+        get_router_with_settings(redirect, TEST_BASE_URL)
+    }
+
+    fn get_router_with_settings(redirect: RedirectKind, base_url: &str) -> Router {
+        let store = Arc::new(DashMap::new());
+        store.insert("test-id".to_owned(), StoredUrl::new("test-url"));
+        store.insert("test-id-2".to_owned(), StoredUrl::new("test-url-2"));
+        let repo = InMemoryRepository::new(store);
+
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+
+        let config = Config {
+            base_url: base_url.to_owned(),
+            ..Config::default()
+        };
+
+        get_router(AppState {
+            redirect,
+            ..AppState::new(Arc::new(container), &config)
+        })
+    }
+
+    #[tokio::test]
+    async fn test_get_full_url() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id")
+                    .header(header::ACCEPT, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: FullUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.url, "test-url");
+    }
+
+    async fn head(router: Router, uri: &str, accept_json: bool) -> Response {
+        let mut request = Request::builder().method(Method::HEAD).uri(uri);
+        if accept_json {
+            request = request.header(header::ACCEPT, mime::APPLICATION_JSON.as_ref());
+        }
+
+        router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn head_known_id_is_ok_without_body() {
+        // When
+        let response = head(get_router_with_mock_container(), "/test-id", true).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn head_known_id_redirects_without_body() {
+        // When
+        let response = head(get_router_with_mock_container(), "/test-id", false).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()[header::LOCATION], "test-url");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn head_unknown_id_is_not_found_without_body() {
+        // When
+        let response = head(get_router_with_mock_container(), "/not-found", true).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn head_does_not_count_clicks() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        head(router.clone(), "/test-id", false).await;
+        let stats = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        let body = stats.into_body().collect().await.unwrap().to_bytes();
+        let body: StatsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.clicks, 0);
+    }
+
+    #[tokio::test]
+    async fn get_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/not-found")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.code, "not_found");
+        assert_eq!(body.message, "Not found");
+    }
+
+    #[tokio::test]
+    async fn test_get_different_id() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id-2")
+                    .header(header::ACCEPT, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: FullUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.url, "test-url-2");
+    }
+
+    #[tokio::test]
+    async fn resolve_returns_json_for_html_clients() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/api/resolve/test-id")
+                    .header(header::ACCEPT, mime::TEXT_HTML.as_ref())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::LOCATION).is_none());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ResolveResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "test-id");
+        assert_eq!(body.url, "test-url");
+    }
+
+    #[tokio::test]
+    async fn resolve_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/api/resolve/not-found")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Not found");
+    }
+
+    #[tokio::test]
+    async fn get_redirects_by_default() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()[header::LOCATION], "test-url");
+    }
+
+    #[tokio::test]
+    async fn get_redirects_html_clients() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id-2")
+                    .header(header::ACCEPT, "text/html,application/json;q=0.9,*/*;q=0.8")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()[header::LOCATION], "test-url-2");
+    }
+
+    #[tokio::test]
+    async fn get_redirects_permanently_when_configured() {
+        // Given
+        let router = get_router_with_redirect(RedirectKind::Permanent);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(response.headers()[header::LOCATION], "test-url");
+    }
+
+    #[tokio::test]
+    async fn short_url() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com".to_owned(),
+            alias: None,
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::LOCATION], "/new-id");
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ShortUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "new-id");
+        assert_eq!(body.short_url, "http://localhost:3000/new-id");
+    }
+
+    #[tokio::test]
+    async fn short_url_with_legacy_create_status() {
+        // Given
+        let container = Container::new(
+            FakeIdProvider::new("new-id".to_owned()),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+        );
+        let config = Config {
+            legacy_create_status: true,
+            ..Config::default()
+        };
+        let router = get_router(AppState::new(Arc::new(container), &config));
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(r#"{"url":"https://example.com"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::LOCATION).is_none());
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ShortUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "new-id");
+    }
+
+    #[tokio::test]
+    async fn short_url_with_alias() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com".to_owned(),
+            alias: Some("my-brand".to_owned()),
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::LOCATION], "/my-brand");
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ShortUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "my-brand");
+    }
+
+    #[tokio::test]
+    async fn short_url_with_taken_alias() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com".to_owned(),
+            alias: Some("test-id".to_owned()),
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Alias already taken");
+    }
+
+    #[tokio::test]
+    async fn short_url_with_invalid_alias() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com".to_owned(),
+            alias: Some("not valid!".to_owned()),
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn dry_run_does_not_persist() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        let repo = InMemoryRepository::new(store.clone());
+        let container = Arc::new(Container::new(
+            FakeIdProvider::new("test-id".to_owned()),
+            repo.clone(),
+            repo,
+        ));
+        let router = get_router(AppState::new(container, &Config::default()));
+        let create_short_url_request = CreateShortURLRequest {
+            url: "HTTPS://Example.com".to_owned(),
+            alias: None,
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: true,
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ShortUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "test-id");
+        assert_eq!(body.url, Some("https://example.com/".to_owned()));
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn password_protected_link() {
+        // Given
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container = Arc::new(Container::new(
+            FakeIdProvider::new("test-id".to_owned()),
+            repo.clone(),
+            repo,
+        ));
+        let router = get_router(AppState::new(container, &Config::default()));
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com/".to_owned(),
+            alias: None,
+            ttl_seconds: None,
+            password: Some("open sesame".to_owned()),
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+        let get = |uri: &str, header: Option<&str>| {
+            let mut request = Request::builder().uri(uri);
+            if let Some(password) = header {
+                request = request.header(LINK_PASSWORD_HEADER, password);
+            }
+            router.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        // When
+        let created = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let without_password = get("/test-id", None).await.unwrap();
+        let wrong_password = get("/test-id?password=nope", None).await.unwrap();
+        let right_password = get("/test-id?password=open%20sesame", None).await.unwrap();
+        let right_header = get("/test-id", Some("open sesame")).await.unwrap();
+
+        // Then
+        assert_eq!(created.status(), StatusCode::CREATED);
+        assert_eq!(without_password.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(wrong_password.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(right_password.status(), StatusCode::FOUND);
+        assert_eq!(
+            right_password.headers()[header::LOCATION],
+            "https://example.com/"
+        );
+        assert_eq!(right_header.status(), StatusCode::FOUND);
+    }
+
+    #[tokio::test]
+    async fn idn_url_redirects_to_punycode_host() {
+        // Given
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container = Container::new(FakeIdProvider::new("idn".to_owned()), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+        let request = CreateShortURLRequest {
+            url: "http://münchen.de/straße".to_owned(),
+            alias: None,
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let created = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_string(&request).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let redirect = router
+            .oneshot(Request::builder().uri("/idn").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(created.status(), StatusCode::CREATED);
+        assert_eq!(redirect.status(), StatusCode::FOUND);
+        assert_eq!(
+            redirect.headers()[header::LOCATION],
+            "http://xn--mnchen-3ya.de/stra%C3%9Fe"
+        );
+    }
+
+    #[tokio::test]
+    async fn short_and_get() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        let repo = InMemoryRepository::new(store.clone());
+        let repo2 = InMemoryRepository::new(store);
+
+        let container = Arc::new(Container::new(
+            FakeIdProvider::new("test-id".to_owned()),
+            repo,
+            repo2,
+        ));
+
+        let state = AppState::new(container, &Config::default());
+        let router1 = get_router(state.clone());
+        let router2 = get_router(state);
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com/".to_owned(),
+            alias: None,
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let resp1 = router1
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let resp2 = router2
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id")
+                    .header(header::ACCEPT, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(resp1.status(), StatusCode::CREATED);
+        let body = resp1.into_body().collect().await.unwrap().to_bytes();
+        let body: ShortUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "test-id");
+
+        assert_eq!(resp2.status(), StatusCode::OK);
+        let body = resp2.into_body().collect().await.unwrap().to_bytes();
+        let body: FullUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.url, "https://example.com/");
+    }
+
+    #[tokio::test]
+    async fn short_delete_and_get() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com/".to_owned(),
+            alias: Some("to-delete".to_owned()),
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let create_response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let delete_response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri("/to-delete")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let get_response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/to-delete")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(create_response.status(), StatusCode::CREATED);
+        assert_eq!(delete_response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(get_response.status(), StatusCode::NOT_FOUND);
+
+        let body = get_response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Not found");
+    }
+
+    #[tokio::test]
+    async fn delete_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri("/not-found")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn root_describes_the_service() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ServiceInfoResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.name, env!("CARGO_PKG_NAME"));
+        assert_eq!(body.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(body.openapi, "/openapi.json");
+        assert_eq!(body.docs, "/docs");
+    }
+
+    #[tokio::test]
+    async fn root_redirects_when_configured() {
+        // Given
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let config = Config {
+            root_redirect: Some("https://about.example.com/".to_owned()),
+            ..Config::default()
+        };
+        let router = get_router(AppState::new(Arc::new(container), &config));
+
+        // When
+        let response = router
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            "https://about.example.com/"
+        );
+    }
+
+    #[tokio::test]
+    async fn health() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body, serde_json::json!({ "status": "ok" }));
+    }
+
+    #[tokio::test]
+    async fn health_unavailable_when_store_is_unreachable() {
+        // Given
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container = Container::new(
+            FakeIdProvider::new("new-id".to_owned()),
+            repo,
+            UnreachableRepository,
+        );
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body, serde_json::json!({ "status": "unavailable" }));
+    }
+
+    #[tokio::test]
+    async fn clicks_are_counted() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        for _ in 0..3 {
+            let response = router
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/test-id")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::FOUND);
+        }
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: StatsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "test-id");
+        assert_eq!(body.clicks, 3);
+    }
+
+    #[tokio::test]
+    async fn preview_known_link_without_counting_click() {
+        // Given
+        let router = get_router_with_mock_container();
+        router.clone().oneshot(shorten_request(None)).await.unwrap();
+
+        // When
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/new-id/preview")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let stats = router
+            .oneshot(
+                Request::builder()
+                    .uri("/new-id/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: PreviewResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "new-id");
+        assert_eq!(body.url.as_deref(), Some("https://example.com/"));
+        assert_eq!(body.scheme.as_deref(), Some("https"));
+        assert_eq!(body.host.as_deref(), Some("example.com"));
+        assert!(!body.disabled);
+        assert!(!body.expired);
+        assert_eq!(body.expires_at, None);
+        assert_eq!(body.clicks, 0);
+        assert!(body.created_at.is_some());
+
+        let stats = stats.into_body().collect().await.unwrap().to_bytes();
+        let stats: StatsResponse = serde_json::from_slice(&stats).unwrap();
+        assert_eq!(stats.clicks, 0);
+        assert_eq!(stats.created_at, body.created_at);
+    }
+
+    async fn availability_of(id: &str) -> AvailabilityResponse {
+        let response = get_router_with_mock_container()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/available/{id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn occupied_id_is_unavailable() {
+        // When
+        let body = availability_of("test-id").await;
+
+        // Then
+        assert_eq!(body.id, "test-id");
+        assert!(!body.available);
+    }
+
+    #[tokio::test]
+    async fn free_id_is_available() {
+        // When
+        let body = availability_of("free-id").await;
+
+        // Then
+        assert_eq!(body.id, "free-id");
+        assert!(body.available);
+    }
+
+    #[tokio::test]
+    async fn title_and_description_round_trip() {
+        // Given
+        let router = get_router_with_mock_container();
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com/".to_owned(),
+            alias: Some("titled".to_owned()),
+            ttl_seconds: None,
+            password: None,
+            title: Some("Example".to_owned()),
+            description: Some("An example page".to_owned()),
+            dry_run: false,
+        };
+        let get = |uri: &'static str| {
+            router
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        };
+
+        // When
+        let create_response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let preview_response = get("/titled/preview").await.unwrap();
+        let stats_response = get("/titled/stats").await.unwrap();
+
+        // Then
+        assert_eq!(create_response.status(), StatusCode::CREATED);
+
+        let body = preview_response
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes();
+        let preview: PreviewResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(preview.title.as_deref(), Some("Example"));
+        assert_eq!(preview.description.as_deref(), Some("An example page"));
+
+        let body = stats_response
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes();
+        let stats: StatsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(stats.title.as_deref(), Some("Example"));
+        assert_eq!(stats.description.as_deref(), Some("An example page"));
+    }
+
+    #[tokio::test]
+    async fn preview_exposes_created_at() {
+        // Given
+        let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let store = Arc::new(DashMap::new());
+        store.insert(
+            "dated".to_owned(),
+            StoredUrl {
+                created_at: Some(created_at),
+                ..StoredUrl::new("https://example.com/")
+            },
+        );
+        let repo = InMemoryRepository::new(store);
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/dated/preview")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["created_at"], "2025-01-01T12:00:00Z");
+    }
+
+    #[tokio::test]
+    async fn preview_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/not-found/preview")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn preview_and_share_hide_protected_targets() {
+        // Given
+        let router = get_router_with_mock_container();
+        let created = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        r#"{"url":"https://secret.example.com/","password":"hunter2"}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.status(), StatusCode::CREATED);
+
+        // When
+        let preview = router
+            .clone()
+            .oneshot(Request::get("/new-id/preview").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let share = router
+            .oneshot(Request::get("/new-id/share").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(preview.status(), StatusCode::OK);
+        assert_eq!(share.status(), StatusCode::OK);
+        let preview = preview.into_body().collect().await.unwrap().to_bytes();
+        let share = share.into_body().collect().await.unwrap().to_bytes();
+        for body in [&preview, &share] {
+            let body = std::str::from_utf8(body).unwrap();
+            assert!(
+                !body.contains("secret.example.com"),
+                "leaked target: {body}"
+            );
+        }
+        let preview: PreviewResponse = serde_json::from_slice(&preview).unwrap();
+        assert!(preview.protected);
+    }
+
+    #[tokio::test]
+    async fn export_streams_ndjson() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/export")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            NDJSON_CONTENT_TYPE
+        );
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        let items: HashSet<(String, String)> = body
+            .lines()
+            .map(|line| {
+                let item: UrlItem = serde_json::from_str(line).unwrap();
+                (item.id, item.url)
+            })
+            .collect();
+        assert_eq!(
+            items,
+            HashSet::from([
+                ("test-id".to_owned(), "test-url".to_owned()),
+                ("test-id-2".to_owned(), "test-url-2".to_owned()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn purge_expired_removes_only_expired_links() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        store.insert(
+            "expired".to_owned(),
+            StoredUrl {
+                expires_at: Some(Utc::now() - chrono::Duration::hours(1)),
+                ..StoredUrl::new("https://expired.example/")
+            },
+        );
+        store.insert(
+            "alive".to_owned(),
+            StoredUrl {
+                expires_at: Some(Utc::now() + chrono::Duration::hours(1)),
+                ..StoredUrl::new("https://alive.example/")
+            },
+        );
+        store.insert(
+            "forever".to_owned(),
+            StoredUrl::new("https://forever.example/"),
+        );
+        let repo = InMemoryRepository::new(store.clone());
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/admin/purge-expired")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: PurgeExpiredResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.purged, 1);
+        assert!(!store.contains_key("expired"));
+        assert!(store.contains_key("alive"));
+        assert!(store.contains_key("forever"));
+    }
+
+    async fn import_with(mode: &str) -> (ImportResponse, Arc<DashMap<String, StoredUrl>>) {
+        let store = Arc::new(DashMap::new());
+        store.insert("test-id".to_owned(), StoredUrl::new("https://old.example/"));
+        let repo = InMemoryRepository::new(store.clone());
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+        let body = [
+            r#"{"id":"test-id","url":"https://new.example/"}"#,
+            r#"{"id":"imported","url":"https://imported.example"}"#,
+            "",
+            r#"{"id":"bad.id","url":"https://bad.example/"}"#,
+            "not json",
+        ]
+        .join("\n");
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/admin/import?mode={mode}"))
+                    .header(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (serde_json::from_slice(&body).unwrap(), store)
+    }
+
+    #[tokio::test]
+    async fn import_skips_existing_ids() {
+        // When
+        let (response, store) = import_with("skip").await;
+
+        // Then
+        assert_eq!(response.imported, 1);
+        assert_eq!(response.skipped, 1);
+        assert_eq!(response.errors.len(), 2);
+        assert_eq!(
+            response.errors[0],
+            ImportError {
+                line: 4,
+                error: ValidationError::new(ValidationCode::InvalidId, "Invalid id"),
+            }
+        );
+        assert_eq!(response.errors[1].line, 5);
+        assert_eq!(response.errors[1].error.code, ValidationCode::InvalidJson);
+        assert!(response.errors[1].error.message.starts_with("Invalid JSON"));
+        assert_eq!(
+            store.get("test-id").unwrap().full_url,
+            "https://old.example/"
+        );
+        assert_eq!(
+            store.get("imported").unwrap().full_url,
+            "https://imported.example/"
+        );
+        assert_eq!(store.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn import_overwrites_existing_ids() {
+        // When
+        let (response, store) = import_with("overwrite").await;
+
+        // Then
+        assert_eq!(response.imported, 2);
+        assert_eq!(response.skipped, 0);
+        assert_eq!(response.errors.len(), 2);
+        assert_eq!(
+            store.get("test-id").unwrap().full_url,
+            "https://new.example/"
+        );
+        assert_eq!(
+            store.get("imported").unwrap().full_url,
+            "https://imported.example/"
+        );
+        assert_eq!(store.len(), 2);
+    }
+
+    async fn send_with_content_type(
+        method: Method,
+        uri: &str,
+        content_type: Option<&str>,
+    ) -> Response {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(content_type) = content_type {
+            builder = builder.header(header::CONTENT_TYPE, content_type);
+        }
+
+        get_router_with_mock_container()
+            .oneshot(
+                builder
+                    .body(Body::from(r#"{"url":"https://example.com"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn non_json_body_is_unsupported_media_type() {
+        // When
+        let plain_text = send_with_content_type(Method::POST, "/", Some("text/plain")).await;
+        let missing = send_with_content_type(Method::POST, "/batch", None).await;
+        let update = send_with_content_type(Method::PUT, "/test-id", Some("text/plain")).await;
+
+        // Then
+        assert_eq!(missing.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(update.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(plain_text.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let body = plain_text.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Content-Type must be application/json");
+    }
+
+    async fn post_json(body: &'static str) -> (StatusCode, ErrorResponse) {
+        let response = get_router_with_mock_container()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn missing_url_field_is_bad_request() {
+        // When
+        let (status, body) = post_json(r#"{"ur":"https://example.com"}"#).await;
+
+        // Then
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body.message,
+            "Invalid URL: missing field `url` at line 1 column 28"
+        );
+    }
+
+    #[tokio::test]
+    async fn wrong_typed_url_field_is_bad_request() {
+        // When
+        let (status, body) = post_json(r#"{"url":1}"#).await;
+
+        // Then
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body.message,
+            "Invalid URL: url: invalid type: integer `1`, expected a string at line 1 column 8"
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_json_is_bad_request() {
+        // When
+        let (status, body) = post_json(r#"{"url":"#).await;
+
+        // Then
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            body.message
+                .starts_with("Invalid URL: url: EOF while parsing")
+        );
+    }
+
+    #[tokio::test]
+    async fn stats_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/not-found/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn shorten_batch_with_partial_failures() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let batch_request = BatchShortenRequest {
+            urls: vec![
+                "https://example.com".to_owned(),
+                "not a url".to_owned(),
+                "ftp://example.com/file".to_owned(),
+            ],
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/batch")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_string(&batch_request).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: BatchShortenResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body.results,
+            vec![
+                BatchItemResult::Created {
+                    url: "https://example.com".to_owned(),
+                    id: "new-id".to_owned(),
+                },
+                BatchItemResult::Failed {
+                    url: "not a url".to_owned(),
+                    error: ValidationError::new(
+                        ValidationCode::InvalidUrl,
+                        "URL parse error: relative URL without a base"
+                    ),
+                },
+                BatchItemResult::Failed {
+                    url: "ftp://example.com/file".to_owned(),
+                    error: ValidationError::new(
+                        ValidationCode::UnsupportedScheme,
+                        "Unsupported URL scheme"
+                    ),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn shorten_batch_creates_distinct_ids() {
+        // Given
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container = Container::new(NanoIdProvider::default(), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        let batch_request = BatchShortenRequest {
+            urls: vec![
+                "https://example.com".to_owned(),
+                "https://example.org".to_owned(),
+            ],
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/batch")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_string(&batch_request).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["url"], "https://example.com");
+        assert_eq!(results[1]["url"], "https://example.org");
+        assert_ne!(results[0]["id"], results[1]["id"]);
+    }
+
+    #[tokio::test]
+    async fn shorten_is_rate_limited_per_client() {
+        // Given
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container = Container::new(NanoIdProvider::default(), repo.clone(), repo);
+        let config = Config {
+            rate_limit_requests: 1,
+            ..Config::default()
+        };
+        let router = get_router(AppState::new(Arc::new(container), &config));
+
+        let shorten_from = |ip: [u8; 4]| {
+            let mut request = Request::builder()
+                .method(Method::POST)
+                .uri("/")
+                .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                .body(Body::from(r#"{"url":"https://example.com"}"#))
+                .unwrap();
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(SocketAddr::from((ip, 4000))));
+            router.clone().oneshot(request)
+        };
+
+        // When
+        let first = shorten_from([10, 0, 0, 1]).await.unwrap();
+        let second = shorten_from([10, 0, 0, 1]).await.unwrap();
+        let other_client = shorten_from([10, 0, 0, 2]).await.unwrap();
+
+        // Then
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(other_client.status(), StatusCode::CREATED);
+
+        let body = second.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Too many requests");
+    }
+
+    fn get_router_with_allowed_origins(allowed_origins: &[&str]) -> Router {
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let config = Config {
+            allowed_origins: allowed_origins.iter().map(|o| o.to_string()).collect(),
+            ..Config::default()
+        };
+
+        get_router(AppState::new(Arc::new(container), &config))
+    }
+
+    #[tokio::test]
+    async fn cors_preflight_allows_configured_origin() {
+        // Given
+        let router = get_router_with_allowed_origins(&["https://app.sho.rt"]);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri("/")
+                    .header(header::ORIGIN, "https://app.sho.rt")
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                    .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.sho.rt"
+        );
+        assert!(
+            headers[header::ACCESS_CONTROL_ALLOW_METHODS]
+                .to_str()
+                .unwrap()
+                .contains("POST")
+        );
+        assert!(
+            headers[header::ACCESS_CONTROL_ALLOW_METHODS]
+                .to_str()
+                .unwrap()
+                .contains("PATCH")
+        );
+        assert!(
+            headers[header::ACCESS_CONTROL_ALLOW_HEADERS]
+                .to_str()
+                .unwrap()
+                .contains("content-type")
+        );
+    }
+
+    #[tokio::test]
+    async fn cors_rejects_unknown_origin() {
+        // Given
+        let router = get_router_with_allowed_origins(&["https://app.sho.rt"]);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri("/")
+                    .header(header::ORIGIN, "https://evil.example")
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert!(
+            !response
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        );
+    }
+
+    #[tokio::test]
+    async fn cors_cross_origin_post() {
+        // Given
+        let router = get_router_with_allowed_origins(&["https://app.sho.rt"]);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::ORIGIN, "https://app.sho.rt")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(r#"{"url":"https://example.com"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.sho.rt"
+        );
+    }
+
+    #[tokio::test]
+    async fn cors_is_permissive_without_configured_origins() {
+        // Given
+        let router = get_router_with_allowed_origins(&[]);
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::ORIGIN, "https://anywhere.example")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(r#"{"url":"https://example.com"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    }
+
+    async fn list_urls_with(config: Config, uri: &str) -> ListUrlsResponse {
+        let store = Arc::new(DashMap::new());
+        for id in ["a", "b", "c"] {
+            store.insert(
+                id.to_owned(),
+                StoredUrl::new(format!("https://{id}.example/")),
+            );
+        }
+        let repo = InMemoryRepository::new(store);
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &config));
+
+        let response = router
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn list_urls_returns_page_and_total() {
+        // When
+        let body = list_urls_with(Config::default(), "/admin/urls?offset=1&limit=1").await;
+
+        // Then
+        assert_eq!(body.total, 3);
+        assert_eq!(body.items.len(), 1);
+        assert_eq!(body.items[0].id, "b");
+        assert_eq!(body.items[0].url, "https://b.example/");
+    }
+
+    #[tokio::test]
+    async fn list_urls_caps_limit() {
+        // Given
+        let config = Config {
+            max_list_limit: 2,
+            ..Config::default()
+        };
+
+        // When
+        let body = list_urls_with(config, "/admin/urls?limit=50").await;
+
+        // Then
+        assert_eq!(body.total, 3);
+        assert_eq!(body.items.len(), 2);
+    }
+
+    async fn list_urls_encoded(count: usize) -> Response {
+        let store = Arc::new(DashMap::new());
+        for i in 0..count {
+            store.insert(
+                format!("id-{i}"),
+                StoredUrl::new(format!("https://example.com/{i}")),
+            );
+        }
+        let repo = InMemoryRepository::new(store);
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        router
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/urls")
+                    .header(header::ACCEPT_ENCODING, "gzip")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn large_responses_are_gzipped() {
+        // When
+        let response = list_urls_encoded(100).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    }
+
+    #[tokio::test]
+    async fn small_responses_are_not_compressed() {
+        // When
+        let response = list_urls_encoded(1).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
+    async fn reverse_lookup_for(url: &str) -> ReverseLookupResponse {
+        let store = Arc::new(DashMap::new());
+        for (id, target) in [
+            ("a", "https://example.com/"),
+            ("b", "https://other.example/"),
+            ("c", "https://example.com/"),
+        ] {
+            store.insert(id.to_owned(), StoredUrl::new(target));
+        }
+        let repo = InMemoryRepository::new(store);
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/admin/reverse?url={url}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reverse_lookup_returns_all_ids_for_normalized_url() {
+        // When
+        let body = reverse_lookup_for("https%3A%2F%2FEXAMPLE.com").await;
+
+        // Then
+        assert_eq!(body.url, "https://example.com/");
+        assert_eq!(body.ids, vec!["a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn reverse_lookup_of_unknown_url_is_empty() {
+        // When
+        let body = reverse_lookup_for("https%3A%2F%2Funknown.example").await;
+
+        // Then
+        assert!(body.ids.is_empty());
+    }
+
+    async fn shorten_invalid(url: &str) -> (StatusCode, ErrorResponse) {
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: url.to_owned(),
+            alias: None,
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn short_url_reports_parse_error_reason() {
+        // When
+        let (relative_status, relative) = shorten_invalid("not a url").await;
+        let (empty_host_status, empty_host) = shorten_invalid("https://").await;
+
+        // Then
+        assert_eq!(relative_status, StatusCode::BAD_REQUEST);
+        assert_eq!(empty_host_status, StatusCode::BAD_REQUEST);
+        assert_eq!(relative.message, "Invalid URL: relative URL without a base");
+        assert_eq!(empty_host.message, "Invalid URL: empty host");
+    }
+
+    #[tokio::test]
+    async fn short_url_with_reserved_alias() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com".to_owned(),
+            alias: Some("health".to_owned()),
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Id is reserved");
+    }
+
+    #[tokio::test]
+    async fn shorten_rejects_oversized_body() {
+        // Given
+        let router = get_router_with_mock_container();
+        let body = format!(
+            r#"{{"url":"https://example.com/{}"}}"#,
+            "a".repeat(9 * 1024)
+        );
+
+        // When
+        let shorten = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(body.clone()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let batch = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/batch")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(shorten.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(batch.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn short_url_too_long() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: format!("https://example.com/{}", "a".repeat(2048)),
+            alias: None,
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.errors.len(), 1);
+        assert_eq!(body.errors[0].field, "url");
+        assert_eq!(body.errors[0].code, "url_too_long");
+    }
+
+    #[tokio::test]
+    async fn short_url_longer_than_configured_limit() {
+        // Given
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("new-id".to_owned()))
+            .with_command_repository(repo.clone())
+            .with_query_repository(repo)
+            .with_url_policy(UrlPolicy::default().with_max_url_length(32))
+            .build();
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        r#"{"url":"https://example.com/a-path-past-the-limit"}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.errors.len(), 1);
+        assert_eq!(body.errors[0].code, "url_too_long");
+        assert_eq!(body.errors[0].message, "URL must be at most 32 bytes");
+    }
+
+    #[tokio::test]
+    async fn shorten_reports_every_invalid_field() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: String::new(),
+            alias: Some("not valid!".to_owned()),
+            ttl_seconds: Some(0),
+            password: None,
+            title: Some("t".repeat(MAX_TITLE_LENGTH + 1)),
+            description: None,
+            dry_run: false,
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.code, "validation_failed");
+        let violations: Vec<(&str, &str)> = body
+            .errors
+            .iter()
+            .map(|e| (e.field.as_str(), e.code.as_str()))
+            .collect();
+        assert_eq!(
+            violations,
+            [
+                ("url", "invalid_url"),
+                ("alias", "invalid_alias"),
+                ("ttl_seconds", "invalid_ttl"),
+                ("title", "too_long"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn every_id_route_checks_the_checksum() {
+        // Given
+        let checksum = Checksum::new(UNAMBIGUOUS_ALPHABET.to_vec()).unwrap();
+        let id = checksum.append("abc").unwrap();
+        let typo = format!("abd{}", &id[3..]);
+        let store = Arc::new(DashMap::new());
+        store.insert(id.to_string(), StoredUrl::new("https://example.com/"));
+        store.insert(typo.clone(), StoredUrl::new("https://example.com/"));
+        let repo = InMemoryRepository::new(store);
+        let container = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("new-id".to_owned()))
+            .with_command_repository(repo.clone())
+            .with_query_repository(repo)
+            .with_checksum(Some(checksum))
+            .build();
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+        let get = |uri: String| {
+            router
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        };
+
+        // When
+        let valid = get(format!("/{id}/preview")).await.unwrap();
+        let mut codes = Vec::new();
+        for suffix in ["", "+", "/stats", "/preview", "/qr", "/share"] {
+            let response = get(format!("/{typo}{suffix}")).await.unwrap();
+            let status = response.status();
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+            codes.push((suffix, status, body.code));
+        }
+
+        // Then
+        assert_eq!(valid.status(), StatusCode::OK);
+        for (suffix, status, code) in codes {
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{suffix}");
+            assert_eq!(code, "invalid_checksum", "{suffix}");
+        }
+    }
+
+    #[tokio::test]
+    async fn blocked_paths_are_not_resolved() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        store.insert("wp-admin".to_owned(), StoredUrl::new("https://example.com"));
+        let repo = InMemoryRepository::new(store);
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let config = Config {
+            blocked_paths: vec!["/WP-".to_owned()],
+            ..Config::default()
+        };
+        let router = get_router(AppState::new(Arc::new(container), &config));
+
+        // When
+        let response = router
+            .oneshot(Request::get("/wp-admin").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    fn get_router_with_api_token(api_token: Option<&str>) -> Router {
+        let store = Arc::new(DashMap::new());
+        store.insert("test-id".to_owned(), StoredUrl::new("test-url"));
+        let repo = InMemoryRepository::new(store);
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let config = Config {
+            api_token: api_token.map(str::to_owned),
+            ..Config::default()
+        };
+
+        get_router(AppState::new(Arc::new(container), &config))
+    }
+
+    fn shorten_request(authorization: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref());
+        if let Some(authorization) = authorization {
+            builder = builder.header(header::AUTHORIZATION, authorization);
+        }
+
+        builder
+            .body(Body::from(r#"{"url":"https://example.com"}"#))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn write_with_valid_token_is_authorized() {
+        // Given
+        let router = get_router_with_api_token(Some("secret"));
+
+        // When
+        let response = router
+            .oneshot(shorten_request(Some("Bearer secret")))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn write_without_valid_token_is_unauthorized() {
+        // Given
+        let router = get_router_with_api_token(Some("secret"));
+
+        // When
+        let missing = router.clone().oneshot(shorten_request(None)).await.unwrap();
+        let wrong = router
+            .clone()
+            .oneshot(shorten_request(Some("Bearer nope")))
+            .await
+            .unwrap();
+        let delete = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri("/test-id")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let patch = router
+            .oneshot(patch_request("/test-id", r#"{"disabled":true}"#))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(delete.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(patch.status(), StatusCode::UNAUTHORIZED);
+
+        let body = missing.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Unauthorized");
+    }
+
+    fn admin_request(uri: &str, authorization: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(authorization) = authorization {
+            builder = builder.header(header::AUTHORIZATION, authorization);
+        }
+
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_requires_token() {
+        // Given
+        let router = get_router_with_api_token(Some("secret"));
+
+        // When
+        let missing = router
+            .clone()
+            .oneshot(admin_request("/admin/export", None))
+            .await
+            .unwrap();
+        let valid = router
+            .oneshot(admin_request("/admin/export", Some("Bearer secret")))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(valid.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn listing_requires_token() {
+        // Given
+        let router = get_router_with_api_token(Some("secret"));
+
+        // When
+        let missing = router
+            .clone()
+            .oneshot(admin_request("/admin/urls", None))
+            .await
+            .unwrap();
+        let valid = router
+            .oneshot(admin_request("/admin/urls", Some("Bearer secret")))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(valid.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn reverse_lookup_requires_token() {
+        // Given
+        let router = get_router_with_api_token(Some("secret"));
+        let uri = "/admin/reverse?url=https://example.com/";
+
+        // When
+        let missing = router
+            .clone()
+            .oneshot(admin_request(uri, None))
+            .await
+            .unwrap();
+        let valid = router
+            .oneshot(admin_request(uri, Some("Bearer secret")))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(valid.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn access_log_requires_token() {
+        // Given
+        let router = get_router_with_api_token(Some("secret"));
+
+        // When
+        let missing = router
+            .clone()
+            .oneshot(admin_request("/test-id/accesses", None))
+            .await
+            .unwrap();
+        let valid = router
+            .oneshot(admin_request("/test-id/accesses", Some("Bearer secret")))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(valid.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn read_is_public_with_token_configured() {
+        // Given
+        let router = get_router_with_api_token(Some("secret"));
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::FOUND);
+    }
+
+    #[tokio::test]
+    async fn write_is_open_without_token_configured() {
+        // Given
+        let router = get_router_with_api_token(None);
+
+        // When
+        let response = router.oneshot(shorten_request(None)).await.unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    async fn get_with_extension(uri: &str) -> Response {
+        let store = Arc::new(DashMap::new());
+        store.insert("test-id".to_owned(), StoredUrl::new("test-url"));
+        store.insert("release.notes".to_owned(), StoredUrl::new("notes-url"));
+        let repo = InMemoryRepository::new(store);
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        router
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_full_url_with_json_extension() {
+        // When
+        let response = get_with_extension("/test-id.json").await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: FullUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.url, "test-url");
+    }
+
+    #[tokio::test]
+    async fn get_full_url_without_extension_redirects() {
+        // When
+        let response = get_with_extension("/test-id").await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()[header::LOCATION], "test-url");
+    }
+
+    #[tokio::test]
+    async fn plus_suffix_returns_stats() {
+        // When
+        let response = get_with_extension("/test-id+").await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(header::LOCATION));
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: StatsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "test-id");
+        assert_eq!(body.clicks, 0);
+    }
+
+    #[tokio::test]
+    async fn plus_suffix_does_not_change_plain_resolution() {
+        // When
+        let plain = get_with_extension("/test-id").await;
+        let missing = get_with_extension("/missing+").await;
+        let bare = get_with_extension("/+").await;
+
+        // Then
+        assert_eq!(plain.status(), StatusCode::FOUND);
+        assert_eq!(plain.headers()[header::LOCATION], "test-url");
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        assert_eq!(bare.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn resolves_links_not_stored_locally() {
+        // Given
+        let remote = Arc::new(DashMap::new());
+        remote.insert("test-id".to_owned(), StoredUrl::new("test-url"));
+        let container = Container::new(
+            FakeIdProvider::new("new-id".to_owned()),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+            InMemoryRepository::new(remote),
+        );
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()[header::LOCATION], "test-url");
+    }
+
+    #[tokio::test]
+    async fn case_insensitive_ids_count_clicks_on_the_stored_id() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        store.insert("test-id".to_owned(), StoredUrl::new("test-url"));
+        let repo = InMemoryRepository::new(store);
+        let container = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("new-id".to_owned()))
+            .with_command_repository(repo.clone())
+            .with_query_repository(repo)
+            .with_case_insensitive_ids(true)
+            .build();
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        // When
+        let resolved = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/Test-ID")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let stats = router
+            .oneshot(
+                Request::builder()
+                    .uri("/TEST-id/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(resolved.status(), StatusCode::FOUND);
+        assert_eq!(stats.status(), StatusCode::OK);
+
+        let body = stats.into_body().collect().await.unwrap().to_bytes();
+        let body: StatsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "test-id");
+        assert_eq!(body.clicks, 1);
+    }
+
+    #[tokio::test]
+    async fn openapi_spec_describes_routes() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/openapi.json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let spec: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let shorten = &spec["paths"]["/"]["post"]["responses"];
+        let resolve = &spec["paths"]["/{id}"]["get"]["responses"];
+        assert!(shorten["200"].is_object());
+        assert!(shorten["400"].is_object());
+        assert!(resolve["200"].is_object());
+        assert!(resolve["400"].is_object());
+        assert!(resolve["404"].is_object());
+        assert!(
+            spec["components"]["schemas"]["ErrorResponse"]["properties"]["message"].is_object()
+        );
+    }
+
+    #[tokio::test]
+    async fn docs_serves_swagger_ui() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(Request::builder().uri("/docs").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("/openapi.json"));
+    }
+
+    #[tokio::test]
+    async fn get_full_url_rejects_illegal_id_before_lookup() {
+        // When
+        let dotted = get_with_extension("/release.notes").await;
+        let unknown_extension = get_with_extension("/test-id.xml").await;
+
+        // Then
+        assert_eq!(dotted.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(unknown_extension.status(), StatusCode::BAD_REQUEST);
+
+        let body = dotted.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Invalid id");
+    }
+
+    #[tokio::test]
+    async fn id_routes_reject_illegal_chars() {
+        // Given
+        let router = get_router_with_mock_container();
+        let requests = [
+            (Method::GET, "/bad.id/stats"),
+            (Method::GET, "/bad%20id/qr"),
+            (Method::GET, "/bad.id/accesses"),
+            (Method::DELETE, "/bad.id"),
+            (Method::POST, "/bad.id/disable"),
+        ];
+
+        for (method, uri) in requests {
+            // When
+            let response = router
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            // Then
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn get_full_url_records_accesses() {
+        // Given
+        let router = get_router_with_mock_container();
+        for user_agent in ["curl/8.0", "Mozilla/5.0"] {
+            router
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/test-id")
+                        .header(header::USER_AGENT, user_agent)
+                        .header(header::REFERER, "https://news.example/")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id/accesses")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: AccessesResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.id, "test-id");
+        let user_agents: Vec<_> = body
+            .accesses
+            .iter()
+            .map(|access| access.user_agent.as_deref().unwrap())
+            .collect();
+        assert_eq!(user_agents, vec!["Mozilla/5.0", "curl/8.0"]);
+        assert!(
+            body.accesses
+                .iter()
+                .all(|access| access.referer.as_deref() == Some("https://news.example/"))
+        );
+    }
+
+    #[tokio::test]
+    async fn get_accesses_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/missing-id/accesses")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn disable_then_enable() {
+        // Given
+        let router = get_router_with_mock_container();
+        let send = |method: Method, uri: &str| {
+            router.clone().oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(header::ACCEPT, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        // When
+        let disable = send(Method::POST, "/test-id/disable").await.unwrap();
+        let while_disabled = send(Method::GET, "/test-id").await.unwrap();
+        let enable = send(Method::POST, "/test-id/enable").await.unwrap();
+        let while_enabled = send(Method::GET, "/test-id").await.unwrap();
+        let unknown = send(Method::POST, "/missing-id/disable").await.unwrap();
+
+        // Then
+        assert_eq!(disable.status(), StatusCode::NO_CONTENT);
+        assert_eq!(while_disabled.status(), StatusCode::FORBIDDEN);
+        assert_eq!(enable.status(), StatusCode::NO_CONTENT);
+        assert_eq!(while_enabled.status(), StatusCode::OK);
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+
+        let body = while_disabled
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Disabled");
+    }
+
+    #[tokio::test]
+    async fn get_qr_png() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id/qr")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(!body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_qr_svg() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id/qr?format=svg&size=5000")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/svg+xml");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(!body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_qr_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/missing-id/qr")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn get_share_bundle() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        store.insert("test-id".to_owned(), StoredUrl::new("https://example.com/"));
+        let repo = InMemoryRepository::new(store);
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id/share?size=5000")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["short_url"], "http://localhost:3000/test-id");
+        assert_eq!(body["target"], "https://example.com/");
+        assert!(body.get("created_at").is_some());
+        let png = BASE64_STANDARD
+            .decode(body["qr_base64"].as_str().unwrap())
+            .unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+
+    #[tokio::test]
+    async fn get_share_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/missing-id/share")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    async fn shorten_with_base_url(base_url: &str) -> ShortUrlResponse {
+        let router = get_router_with_settings(RedirectKind::default(), base_url);
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com".to_owned(),
+            alias: None,
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn short_url_with_base_url_without_trailing_slash() {
+        // When
+        let body = shorten_with_base_url("https://sho.rt").await;
+
+        // Then
+        assert_eq!(body.id, "new-id");
+        assert_eq!(body.short_url, "https://sho.rt/new-id");
+    }
+
+    #[tokio::test]
+    async fn short_url_with_base_url_with_trailing_slash() {
+        // When
+        let body = shorten_with_base_url("https://sho.rt/").await;
+
+        // Then
+        assert_eq!(body.id, "new-id");
+        assert_eq!(body.short_url, "https://sho.rt/new-id");
+    }
+
+    #[tokio::test]
+    async fn server_shuts_down_on_request() {
+        // Given
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+        let config = Config {
+            port: 0,
+            bind_addr: "127.0.0.1".to_owned(),
+            ..Config::default()
+        };
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let server = Server::new(config, Arc::new(container)).with_shutdown(shutdown_rx);
+        let handle = tokio::spawn(server.run());
+
+        // When
+        shutdown_tx.send(()).unwrap();
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), handle).await;
+
+        // Then
+        assert!(matches!(result, Ok(Ok(()))));
+    }
+
+    #[tokio::test]
+    async fn short_update_and_get() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
+            url: "https://example.com/".to_owned(),
+            alias: Some("campaign".to_owned()),
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
+        };
+        let update_short_url_request = UpdateShortUrlRequest {
+            url: "https://example.org/".to_owned(),
+        };
+
+        // When
+        let create_response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&create_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let update_response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri("/campaign")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&update_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let get_response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/campaign")
+                    .header(header::ACCEPT, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(create_response.status(), StatusCode::CREATED);
+        assert_eq!(update_response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(get_response.status(), StatusCode::OK);
+
+        let body = get_response.into_body().collect().await.unwrap().to_bytes();
+        let body: FullUrlResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.url, "https://example.org/");
+    }
+
+    #[tokio::test]
+    async fn update_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let update_short_url_request = UpdateShortUrlRequest {
+            url: "https://example.org/".to_owned(),
+        };
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri("/not-found")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(
+                        serde_json::to_string(&update_short_url_request).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    fn get_router_with_store(store: Arc<DashMap<String, StoredUrl>>) -> Router {
+        let repo = InMemoryRepository::new(store);
+        let container =
+            Container::new(FakeIdProvider::new("new-id".to_owned()), repo.clone(), repo);
+
+        get_router(AppState::new(Arc::new(container), &Config::default()))
+    }
+
+    fn patch_request(uri: &str, body: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::PATCH)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+            .body(Body::from(body.to_owned()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn patch_disabled_keeps_url() {
+        // Given
+        let store = Arc::new(DashMap::new());
+        store.insert("test-id".to_owned(), StoredUrl::new("https://example.com/"));
+        let router = get_router_with_store(store.clone());
+
+        // When
+        let response = router
+            .oneshot(patch_request("/test-id", r#"{"disabled":true}"#))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let record = store.get("test-id").unwrap();
+        assert!(record.disabled);
+        assert_eq!(record.full_url, "https://example.com/");
+        assert_eq!(record.expires_at, None);
+    }
+
+    #[tokio::test]
+    async fn patch_ttl_extends_expiry() {
+        // Given
+        let expires_at = Utc::now() + Duration::minutes(1);
+        let store = Arc::new(DashMap::new());
+        store.insert(
+            "test-id".to_owned(),
+            StoredUrl {
+                expires_at: Some(expires_at),
+                ..StoredUrl::new("https://example.com/")
+            },
+        );
+        let router = get_router_with_store(store.clone());
+
+        // When
+        let extend = router
+            .clone()
+            .oneshot(patch_request("/test-id", r#"{"ttl_seconds":3600}"#))
+            .await
+            .unwrap();
+        let extended = store.get("test-id").unwrap().expires_at;
+        let clear = router
+            .oneshot(patch_request("/test-id", r#"{"ttl_seconds":null}"#))
+            .await
+            .unwrap();
+        let cleared = store.get("test-id").unwrap().expires_at;
+
+        // Then
+        assert_eq!(extend.status(), StatusCode::NO_CONTENT);
+        assert!(extended.unwrap() >= expires_at + Duration::minutes(58));
+        assert_eq!(clear.status(), StatusCode::NO_CONTENT);
+        assert_eq!(cleared, None);
+        assert_eq!(
+            store.get("test-id").unwrap().full_url,
+            "https://example.com/"
+        );
+    }
+
+    #[tokio::test]
+    async fn patch_not_found() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(patch_request("/not-found", r#"{"disabled":false}"#))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn patch_rejects_out_of_range_ttl() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let huge = router
+            .clone()
+            .oneshot(patch_request(
+                "/test-id",
+                &format!(r#"{{"ttl_seconds":{}}}"#, u64::MAX),
+            ))
+            .await
+            .unwrap();
+        let zero = router
+            .oneshot(patch_request("/test-id", r#"{"ttl_seconds":0}"#))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(huge.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(zero.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn metrics_count_created_urls() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        let create_short_url_request = CreateShortURLRequest {
             url: "https://example.com".to_owned(),
+            alias: Some("counted".to_owned()),
+            ttl_seconds: None,
+            password: None,
+            title: None,
+            description: None,
+            dry_run: false,
         };
 
         // When
-        let response = router
+        let create_response = router
+            .clone()
             .oneshot(
                 Request::builder()
                     .method(Method::POST)
@@ -269,68 +4880,426 @@ mod tests {
             .await
             .unwrap();
 
+        let metrics_response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
         // Then
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(create_response.status(), StatusCode::CREATED);
+        assert_eq!(metrics_response.status(), StatusCode::OK);
 
-        let body = response.into_body().collect().await.unwrap().to_bytes();
-        let body: ShortUrlResponse = serde_json::from_slice(&body).unwrap();
-        assert_eq!(body.id, "test-id");
+        let body = metrics_response
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        let created_total = body
+            .lines()
+            .find_map(|line| line.strip_prefix("urls_created_total "))
+            .and_then(|value| value.parse::<f64>().ok())
+            .unwrap();
+        assert!(created_total >= 1.0);
+        assert!(body.contains("http_request_duration_seconds"));
     }
 
     #[tokio::test]
-    async fn short_and_get() {
+    async fn generates_request_id() {
         // Given
-        let store = Arc::new(DashMap::new());
-        let repo = InMemoryRepository::new(store.clone());
-        let repo2 = InMemoryRepository::new(store);
+        let router = get_router_with_mock_container();
 
-        let container = Arc::new(Container::new(
-            FakeIdProvider::new("test-id".to_owned()),
-            repo,
-            repo2,
-        ));
+        // When
+        let first = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let second = router
+            .oneshot(
+                Request::builder()
+                    .uri("/missing")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
 
-        let router1 = get_router(container.clone());
-        let router2 = get_router(container.clone());
+        // Then
+        let first = first.headers()[REQUEST_ID_HEADER].to_str().unwrap();
+        let second = second.headers()[REQUEST_ID_HEADER].to_str().unwrap();
+        assert!(!first.is_empty());
+        assert_ne!(first, second);
+    }
 
-        let create_short_url_request = CreateShortURLRequest {
-            url: "https://example.com/".to_owned(),
+    #[tokio::test]
+    async fn preserves_client_request_id() {
+        // Given
+        let router = get_router_with_mock_container();
+
+        // When
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/test-id")
+                    .header(REQUEST_ID_HEADER, "client-id-123")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "client-id-123");
+    }
+
+    #[tokio::test]
+    async fn deleted_id_returns_gone_with_tombstones() {
+        // Given
+        let repo = InMemoryRepository::new(Arc::new(DashMap::new()));
+        let container = ContainerBuilder::new()
+            .with_id_provider(FakeIdProvider::new("test-id".to_owned()))
+            .with_command_repository(repo.clone())
+            .with_query_repository(repo)
+            .with_tombstones(Some(Tombstones::new(None)))
+            .build();
+        let router = get_router(AppState::new(Arc::new(container), &Config::default()));
+        let send = |method: Method, uri: &str, body: Body| {
+            router.clone().oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(body)
+                    .unwrap(),
+            )
         };
 
         // When
-        let resp1 = router1
+        let created = send(
+            Method::POST,
+            "/",
+            Body::from(r#"{"url":"https://example.com/"}"#),
+        )
+        .await
+        .unwrap();
+        let deleted = send(Method::DELETE, "/test-id", Body::empty())
+            .await
+            .unwrap();
+        let after_delete = send(Method::GET, "/test-id", Body::empty()).await.unwrap();
+        let unknown = send(Method::GET, "/unknown-id", Body::empty())
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(created.status(), StatusCode::CREATED);
+        assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+        assert_eq!(after_delete.status(), StatusCode::GONE);
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+        let body = after_delete.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Gone");
+    }
+
+    async fn get_missing(problem_json: bool, accept_language: Option<&str>) -> Response {
+        let container = Container::new(
+            FakeIdProvider::new("test-id".to_owned()),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+        );
+        let config = Config {
+            problem_json,
+            ..Config::default()
+        };
+        let router = get_router(AppState::new(Arc::new(container), &config));
+        let mut request = Request::builder().uri("/missing");
+        if let Some(language) = accept_language {
+            request = request.header(header::ACCEPT_LANGUAGE, language);
+        }
+
+        router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn not_found_uses_simple_format_by_default() {
+        // When
+        let response = get_missing(false, None).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            mime::APPLICATION_JSON.as_ref()
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.message, "Not found");
+    }
+
+    #[tokio::test]
+    async fn error_message_follows_accept_language() {
+        // When
+        let russian = get_missing(false, Some("ru-RU,ru;q=0.9,en;q=0.8")).await;
+        let english = get_missing(false, Some("en-US,en;q=0.9")).await;
+        let unsupported = get_missing(false, Some("fr-FR")).await;
+
+        // Then
+        assert_eq!(russian.status(), StatusCode::NOT_FOUND);
+        let mut messages = Vec::new();
+        for response in [russian, english, unsupported] {
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body.code, "not_found");
+            messages.push(body.message);
+        }
+        assert_eq!(messages, ["Не найдено", "Not found", "Not found"]);
+    }
+
+    #[tokio::test]
+    async fn problem_json_detail_is_localized() {
+        // When
+        let response = get_missing(true, Some("ru")).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: problem::ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.detail, "Не найдено");
+        assert_eq!(body.title, "Not Found");
+    }
+
+    #[tokio::test]
+    async fn not_found_uses_problem_json_when_enabled() {
+        // When
+        let response = get_missing(true, None).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            problem::PROBLEM_JSON_CONTENT_TYPE
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: problem::ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            problem::ProblemDetails {
+                kind: "urn:url-shortener:error:not-found".to_owned(),
+                title: "Not Found".to_owned(),
+                status: 404,
+                detail: "Not found".to_owned(),
+                errors: Vec::new(),
+            }
+        );
+    }
+
+    async fn post_invalid(problem_json: bool) -> Response {
+        let container = Container::new(
+            FakeIdProvider::new("test-id".to_owned()),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+        );
+        let config = Config {
+            problem_json,
+            ..Config::default()
+        };
+        let router = get_router(AppState::new(Arc::new(container), &config));
+
+        router
             .oneshot(
                 Request::builder()
                     .method(Method::POST)
                     .uri("/")
                     .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
-                    .body(Body::from(
-                        serde_json::to_string(&create_short_url_request).unwrap(),
-                    ))
+                    .header(header::ACCEPT_LANGUAGE, "ru")
+                    .body(Body::from(r#"{"url":"","ttl_seconds":0}"#))
                     .unwrap(),
             )
             .await
-            .unwrap();
+            .unwrap()
+    }
 
-        let resp2 = router2
-            .oneshot(
+    #[tokio::test]
+    async fn field_errors_are_localized_and_kept() {
+        // When
+        let response = post_invalid(false).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.code, "validation_failed");
+        assert_eq!(body.message, "Запрос не прошёл проверку");
+        let fields: Vec<&str> = body.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["url", "ttl_seconds"]);
+    }
+
+    #[tokio::test]
+    async fn field_errors_use_problem_json_when_enabled() {
+        // When
+        let response = post_invalid(true).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            problem::PROBLEM_JSON_CONTENT_TYPE
+        );
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: problem::ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.kind, "urn:url-shortener:error:validation-failed");
+        assert_eq!(body.detail, "Запрос не прошёл проверку");
+        let codes: Vec<&str> = body.errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, ["invalid_url", "invalid_ttl"]);
+    }
+
+    /// Responses for a missing, an expired and a disabled id, one row per
+    /// route that takes an id.
+    async fn resolve_unavailable(opaque_not_found: bool) -> Vec<Vec<(StatusCode, body::Bytes)>> {
+        let store = Arc::new(DashMap::new());
+        store.insert(
+            "expired".to_owned(),
+            StoredUrl {
+                expires_at: Some(Utc::now() - Duration::seconds(1)),
+                ..StoredUrl::new("https://expired.example/")
+            },
+        );
+        store.insert(
+            "disabled".to_owned(),
+            StoredUrl {
+                disabled: true,
+                ..StoredUrl::new("https://disabled.example/")
+            },
+        );
+        let repo = InMemoryRepository::new(store);
+        let container = Arc::new(Container::new(
+            FakeIdProvider::new("test-id".to_owned()),
+            repo.clone(),
+            repo,
+        ));
+        let config = Config {
+            opaque_not_found,
+            ..Config::default()
+        };
+        let router = get_router(AppState::new(container, &config));
+
+        let mut rows = Vec::new();
+        for suffix in ["", "+", "/stats", "/preview", "/qr", "/share", "/accesses"] {
+            let mut row = Vec::new();
+            for id in ["missing", "expired", "disabled"] {
+                let response = router
+                    .clone()
+                    .oneshot(
+                        Request::builder()
+                            .uri(format!("/{id}{suffix}"))
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let body = response.into_body().collect().await.unwrap().to_bytes();
+                row.push((status, body));
+            }
+            rows.push(row);
+        }
+
+        rows
+    }
+
+    #[tokio::test]
+    async fn opaque_not_found_hides_expired_and_disabled() {
+        // When
+        let rows = resolve_unavailable(true).await;
+
+        // Then
+        for row in rows {
+            assert_eq!(row[0].0, StatusCode::NOT_FOUND);
+            assert!(row.iter().all(|response| *response == row[0]));
+        }
+    }
+
+    #[tokio::test]
+    async fn verbose_mode_distinguishes_unavailable_states() {
+        // When
+        let rows = resolve_unavailable(false).await;
+
+        // Then
+        let statuses: Vec<_> = rows[0].iter().map(|(status, _)| *status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                StatusCode::NOT_FOUND,
+                StatusCode::GONE,
+                StatusCode::FORBIDDEN
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit_queues_excess_requests() {
+        // Given
+        let container = Container::new(
+            FakeIdProvider::new("new-id".to_owned()),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+            InMemoryRepository::new(Arc::new(DashMap::new())),
+        );
+        let config = Config {
+            max_concurrent_requests: Some(1),
+            ..Config::default()
+        };
+        let router = get_router(AppState::new(Arc::new(container), &config));
+        let (body_tx, body_rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(1);
+        let slow = tokio::spawn(
+            router.clone().oneshot(
                 Request::builder()
-                    .uri("/test-id")
+                    .method(Method::POST)
+                    .uri("/")
+                    .header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from_stream(futures::stream::unfold(
+                        body_rx,
+                        |mut rx| async move { rx.recv().await.map(|chunk| (chunk, rx)) },
+                    )))
+                    .unwrap(),
+            ),
+        );
+        tokio::task::yield_now().await;
+        let health = || {
+            router.clone().oneshot(
+                Request::builder()
+                    .uri("/health")
                     .body(Body::empty())
                     .unwrap(),
             )
+        };
+
+        // When
+        let queued = tokio::time::timeout(std::time::Duration::from_millis(100), health()).await;
+        body_tx
+            .send(Ok(r#"{"url":"https://example.com"}"#.to_owned()))
             .await
             .unwrap();
+        drop(body_tx);
+        let created = slow.await.unwrap().unwrap();
+        let released = health().await.unwrap();
 
         // Then
-        assert_eq!(resp1.status(), StatusCode::OK);
-        let body = resp1.into_body().collect().await.unwrap().to_bytes();
-        let body: ShortUrlResponse = serde_json::from_slice(&body).unwrap();
-        assert_eq!(body.id, "test-id");
-
-        assert_eq!(resp2.status(), StatusCode::OK);
-        let body = resp2.into_body().collect().await.unwrap().to_bytes();
-        let body: FullUrlResponse = serde_json::from_slice(&body).unwrap();
-        assert_eq!(body.url, "https://example.com/");
+        assert!(queued.is_err());
+        assert_eq!(created.status(), StatusCode::CREATED);
+        assert_eq!(released.status(), StatusCode::OK);
     }
 }
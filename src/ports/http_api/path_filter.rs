@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+
+/// Answers 404 right away for paths only scanners ask for, such as
+/// `/wp-login.php` or `/.env`, so they never reach a handler or the store.
+pub async fn reject_blocked(
+    State(patterns): State<Arc<[String]>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_blocked(&patterns, request.uri().path()) {
+        return AppError::NotFound.into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Whether `path` contains any of `patterns`, ignoring ASCII case. Patterns
+/// are expected in lowercase.
+fn is_blocked(patterns: &[String], path: &str) -> bool {
+    let path = path.to_ascii_lowercase();
+
+    patterns
+        .iter()
+        .any(|pattern| path.contains(pattern.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{Router, body::Body, extract::Path, http::StatusCode, middleware, routing::get};
+    use tower::ServiceExt;
+
+    use crate::app::{
+        domain::StoredUrl,
+        query::get_full_url::{GetFullUrlQuery, GetFullUrlRepository},
+    };
+
+    use super::*;
+
+    /// Counts every lookup so tests can assert the store was never asked.
+    #[derive(Clone, Default)]
+    struct CountingRepository {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl GetFullUrlRepository for CountingRepository {
+        async fn get_record(&self, _id: &str) -> Result<StoredUrl, AppError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(AppError::NotFound)
+        }
+    }
+
+    fn router(repository: CountingRepository, patterns: &[&str]) -> Router {
+        let query = Arc::new(GetFullUrlQuery::new(repository));
+        let patterns: Arc<[String]> = patterns.iter().map(|p| p.to_string()).collect();
+
+        Router::new()
+            .route(
+                "/{id}",
+                get(move |Path(id): Path<String>| async move {
+                    query
+                        .execute(&id)
+                        .await
+                        .map_err(IntoResponse::into_response)
+                }),
+            )
+            .layer(middleware::from_fn_with_state(patterns, reject_blocked))
+    }
+
+    async fn get_status(router: Router, uri: &str) -> StatusCode {
+        router
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn blocked_paths_never_reach_the_repository() {
+        // Given
+        let repository = CountingRepository::default();
+        let router = router(repository.clone(), &[".php", "/.env"]);
+
+        // When
+        let php = get_status(router.clone(), "/wp-login.PHP").await;
+        let env = get_status(router, "/.env").await;
+
+        // Then
+        assert_eq!(php, StatusCode::NOT_FOUND);
+        assert_eq!(env, StatusCode::NOT_FOUND);
+        assert_eq!(repository.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn other_paths_are_resolved() {
+        // Given
+        let repository = CountingRepository::default();
+        let router = router(repository.clone(), &[".php"]);
+
+        // When
+        let status = get_status(router, "/abc123").await;
+
+        // Then
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(repository.calls.load(Ordering::SeqCst), 1);
+    }
+}
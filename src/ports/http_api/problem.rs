@@ -0,0 +1,59 @@
+use axum::{
+    body::Body,
+    http::{HeaderValue, header},
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FieldError;
+
+use super::validation::FieldErrorResponse;
+
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+const TYPE_PREFIX: &str = "urn:url-shortener:error:";
+
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub kind: &'static str,
+    pub code: &'static str,
+    pub detail: String,
+    pub errors: Vec<FieldError>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldErrorResponse>,
+}
+
+pub async fn render(response: Response) -> Response {
+    let Some(problem) = response.extensions().get::<Problem>().cloned() else {
+        return response;
+    };
+
+    let (mut parts, _) = response.into_parts();
+    let details = ProblemDetails {
+        kind: format!("{TYPE_PREFIX}{}", problem.kind),
+        title: parts
+            .status
+            .canonical_reason()
+            .unwrap_or_default()
+            .to_owned(),
+        status: parts.status.as_u16(),
+        detail: problem.detail,
+        errors: problem.errors.into_iter().map(Into::into).collect(),
+    };
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE),
+    );
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let body = serde_json::to_vec(&details).expect("problem details serialize to JSON");
+
+    Response::from_parts(parts, Body::from(body))
+}
@@ -0,0 +1,69 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, Luma};
+use qrcode::{QrCode, render::svg};
+use serde::Deserialize;
+
+pub const DEFAULT_SIZE: u32 = 256;
+pub const MAX_SIZE: u32 = 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+impl QrFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            QrFormat::Png => mime::IMAGE_PNG.as_ref(),
+            QrFormat::Svg => mime::IMAGE_SVG.as_ref(),
+        }
+    }
+}
+
+pub fn render(data: &str, format: QrFormat, size: u32) -> Result<Vec<u8>, String> {
+    let code = QrCode::new(data.as_bytes()).map_err(|e| e.to_string())?;
+
+    match format {
+        QrFormat::Png => {
+            let image = code.render::<Luma<u8>>().min_dimensions(size, size).build();
+            let mut bytes = Cursor::new(Vec::new());
+            image
+                .write_to(&mut bytes, ImageFormat::Png)
+                .map_err(|e| e.to_string())?;
+
+            Ok(bytes.into_inner())
+        }
+        QrFormat::Svg => Ok(code
+            .render::<svg::Color>()
+            .min_dimensions(size, size)
+            .build()
+            .into_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_png() {
+        // When
+        let bytes = render("https://sho.rt/123", QrFormat::Png, DEFAULT_SIZE).unwrap();
+
+        // Then
+        assert!(bytes.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn renders_svg() {
+        // When
+        let bytes = render("https://sho.rt/123", QrFormat::Svg, DEFAULT_SIZE).unwrap();
+
+        // Then
+        assert!(String::from_utf8(bytes).unwrap().contains("<svg"));
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dashmap::DashMap;
+
+use crate::error::AppError;
+
+struct Window {
+    started_at: Instant,
+    requests: u32,
+}
+
+pub struct RateLimiter {
+    max_requests: u32,
+    window: Duration,
+    clients: DashMap<IpAddr, Window>,
+}
+
+impl RateLimiter {
+    pub fn new(max_requests: u32, window: Duration) -> Self {
+        Self {
+            max_requests,
+            window,
+            clients: DashMap::new(),
+        }
+    }
+
+    fn check(&self, client: IpAddr) -> Result<(), AppError> {
+        let now = Instant::now();
+        let mut window = self.clients.entry(client).or_insert(Window {
+            started_at: now,
+            requests: 0,
+        });
+
+        if now.duration_since(window.started_at) >= self.window {
+            window.started_at = now;
+            window.requests = 0;
+        }
+
+        if window.requests >= self.max_requests {
+            return Err(AppError::RateLimited);
+        }
+        window.requests += 1;
+
+        Ok(())
+    }
+}
+
+pub async fn limit(
+    State(rate_limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    match rate_limiter.check(client) {
+        Ok(()) => next.run(request).await,
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_requests_over_the_limit() {
+        // Given
+        let sut = RateLimiter::new(2, Duration::from_secs(60));
+        let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+        // When
+        let first = sut.check(client);
+        let second = sut.check(client);
+        let third = sut.check(client);
+
+        // Then
+        assert_eq!(first, Ok(()));
+        assert_eq!(second, Ok(()));
+        assert_eq!(third, Err(AppError::RateLimited));
+    }
+
+    #[test]
+    fn limits_clients_independently() {
+        // Given
+        let sut = RateLimiter::new(1, Duration::from_secs(60));
+
+        // When
+        let first = sut.check(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let second = sut.check(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)));
+
+        // Then
+        assert_eq!(first, Ok(()));
+        assert_eq!(second, Ok(()));
+    }
+
+    #[test]
+    fn resets_after_window() {
+        // Given
+        let sut = RateLimiter::new(1, Duration::ZERO);
+        let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+        // When
+        let first = sut.check(client);
+        let second = sut.check(client);
+
+        // Then
+        assert_eq!(first, Ok(()));
+        assert_eq!(second, Ok(()));
+    }
+}
@@ -0,0 +1,48 @@
+use std::{sync::OnceLock, time::Instant};
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+
+pub const URLS_CREATED_TOTAL: &str = "urls_created_total";
+pub const URLS_RESOLVED_TOTAL: &str = "urls_resolved_total";
+const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+
+static METRICS_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+pub fn metrics_handle() -> PrometheusHandle {
+    METRICS_HANDLE
+        .get_or_init(|| {
+            PrometheusBuilder::new()
+                .install_recorder()
+                .expect("failed to install Prometheus recorder")
+        })
+        .clone()
+}
+
+pub async fn track_metrics(request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|matched_path| matched_path.as_str().to_owned())
+        .unwrap_or_else(|| request.uri().path().to_owned());
+
+    let response = next.run(request).await;
+
+    let labels = [
+        ("method", method),
+        ("path", path),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    metrics::counter!(HTTP_REQUESTS_TOTAL, &labels).increment(1);
+    metrics::histogram!(HTTP_REQUEST_DURATION_SECONDS, &labels)
+        .record(start.elapsed().as_secs_f64());
+
+    response
+}
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+
+pub async fn limit(State(timeout): State<Duration>, request: Request, next: Next) -> Response {
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => AppError::Timeout.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{Router, body::Body, http::StatusCode, middleware, routing::get};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use crate::app::query::health_check::{HealthCheckQuery, HealthCheckRepository};
+
+    use super::*;
+
+    struct SlowRepository {
+        delay: Duration,
+    }
+
+    impl HealthCheckRepository for SlowRepository {
+        async fn ping(&self) -> Result<(), AppError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+    }
+
+    async fn ping_with_timeout(delay: Duration, timeout: Duration) -> Response {
+        let query = Arc::new(HealthCheckQuery::new(SlowRepository { delay }));
+        let router = Router::new()
+            .route(
+                "/health",
+                get(move || async move {
+                    query
+                        .execute()
+                        .await
+                        .map(|()| StatusCode::OK)
+                        .map_err(IntoResponse::into_response)
+                }),
+            )
+            .layer(middleware::from_fn_with_state(timeout, limit));
+
+        router
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn slow_repository_times_out() {
+        // When
+        let response = ping_with_timeout(Duration::from_secs(5), Duration::from_millis(10)).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({ "code": "timeout", "message": "Request timed out" })
+        );
+    }
+
+    #[tokio::test]
+    async fn fast_repository_completes() {
+        // When
+        let response = ping_with_timeout(Duration::ZERO, Duration::from_secs(5)).await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::{AppError, FieldError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationCode {
+    NotFound,
+    Expired,
+    Disabled,
+    Gone,
+    InvalidUrl,
+    UnsupportedScheme,
+    UrlTooLong,
+    InvalidAlias,
+    InvalidId,
+    InvalidChecksum,
+    InvalidJson,
+    InvalidTtl,
+    TooLong,
+    ValidationFailed,
+    AliasTaken,
+    ReservedId,
+    BlockedDomain,
+    IdGenerationFailed,
+    RateLimited,
+    Unauthorized,
+    UnsupportedMediaType,
+    Timeout,
+    Internal,
+    Unavailable,
+}
+
+impl ValidationCode {
+    /// The code as it appears on the wire.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ValidationCode::NotFound => "not_found",
+            ValidationCode::Expired => "expired",
+            ValidationCode::Disabled => "disabled",
+            ValidationCode::Gone => "gone",
+            ValidationCode::InvalidUrl => "invalid_url",
+            ValidationCode::UnsupportedScheme => "unsupported_scheme",
+            ValidationCode::UrlTooLong => "url_too_long",
+            ValidationCode::InvalidAlias => "invalid_alias",
+            ValidationCode::InvalidId => "invalid_id",
+            ValidationCode::InvalidChecksum => "invalid_checksum",
+            ValidationCode::InvalidJson => "invalid_json",
+            ValidationCode::InvalidTtl => "invalid_ttl",
+            ValidationCode::TooLong => "too_long",
+            ValidationCode::ValidationFailed => "validation_failed",
+            ValidationCode::AliasTaken => "alias_taken",
+            ValidationCode::ReservedId => "reserved_id",
+            ValidationCode::BlockedDomain => "blocked_domain",
+            ValidationCode::IdGenerationFailed => "id_generation_failed",
+            ValidationCode::RateLimited => "rate_limited",
+            ValidationCode::Unauthorized => "unauthorized",
+            ValidationCode::UnsupportedMediaType => "unsupported_media_type",
+            ValidationCode::Timeout => "timeout",
+            ValidationCode::Internal => "internal",
+            ValidationCode::Unavailable => "unavailable",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ValidationError {
+    pub code: ValidationCode,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(code: ValidationCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<AppError> for ValidationError {
+    fn from(e: AppError) -> Self {
+        let code = match &e {
+            AppError::NotFound => ValidationCode::NotFound,
+            AppError::Expired => ValidationCode::Expired,
+            AppError::Disabled => ValidationCode::Disabled,
+            AppError::Gone => ValidationCode::Gone,
+            AppError::UrlParseError(_) => ValidationCode::InvalidUrl,
+            AppError::UnsupportedScheme => ValidationCode::UnsupportedScheme,
+            AppError::UrlTooLong => ValidationCode::UrlTooLong,
+            AppError::InvalidAlias => ValidationCode::InvalidAlias,
+            AppError::InvalidId => ValidationCode::InvalidId,
+            AppError::InvalidChecksum => ValidationCode::InvalidChecksum,
+            AppError::AliasTaken => ValidationCode::AliasTaken,
+            AppError::ReservedId => ValidationCode::ReservedId,
+            AppError::BlockedDomain => ValidationCode::BlockedDomain,
+            AppError::IdGenerationFailed => ValidationCode::IdGenerationFailed,
+            AppError::RateLimited => ValidationCode::RateLimited,
+            AppError::Unauthorized => ValidationCode::Unauthorized,
+            AppError::UnsupportedMediaType => ValidationCode::UnsupportedMediaType,
+            AppError::Timeout => ValidationCode::Timeout,
+            AppError::Validation(_) => ValidationCode::ValidationFailed,
+            AppError::RepositoryError(e) => {
+                tracing::error!("repository error: {}", e);
+                return Self::new(ValidationCode::Internal, "Internal server error");
+            }
+            AppError::Transient(e) => {
+                tracing::warn!("transient repository error: {}", e);
+                return Self::new(ValidationCode::Unavailable, "Service unavailable");
+            }
+        };
+
+        Self::new(code, e.to_string())
+    }
+}
+
+const MAX_TTL_SECONDS: u64 = 10 * 365 * 24 * 60 * 60;
+
+/// One entry of the `errors` list in a `422 Unprocessable Entity` body.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct FieldErrorResponse {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+impl From<FieldError> for FieldErrorResponse {
+    fn from(e: FieldError) -> Self {
+        Self {
+            field: e.field.to_owned(),
+            code: e.code.to_owned(),
+            message: e.message,
+        }
+    }
+}
+
+/// Collects every field violation of a request instead of stopping at the
+/// first one. Reported as [`AppError::Validation`].
+#[derive(Debug, Default, PartialEq)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    /// Records a violation of `field` unless `valid` holds.
+    pub fn check(
+        &mut self,
+        valid: bool,
+        field: &'static str,
+        code: ValidationCode,
+        message: impl Into<String>,
+    ) -> &mut Self {
+        if !valid {
+            self.0.push(FieldError {
+                field,
+                code: code.as_str(),
+                message: message.into(),
+            });
+        }
+        self
+    }
+
+    /// Bounds `ttl_seconds` so the expiry stays representable.
+    pub fn check_ttl(&mut self, ttl_seconds: Option<u64>) -> &mut Self {
+        self.check(
+            ttl_seconds.is_none_or(|ttl| (1..=MAX_TTL_SECONDS).contains(&ttl)),
+            "ttl_seconds",
+            ValidationCode::InvalidTtl,
+            format!("TTL must be between 1 and {} seconds", MAX_TTL_SECONDS),
+        )
+    }
+
+    pub fn into_result(self) -> Result<(), AppError> {
+        match self.0.is_empty() {
+            true => Ok(()),
+            false => Err(AppError::Validation(self.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code_of(e: AppError) -> serde_json::Value {
+        serde_json::to_value(ValidationError::from(e)).unwrap()["code"].clone()
+    }
+
+    #[test]
+    fn maps_errors_to_stable_codes() {
+        // Then
+        assert_eq!(
+            code_of(AppError::UrlParseError("bad".to_owned())),
+            "invalid_url"
+        );
+        assert_eq!(code_of(AppError::UnsupportedScheme), "unsupported_scheme");
+        assert_eq!(code_of(AppError::UrlTooLong), "url_too_long");
+        assert_eq!(code_of(AppError::InvalidAlias), "invalid_alias");
+        assert_eq!(code_of(AppError::InvalidId), "invalid_id");
+        assert_eq!(code_of(AppError::InvalidChecksum), "invalid_checksum");
+        assert_eq!(code_of(AppError::AliasTaken), "alias_taken");
+        assert_eq!(code_of(AppError::ReservedId), "reserved_id");
+        assert_eq!(code_of(AppError::BlockedDomain), "blocked_domain");
+        assert_eq!(
+            code_of(AppError::IdGenerationFailed),
+            "id_generation_failed"
+        );
+        assert_eq!(code_of(AppError::NotFound), "not_found");
+        assert_eq!(
+            code_of(AppError::Validation(Vec::new())),
+            "validation_failed"
+        );
+    }
+
+    #[test]
+    fn field_codes_match_their_serialized_form() {
+        // Given
+        let codes = [
+            ValidationCode::InvalidUrl,
+            ValidationCode::UrlTooLong,
+            ValidationCode::InvalidAlias,
+            ValidationCode::InvalidTtl,
+            ValidationCode::TooLong,
+            ValidationCode::ValidationFailed,
+        ];
+
+        // Then
+        for code in codes {
+            assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+        }
+    }
+
+    #[test]
+    fn hides_repository_details() {
+        // When
+        let internal = ValidationError::from(AppError::RepositoryError("db down".to_owned()));
+        let transient = ValidationError::from(AppError::Transient("timeout".to_owned()));
+
+        // Then
+        assert_eq!(
+            internal,
+            ValidationError::new(ValidationCode::Internal, "Internal server error")
+        );
+        assert_eq!(
+            transient,
+            ValidationError::new(ValidationCode::Unavailable, "Service unavailable")
+        );
+    }
+
+    #[test]
+    fn field_errors_collect_every_violation() {
+        // Given
+        let mut errors = FieldErrors::default();
+
+        // When
+        errors
+            .check(true, "url", ValidationCode::InvalidUrl, "URL is required")
+            .check(
+                false,
+                "alias",
+                ValidationCode::InvalidAlias,
+                "Invalid alias",
+            )
+            .check(
+                false,
+                "ttl_seconds",
+                ValidationCode::InvalidTtl,
+                "Invalid TTL",
+            );
+        let result = errors.into_result();
+
+        // Then
+        let Err(AppError::Validation(violations)) = result else {
+            panic!("expected a validation error, got {:?}", result);
+        };
+        let fields: Vec<&str> = violations.iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["alias", "ttl_seconds"]);
+        assert_eq!(FieldErrors::default().into_result(), Ok(()));
+    }
+}